@@ -46,16 +46,16 @@ pub fn decode_loan(immutable_hex: &String, mutable_hex: &String) {
 
     let mutable_vec_bytes = decode_hex(mutable_hex).expect("The input string could not be parsed correctly");
     let mutable_bytes = mutable_vec_bytes.as_slice();
-    let (collateral_amount, amount_lent): (Decimal, Decimal) = ScryptoDecoder::new(mutable_bytes).decode_payload(92).unwrap();
+    let (collateral_amount, amount_lent, normalised_debt): (Decimal, Decimal, Decimal) = ScryptoDecoder::new(mutable_bytes).decode_payload(92).unwrap();
 
     let immutable_vec_bytes = decode_hex(immutable_hex).expect("The input string could not be parsed correctly");
     let immutable_bytes = immutable_vec_bytes.as_slice();
-    let (collateral_token_tmp, loan_date, loan_to_value, interest_rate): (ResourceAddress, i64, Decimal, Decimal) = ScryptoDecoder::new(immutable_bytes).decode_payload(92).unwrap();
+    let (collateral_token_tmp, loan_date): (ResourceAddress, i64) = ScryptoDecoder::new(immutable_bytes).decode_payload(92).unwrap();
 
     let bech = Bech32Encoder::new(&NetworkDefinition::nebunet());
     let collateral_token = bech.encode_resource_address_to_string(&collateral_token_tmp);
 
-    println!("{} {} {} {} {} {}", collateral_token, collateral_amount, amount_lent, loan_date, loan_to_value, interest_rate);
+    println!("{} {} {} {} {}", collateral_token, collateral_amount, amount_lent, normalised_debt, loan_date);
 }
 
 pub fn decode_position(immutable_hex: &String, mutable_hex: &String) {
@@ -134,6 +134,7 @@ const UserCtx: React.FC<Props> = (props) => {
                         collateral_token: token,
                         collateral_amount: loansList[i]["collateral_amount"],
                         amount_lent: loansList[i]["amount_lent"],
+                        debt: loansList[i]["debt"],
                         loan_date: loansList[i]["loan_date"],
                         loan_to_value: loansList[i]["loan_to_value"],
                         interest_rate: loansList[i]["interest_rate"],
@@ -71,6 +71,7 @@ function Loan() {
         collateral_token: token_default,
         collateral_amount: 0,
         amount_lent: 0,
+        debt: 0,
         loan_date: 0,
         liquidation_price: 0,
         loan_to_value: 0,
@@ -295,8 +296,6 @@ function Loan() {
         setSwapLoading(false);
     }
 
-    const currentUnix = Date.now() / 1000;
-
     async function sendRepayLoan(account: string, stablecoinAmount: number, loanId: string) {
         setRepayLoading(true);
         const flag = await repayLoan(account, stablecoinAmount.toString(), loanId);
@@ -404,9 +403,9 @@ function Loan() {
                                     <div sx={style.swapInfos}>
                                         <span sx={style.swapInfoMain}><span>Total Locked</span>{currentLoan.collateral_amount} {currentLoan.collateral_token.symb}</span>
                                         <span sx={style.swapInfo}><span>Total Borrowed</span>{currentLoan.amount_lent} {stable.symb}</span>
-                                        <span sx={style.swapInfo}><span>Interest</span>{Math.ceil((currentUnix - currentLoan.loan_date) / 86400) * currentLoan.amount_lent * currentLoan.interest_rate} {stable.symb}</span>
+                                        <span sx={style.swapInfo}><span>Interest</span>{currentLoan.debt - currentLoan.amount_lent} {stable.symb}</span>
                                     </div>
-                                    <button sx={repayLoading ? { ...style.swapButton, ...style.swapButtonLoading } : style.swapButton} onClick={() => repayLoading || !currentLoan.id ? null : sendRepayLoan(user.address, Math.ceil((currentUnix - currentLoan.loan_date) / 86400) * currentLoan.amount_lent * currentLoan.interest_rate + currentLoan.amount_lent, currentLoan.id)} >{repayLoading ? "" : "Repay"}</button>
+                                    <button sx={repayLoading ? { ...style.swapButton, ...style.swapButtonLoading } : style.swapButton} onClick={() => repayLoading || !currentLoan.id ? null : sendRepayLoan(user.address, currentLoan.debt * (1 + currentLoan.interest_rate), currentLoan.id)} >{repayLoading ? "" : "Repay"}</button>
                                 </div>
 
                                 <div sx={style.swapZone}>
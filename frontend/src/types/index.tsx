@@ -60,7 +60,9 @@ type lender = {
 
     liquidation_threshold: number,
 
-    liquidation_penalty: number,
+    liquidator_bonus: number,
+
+    rate_accumulator: number,
 
 }
 
@@ -71,6 +73,8 @@ type loan = {
 
     amount_lent: number,
 
+    debt: number,
+
     loan_date: number,
 
     liquidation_price: number,
@@ -102,9 +106,8 @@ type decoded = {
     collateral_token: string,
     collateral_amount: number,
     amount_lent: number,
-    loan_time: number,
-    loan_to_value: number,
-    interest_rate: number
+    normalised_debt: number,
+    loan_time: number
 }
 
 
@@ -7,7 +7,7 @@ import {
     NonFungibleIdsResponse
 } from "@radixdlt/babylon-gateway-api-sdk";
 import { backend_api_url, issuer_address, loan_address, radix_api_url, token_default } from "../general/constants";
-import { amountToLiquidate } from "./stablecoinMaths";
+import { amountToLiquidate, currentRateAccumulator } from "./stablecoinMaths";
 
 import { decoded, Hexes, lender, loan } from "types";
 import { getToken } from "utils/general/generalApiCalls";
@@ -57,12 +57,13 @@ async function getLenderInformation(lender_address: string) {
     const loan_to_value = data[1];
     const daily_interest_rate = data[2];
     const liquidation_threshold = data[3];
-    const liquidation_penalty = data[4];
+    const liquidator_bonus = data[4];
     const oracle_address = data[5];
+    const rate_accumulator = currentRateAccumulator(data[7], daily_interest_rate, data[8]);
 
     const current_price = await getOraclePrice(oracle_address);
 
-    return { lender_address: lender_address, loan_to_value: loan_to_value, daily_interest_rate: daily_interest_rate, liquidation_threshold: liquidation_threshold, liquidation_penalty: liquidation_penalty, collateral_price: current_price }
+    return { lender_address: lender_address, loan_to_value: loan_to_value, daily_interest_rate: daily_interest_rate, interest_rate: daily_interest_rate, liquidation_threshold: liquidation_threshold, liquidator_bonus: liquidator_bonus, rate_accumulator: rate_accumulator, collateral_price: current_price }
 }
 
 async function getLoansOwnedBy(account: string) {
@@ -166,6 +167,7 @@ async function getLoanInformation(mutable_data: string, immutable_data: string,
         collateral_token: token_default,
         collateral_amount: 0,
         amount_lent: 0,
+        debt: 0,
         loan_date: 0,
         liquidation_price: 0,
         loan_to_value: 0,
@@ -180,6 +182,7 @@ async function getLoanInformation(mutable_data: string, immutable_data: string,
         collateral_token: token_default,
         collateral_amount: 0,
         amount_lent: 0,
+        debt: 0,
         loan_date: 0,
         liquidation_price: 0,
         loan_to_value: 0,
@@ -188,24 +191,26 @@ async function getLoanInformation(mutable_data: string, immutable_data: string,
         id: "-1"
     };
 
-    const collateral_price = lender.collateral_price * data.collateral_amount;
+    // The debt of a loan, interests included, is its normalised debt times the rate accumulator of its lender
+    const debt = data.normalised_debt * lender.rate_accumulator;
 
-    const amount_to_liquidate_promise = amountToLiquidate(data.collateral_amount, collateral_price, data.amount_lent, lender.liquidation_threshold, lender.liquidation_penalty, data.interest_rate, data.loan_time);
+    const amount_to_liquidate_promise = amountToLiquidate(data.collateral_amount, lender.collateral_price, debt, lender.liquidation_threshold, lender.liquidator_bonus);
 
     const token_promise = getToken(data.collateral_token);
 
     const [amount_to_liquidate, token] = await Promise.all([amount_to_liquidate_promise, token_promise])
 
-    let liquidation_price = lender.liquidation_threshold * data.amount_lent / data.collateral_amount
+    let liquidation_price = lender.liquidation_threshold * debt / data.collateral_amount
 
     return {
         collateral_token: token,
         collateral_amount: data.collateral_amount,
         amount_lent: data.amount_lent,
+        debt: debt,
         loan_date: data.loan_time,
         liquidation_price: liquidation_price,
-        loan_to_value: data.loan_to_value,
-        interest_rate: data.interest_rate,
+        loan_to_value: lender.loan_to_value,
+        interest_rate: lender.interest_rate,
         amount_to_liquidate: amount_to_liquidate,
         id: id
     };
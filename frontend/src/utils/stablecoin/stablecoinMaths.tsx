@@ -1,18 +1,22 @@
+function currentRateAccumulator(rate_accumulator: number, daily_interest_rate: number, last_accumulator_update: number): number {
 
+    const current_time = Date.now() / 1000;
+    const days_elapsed = Math.max(current_time - last_accumulator_update, 0) / 86400;
 
-function amountToLiquidate(collateral_amount: number, collateral_price: number, amount_lent: number, liquidation_threshold: number, liquidation_penalty: number, daily_interest_rate: number, loan_date: number): number {
+    // The accumulator of the lender compounds continuously at the daily interest rate
+    return rate_accumulator * Math.exp(daily_interest_rate * days_elapsed);
+}
+
+function amountToLiquidate(collateral_amount: number, collateral_price: number, debt: number, liquidation_threshold: number, liquidator_bonus: number): number {
 
     const collateral_value = collateral_amount * collateral_price;
-    const current_time = new Date();
-    const time_passed = (current_time.getSeconds() - loan_date) / (86400);
-    const loan_value = amount_lent + daily_interest_rate * time_passed;
 
-    if (collateral_value / loan_value > liquidation_threshold) { return -1; }
+    if (collateral_value / debt > liquidation_threshold) { return -1; }
 
     // We add one to make sure to be able to liquidate even if there are computational errors
-    return 1 + (loan_value * liquidation_threshold - collateral_amount * (1 - liquidation_penalty) * collateral_price) / (liquidation_threshold - 1);
+    return 1 + (debt * liquidation_threshold - collateral_amount * (1 - liquidator_bonus) * collateral_price) / (liquidation_threshold - 1);
 
 }
 
 
-export { amountToLiquidate }
\ No newline at end of file
+export { amountToLiquidate, currentRateAccumulator }
\ No newline at end of file
@@ -15,19 +15,48 @@ external_component! {
     RouterLocalComponent {
         fn create_pool(&mut self, token: ResourceAddress, initial_rate: Decimal, min_rate: Decimal, max_rate: Decimal);
         fn claim_protocol_fees(&mut self) -> Vec<Bucket>;
+        fn change_flash_loan_fee(&mut self, token: ResourceAddress, flash_loan_fee: Decimal);
     }
 }
 
 // Import the Issuer component
 external_component! {
     IssuerLocalComponent {
-        fn new_lender(&mut self, collateral_address: ResourceAddress, loan_to_value: Decimal, interest_rate: Decimal, liquidation_threshold: Decimal, liquidation_incentive: Decimal, oracle: ComponentAddress);
-        fn change_lender_parameters(&mut self, lender_collateral: ResourceAddress, loan_to_value: Decimal, interest_rate: Decimal, liquidation_threshold: Decimal, liquidation_incentive: Decimal);
-        fn change_lender_oracle(&mut self, lender_collateral: ResourceAddress, oracle: ComponentAddress);
+        fn new_lender(&mut self, collateral_address: ResourceAddress, loan_to_value: Decimal, interest_rate: Decimal, liquidation_threshold: Decimal, liquidator_bonus: Decimal, protocol_liquidation_share: Decimal, close_factor: Decimal, oracle: ComponentAddress);
+        fn change_lender_parameters(&mut self, lender_collateral: ResourceAddress, loan_to_value: Decimal, interest_rate: Decimal, liquidation_threshold: Decimal, liquidator_bonus: Decimal, protocol_liquidation_share: Decimal, close_factor: Decimal);
+        fn change_lender_oracle(&mut self, lender_collateral: ResourceAddress, oracle: ComponentAddress, use_truncated_twap: bool);
+        fn set_debt_ceiling(&mut self, lender_collateral: ResourceAddress, debt_ceiling: Decimal);
+        fn set_global_debt_ceiling(&mut self, global_debt_ceiling: Decimal);
+        fn set_minimum_debt(&mut self, lender_collateral: ResourceAddress, minimum_debt: Decimal);
+        fn set_surplus_buffer(&mut self, surplus_buffer: Decimal);
+        fn set_system_auction_parameters(&mut self, auction_start_premium: Decimal, auction_duration: i64);
+        fn set_external_minters(&mut self, psms: Vec<ComponentAddress>, minter_registries: Vec<ComponentAddress>);
+        fn set_flash_mint_parameters(&mut self, flash_mint_fee: Decimal, flash_mint_max: Decimal);
+        fn set_flash_loan_fee(&mut self, token: ResourceAddress, flash_loan_fee: Decimal);
+        fn set_redemption_fee(&mut self, redemption_fee: Decimal);
+        fn set_savings_rate(&mut self, savings_rate: Decimal);
+        fn change_lender_liquidation_mode(&mut self, lender_collateral: ResourceAddress, use_auction_liquidation: bool, auction_start_premium: Decimal, auction_duration: i64, kick_incentive: Decimal);
         fn give_tokens(&mut self, tokens: Vec<Bucket>);
     }
 }
 
+// Import the Psm component
+external_component! {
+    PsmLocalComponent {
+        fn set_asset_parameters(&mut self, asset: ResourceAddress, fee_in: Decimal, fee_out: Decimal, cap: Decimal);
+        fn claim_fees(&mut self) -> Bucket;
+    }
+}
+
+// Import the MinterRegistry component
+external_component! {
+    MinterRegistryLocalComponent {
+        fn new_minter(&mut self, name: String, cap: Decimal) -> Bucket;
+        fn change_minter_cap(&mut self, minter_id: NonFungibleLocalId, cap: Decimal);
+        fn revoke_minter(&mut self, minter_id: NonFungibleLocalId);
+    }
+}
+
 #[blueprint]
 mod dao {
     use crate::proposal::ProposalComponent;
@@ -72,7 +101,9 @@ mod dao {
         /// * `iloan_to_value` - Loan to value of the first Lender.
         /// * `interest_rate` - Daily interest rate of a loan.
         /// * `liquidiation_threshold` - Collateralisation ratio from where you can be liquidated.
-        /// * `liquidiation_penalty` - Penalty when you are liquidated.
+        /// * `liquidator_bonus` - Share of the repaid debt paid in collateral to the liquidator.
+        /// * `protocol_liquidation_share` - Share of the claimable interests taken by the protocol on full liquidations.
+        /// * `close_factor` - Maximum share of the debt of a loan repaid by a single partial liquidation.
         /// * `oracle` - Address of the oracle used at first.
         /// * `initial_rate` - Initial rate of the AMM.
         /// * `min_rate` - Minimum rate.
@@ -84,7 +115,9 @@ mod dao {
             loan_to_value: Decimal,
             interest_rate: Decimal,
             liquidation_threshold: Decimal,
-            liquidation_penalty: Decimal,
+            liquidator_bonus: Decimal,
+            protocol_liquidation_share: Decimal,
+            close_factor: Decimal,
             oracle: ComponentAddress,
             initial_rate: Decimal,
             min_rate: Decimal,
@@ -223,7 +256,9 @@ mod dao {
                 loan_to_value,
                 interest_rate,
                 liquidation_threshold,
-                liquidation_penalty,
+                liquidator_bonus,
+                protocol_liquidation_share,
+                close_factor,
                 oracle,
                 initial_rate,
                 min_rate,
@@ -372,6 +407,14 @@ mod dao {
             }
         }
 
+        pub fn claim_psm_fees(&mut self, psm: ComponentAddress) {
+            let fees = self
+                .protocol_admin_badge
+                .authorize(|| PsmLocalComponent::at(psm).claim_fees());
+
+            self.put_in_reserves(fees);
+        }
+
         pub fn execute_proposal(&mut self, proposal_receipt: Bucket) -> Option<Vec<Bucket>> {
             assert!(
                 proposal_receipt.resource_address() == self.proposal_receipt_address,
@@ -437,18 +480,13 @@ mod dao {
                     Some(vec![new_stablecoin_minter])
                 }
 
-                ProposedChange::RemoveIssuingRight(_vault_bytes) => {
-                    /* Not doable yet, but the recalling and burning the minter should be done like that:
-                       self.protocol_admin_badge.authorize(|| {
-                           let resource_manager = borrow_resource_manager!(self.stablecoin_minter.resource_address());
-                           let minter = resource_manager.recall(vault_bytes);
-                           resource_manager.burn(minter);
-
-                       });
-                       self.protocol_admin_badge.authorize(|| {
-                           borrow_resource_manager!(self.stablecoin_minter.resource_address(
-                       });
-                    */
+                ProposedChange::RemoveIssuingRight(minter_registry, minter_id) => {
+                    let mut minter_registry = MinterRegistryLocalComponent::at(minter_registry);
+
+                    self.protocol_admin_badge.authorize(|| {
+                        minter_registry.revoke_minter(minter_id);
+                    });
+
                     None
                 }
 
@@ -470,7 +508,9 @@ mod dao {
                     loan_to_value,
                     interest_rate,
                     liquidation_threshold,
-                    liquidation_penalty,
+                    liquidator_bonus,
+                    protocol_liquidation_share,
+                    close_factor,
                     initial_rate,
                     minimum_rate,
                     maximum_rate,
@@ -481,7 +521,9 @@ mod dao {
                         loan_to_value,
                         interest_rate,
                         liquidation_threshold,
-                        liquidation_penalty,
+                        liquidator_bonus,
+                        protocol_liquidation_share,
+                        close_factor,
                         oracle,
                         initial_rate,
                         minimum_rate,
@@ -495,7 +537,9 @@ mod dao {
                     loan_to_value,
                     interest_rate,
                     liquidation_threshold,
-                    liquidation_penalty,
+                    liquidator_bonus,
+                    protocol_liquidation_share,
+                    close_factor,
                 ) => {
                     let mut issuer = IssuerLocalComponent::at(self.stablecoin_issuer);
 
@@ -505,18 +549,20 @@ mod dao {
                             loan_to_value,
                             interest_rate,
                             liquidation_threshold,
-                            liquidation_penalty,
+                            liquidator_bonus,
+                            protocol_liquidation_share,
+                            close_factor,
                         );
                     });
 
                     None
                 }
 
-                ProposedChange::ChangeLenderOracle(lender, oracle_address) => {
+                ProposedChange::ChangeLenderOracle(lender, oracle_address, use_truncated_twap) => {
                     let mut issuer = IssuerLocalComponent::at(self.stablecoin_issuer);
 
                     self.protocol_admin_badge.authorize(|| {
-                        issuer.change_lender_oracle(lender, oracle_address);
+                        issuer.change_lender_oracle(lender, oracle_address, use_truncated_twap);
                     });
 
                     None
@@ -537,6 +583,172 @@ mod dao {
 
                     None
                 }
+
+                ProposedChange::ChangeLenderLiquidationMode(
+                    lender,
+                    use_auction_liquidation,
+                    auction_start_premium,
+                    auction_duration,
+                    kick_incentive,
+                ) => {
+                    let mut issuer = IssuerLocalComponent::at(self.stablecoin_issuer);
+
+                    self.protocol_admin_badge.authorize(|| {
+                        issuer.change_lender_liquidation_mode(
+                            lender,
+                            use_auction_liquidation,
+                            auction_start_premium,
+                            auction_duration,
+                            kick_incentive,
+                        );
+                    });
+
+                    None
+                }
+
+                ProposedChange::ChangeLenderDebtCeiling(lender, debt_ceiling) => {
+                    let mut issuer = IssuerLocalComponent::at(self.stablecoin_issuer);
+
+                    self.protocol_admin_badge.authorize(|| {
+                        issuer.set_debt_ceiling(lender, debt_ceiling);
+                    });
+
+                    None
+                }
+
+                ProposedChange::ChangeGlobalDebtCeiling(global_debt_ceiling) => {
+                    let mut issuer = IssuerLocalComponent::at(self.stablecoin_issuer);
+
+                    self.protocol_admin_badge.authorize(|| {
+                        issuer.set_global_debt_ceiling(global_debt_ceiling);
+                    });
+
+                    None
+                }
+
+                ProposedChange::ChangeLenderMinimumDebt(lender, minimum_debt) => {
+                    let mut issuer = IssuerLocalComponent::at(self.stablecoin_issuer);
+
+                    self.protocol_admin_badge.authorize(|| {
+                        issuer.set_minimum_debt(lender, minimum_debt);
+                    });
+
+                    None
+                }
+
+                ProposedChange::ChangeSurplusBuffer(surplus_buffer) => {
+                    let mut issuer = IssuerLocalComponent::at(self.stablecoin_issuer);
+
+                    self.protocol_admin_badge.authorize(|| {
+                        issuer.set_surplus_buffer(surplus_buffer);
+                    });
+
+                    None
+                }
+
+                ProposedChange::ChangeFlashMintParameters(flash_mint_fee, flash_mint_max) => {
+                    let mut issuer = IssuerLocalComponent::at(self.stablecoin_issuer);
+
+                    self.protocol_admin_badge.authorize(|| {
+                        issuer.set_flash_mint_parameters(flash_mint_fee, flash_mint_max);
+                    });
+
+                    None
+                }
+
+                ProposedChange::ChangeIssuerFlashLoanFee(token, flash_loan_fee) => {
+                    let mut issuer = IssuerLocalComponent::at(self.stablecoin_issuer);
+
+                    self.protocol_admin_badge.authorize(|| {
+                        issuer.set_flash_loan_fee(token, flash_loan_fee);
+                    });
+
+                    None
+                }
+
+                ProposedChange::ChangePoolFlashLoanFee(token, flash_loan_fee) => {
+                    let mut router = RouterLocalComponent::at(self.dex_router);
+
+                    self.protocol_admin_badge.authorize(|| {
+                        router.change_flash_loan_fee(token, flash_loan_fee);
+                    });
+
+                    None
+                }
+
+                ProposedChange::ChangePsmAssetParameters(psm, asset, fee_in, fee_out, cap) => {
+                    let mut psm = PsmLocalComponent::at(psm);
+
+                    self.protocol_admin_badge.authorize(|| {
+                        psm.set_asset_parameters(asset, fee_in, fee_out, cap);
+                    });
+
+                    None
+                }
+
+                ProposedChange::ChangeRedemptionFee(redemption_fee) => {
+                    let mut issuer = IssuerLocalComponent::at(self.stablecoin_issuer);
+
+                    self.protocol_admin_badge.authorize(|| {
+                        issuer.set_redemption_fee(redemption_fee);
+                    });
+
+                    None
+                }
+
+                ProposedChange::ChangeSavingsRate(savings_rate) => {
+                    let mut issuer = IssuerLocalComponent::at(self.stablecoin_issuer);
+
+                    self.protocol_admin_badge.authorize(|| {
+                        issuer.set_savings_rate(savings_rate);
+                    });
+
+                    None
+                }
+
+                ProposedChange::RegisterMinter(minter_registry, name, cap) => {
+                    let mut minter_registry = MinterRegistryLocalComponent::at(minter_registry);
+
+                    let minter_badge = self
+                        .protocol_admin_badge
+                        .authorize(|| minter_registry.new_minter(name, cap));
+
+                    Some(vec![minter_badge])
+                }
+
+                ProposedChange::ChangeMinterCap(minter_registry, minter_id, cap) => {
+                    let mut minter_registry = MinterRegistryLocalComponent::at(minter_registry);
+
+                    self.protocol_admin_badge.authorize(|| {
+                        minter_registry.change_minter_cap(minter_id, cap);
+                    });
+
+                    None
+                }
+
+                ProposedChange::ChangeSystemAuctionParameters(
+                    auction_start_premium,
+                    auction_duration,
+                ) => {
+                    let mut issuer = IssuerLocalComponent::at(self.stablecoin_issuer);
+
+                    self.protocol_admin_badge.authorize(|| {
+                        issuer
+                            .set_system_auction_parameters(auction_start_premium, auction_duration);
+                    });
+
+                    None
+                }
+
+                ProposedChange::ChangeExternalMinters(psms, minter_registries) => {
+                    let mut issuer = IssuerLocalComponent::at(self.stablecoin_issuer);
+
+                    self.protocol_admin_badge.authorize(|| {
+                        issuer.set_external_minters(psms, minter_registries);
+                    });
+
+                    None
+                }
             }
         }
 
@@ -547,7 +759,9 @@ mod dao {
             loan_to_value: Decimal,
             interest_rate: Decimal,
             liquidation_threshold: Decimal,
-            liquidation_penalty: Decimal,
+            liquidator_bonus: Decimal,
+            protocol_liquidation_share: Decimal,
+            close_factor: Decimal,
             oracle: ComponentAddress,
             initial_rate: Decimal,
             min_rate: Decimal,
@@ -563,7 +777,9 @@ mod dao {
                     loan_to_value,
                     interest_rate,
                     liquidation_threshold,
-                    liquidation_penalty,
+                    liquidator_bonus,
+                    protocol_liquidation_share,
+                    close_factor,
                     oracle,
                 );
             });
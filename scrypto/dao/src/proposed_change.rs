@@ -11,13 +11,16 @@ pub enum ProposedChange {
     /// Grants a stablecoin minting badge
     GrantIssuingRight,
 
-    /// Recalls a stablecoin minting badge
-    RemoveIssuingRight(Vec<u8>),
+    /// Revokes a stablecoin minter of a minter registry
+    RemoveIssuingRight(ComponentAddress, NonFungibleLocalId),
 
     /// Allows claiming of a certain amount of resource from the dao reserves
     AllowClaim(Vec<(ResourceAddress, Decimal)>),
 
-    /// Adds a new token as possible collateral. Taking this decision will also create a pool for the given token
+    /// Adds a new token as possible collateral with its loan to value, interest rate, liquidation
+    /// threshold, liquidator bonus, protocol liquidation share and close factor. Taking this
+    /// decision will also create a pool for the given token with its initial, minimum and maximum
+    /// rates
     AddNewCollateralToken(
         ResourceAddress,
         Decimal,
@@ -27,15 +30,74 @@ pub enum ProposedChange {
         Decimal,
         Decimal,
         Decimal,
+        Decimal,
+        Decimal,
         ComponentAddress,
     ),
 
-    /// Changes the parameters of a given stablecoin lender
-    ChangeLenderParameters(ResourceAddress, Decimal, Decimal, Decimal, Decimal),
+    /// Changes the loan to value, the interest rate, the liquidation threshold, the liquidator
+    /// bonus, the protocol liquidation share and the close factor of a given stablecoin lender
+    ChangeLenderParameters(
+        ResourceAddress,
+        Decimal,
+        Decimal,
+        Decimal,
+        Decimal,
+        Decimal,
+        Decimal,
+    ),
 
-    /// Changes the oracle of a given stablecoin lender
-    ChangeLenderOracle(ResourceAddress, ComponentAddress),
+    /// Changes the oracle of a given stablecoin lender and whether it should use the truncated TWAP
+    ChangeLenderOracle(ResourceAddress, ComponentAddress, bool),
 
     /// Adds given tokens to the stablecoin issuer reserves
     AddTokensToIssuerReserves(Vec<(ResourceAddress, Decimal)>),
+
+    /// Changes whether a given stablecoin lender liquidates loans through auctions, the auction
+    /// start premium, the auction duration and the kick incentive
+    ChangeLenderLiquidationMode(ResourceAddress, bool, Decimal, i64, Decimal),
+
+    /// Changes the maximum amount of stablecoins that can be lent against a given collateral token
+    ChangeLenderDebtCeiling(ResourceAddress, Decimal),
+
+    /// Changes the maximum amount of stablecoins that can be lent against all collateral tokens
+    ChangeGlobalDebtCeiling(Decimal),
+
+    /// Changes the minimum amount of stablecoins that a loan of a given stablecoin lender should owe
+    ChangeLenderMinimumDebt(ResourceAddress, Decimal),
+
+    /// Changes the amount of stablecoin reserves above which the surplus can be auctioned
+    ChangeSurplusBuffer(Decimal),
+
+    /// Changes the flash mint fee and the maximum amount of stablecoins that can be flash minted
+    ChangeFlashMintParameters(Decimal, Decimal),
+
+    /// Changes the fee of the flash loans of a given token of the stablecoin issuer reserves
+    ChangeIssuerFlashLoanFee(ResourceAddress, Decimal),
+
+    /// Changes the fee of the flash loans of the DEX pool trading a given token
+    ChangePoolFlashLoanFee(ResourceAddress, Decimal),
+
+    /// Whitelists an asset of a peg stability module or changes its fee in, fee out and cap
+    ChangePsmAssetParameters(ComponentAddress, ResourceAddress, Decimal, Decimal, Decimal),
+
+    /// Changes the share of the collateral taken by a stablecoin redemption paid as fee
+    ChangeRedemptionFee(Decimal),
+
+    /// Changes the daily savings rate paid to the depositors of the savings vault
+    ChangeSavingsRate(Decimal),
+
+    /// Registers a new stablecoin minter with a given name and cap in a minter registry
+    RegisterMinter(ComponentAddress, String, Decimal),
+
+    /// Changes the cap of a stablecoin minter of a minter registry
+    ChangeMinterCap(ComponentAddress, NonFungibleLocalId, Decimal),
+
+    /// Changes the start premium and the duration of the debt and surplus auctions of the
+    /// stablecoin issuer
+    ChangeSystemAuctionParameters(Decimal, i64),
+
+    /// Sets the peg stability modules and the minter registries whose outstanding supply is
+    /// reported by the stablecoin issuer
+    ChangeExternalMinters(Vec<ComponentAddress>, Vec<ComponentAddress>),
 }
@@ -11,3 +11,11 @@ pub const PROTOCOL_FEE: Decimal = Decimal(BnumI256::from_digits([500000000000000
 
 /// Constant equal to 0.997 that represents the real amount of tokens traded (after fees)
 pub const RATIO_TRADED: Decimal = Decimal(BnumI256::from_digits([997000000000000000, 0, 0, 0]));
+
+/// Constant equal to 0.0005 that represents the default fee of a flash loan, paid to liquidity
+/// providers
+pub const DEFAULT_FLASH_LOAN_FEE: Decimal =
+    Decimal(BnumI256::from_digits([500000000000000, 0, 0, 0]));
+
+/// Default maximum number of steps that the truncated oracle series can move per elapsed minute
+pub const MAX_STEP_CHANGE_PER_MINUTE: u16 = 100;
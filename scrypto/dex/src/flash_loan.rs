@@ -0,0 +1,42 @@
+//! Definition of [`FlashLoan`]
+
+use scrypto::prelude::*;
+
+#[derive(
+    NonFungibleData, ScryptoCategorize, LegacyDescribe, ScryptoEncode, ScryptoDecode, Clone,
+)]
+pub struct FlashLoan {
+    /// Other token traded by the pool that lent the tokens
+    pub pool_token: ResourceAddress,
+
+    /// Step of the pool that lent the tokens
+    pub step: u16,
+
+    /// Token lent
+    pub token: ResourceAddress,
+
+    /// Amount of tokens lent
+    pub amount: Decimal,
+
+    /// Fee to pay on top of the amount of tokens lent
+    pub fee: Decimal,
+}
+
+impl FlashLoan {
+    /// Returns a new [`FlashLoan`].
+    pub fn from(
+        pool_token: ResourceAddress,
+        step: u16,
+        token: ResourceAddress,
+        amount: Decimal,
+        fee: Decimal,
+    ) -> Self {
+        Self {
+            pool_token,
+            step,
+            token,
+            amount,
+            fee,
+        }
+    }
+}
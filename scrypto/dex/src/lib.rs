@@ -4,9 +4,12 @@
 
 pub mod constants;
 pub mod decimal_maths;
+pub mod flash_loan;
 pub mod observation_array;
 pub mod oracle;
 pub mod pool;
+pub mod pool_statistics;
 pub mod pool_step;
 pub mod position;
 pub mod router;
+pub mod timestamped_array;
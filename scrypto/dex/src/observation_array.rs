@@ -1,16 +1,20 @@
 //! Circular Array used by the Time Weighted Average Oracle
 
+use crate::timestamped_array::{Timestamped, TimestampedArray};
 use scrypto::prelude::*;
 
-const ARRAY_LENGTH: u16 = 65535;
+/// Default maximum number of observations stored by an [`ObservationArray`]
+pub const ARRAY_LENGTH: u16 = 65535;
 
-#[derive(ScryptoCategorize, LegacyDescribe, ScryptoEncode, ScryptoDecode, Clone)]
+#[derive(
+    ScryptoCategorize, LegacyDescribe, ScryptoEncode, ScryptoDecode, Clone, Debug, PartialEq,
+)]
 pub struct Observation {
     /// Timestamp of the observation: precise up to a minute
-    timestamp: i64,
+    pub timestamp: i64,
 
     /// Step of the observed pool
-    step: u16,
+    pub step: u16,
 }
 
 impl Observation {
@@ -19,52 +23,88 @@ impl Observation {
     }
 }
 
+impl Timestamped for Observation {
+    const NAME: &'static str = "Observations";
+
+    fn timestamp(&self) -> i64 {
+        self.timestamp
+    }
+}
+
 #[derive(ScryptoCategorize, LegacyDescribe, ScryptoEncode, ScryptoDecode, Clone)]
 pub struct ObservationArray {
-    /// Index of the start of the array
-    start: u16,
-
     /// Circular Array that stores observations
-    data: Vec<Observation>,
+    observations: TimestampedArray<Observation>,
 }
 
 impl ObservationArray {
     pub fn new() -> Self {
+        Self::with_capacity(ARRAY_LENGTH)
+    }
+
+    /// Returns an empty array storing at most `capacity` observations
+    ///
+    /// # Arguments
+    /// * `capacity` - maximum number of observations stored by the array
+    pub fn with_capacity(capacity: u16) -> Self {
         Self {
-            start: 0,
-            data: Vec::new(),
+            observations: TimestampedArray::with_capacity(capacity),
         }
     }
 
-    /// Pushes a new observation in the Array
+    /// Returns the maximum number of observations stored by the array
+    pub fn capacity(&self) -> u16 {
+        self.observations.capacity()
+    }
+
+    /// Changes the capacity of the array. Every observation is kept if the new capacity is large
+    /// enough, otherwise only the most recent ones are.
+    ///
+    /// # Arguments
+    /// * `capacity` - new maximum number of observations stored by the array
+    pub fn resize(&mut self, capacity: u16) {
+        self.observations.resize(capacity);
+    }
+
+    /// Returns the number of observations stored in the array
+    pub fn len(&self) -> usize {
+        self.observations.len()
+    }
+
+    /// Returns whether the array does not contain any observation
+    pub fn is_empty(&self) -> bool {
+        self.observations.is_empty()
+    }
+
+    /// Pushes a new observation in the Array, overwriting the oldest one if the array is full.
+    /// If the last observation was made at the same timestamp, it is replaced by the new one.
     ///
     /// # Arguments
     /// * `timestamp` - timestamp of the observation
     /// * `step` - step of the observation
     pub fn push(&mut self, timestamp: i64, step: u16) {
-        let new_obs = Observation::from(timestamp, step);
-
-        if self.data.len() < ARRAY_LENGTH as usize {
-            self.data.push(new_obs);
-        } else {
-            self.data[self.start as usize] = new_obs;
-            self.start += 1;
-        }
+        self.observations.push(Observation::from(timestamp, step));
     }
 
-    /// Gets an observation at a given index
+    /// Gets an observation at a given index, the index 0 being the oldest observation
     ///
     /// # Arguments
     /// * `index` - index of the observation to get
-    pub fn get(&self, index: u16) -> Option<&Observation> {
-        if index as usize >= self.data.len() {
-            return None;
-        }
-        let index = ((index + self.start) as usize) % (ARRAY_LENGTH as usize);
-        self.data.get(index)
+    pub fn get(&self, index: usize) -> Option<&Observation> {
+        self.observations.get(index)
+    }
+
+    /// Returns the most recent observation
+    pub fn last(&self) -> Option<&Observation> {
+        self.observations.last()
     }
 
-    /// Returns the time weighted average step since a given timestamp
+    /// Returns the time weighted average step since a given timestamp or `None` if no
+    /// observation was made.
+    ///
+    /// Each observation is considered to hold until the next one. If the first stored
+    /// observation was made after `timestamp`, the average is computed from this first
+    /// observation.
     ///
     /// # Arguments
     /// * `timestamp` - timestamp to compute the average since
@@ -73,55 +113,33 @@ impl ObservationArray {
         &self,
         timestamp: i64,
         current_timestamp: i64,
-    ) -> u16 {
-        // Get index of the first observation made at timestamp
-        let start_index = self.get_first_observation_index_since(timestamp);
-        let mut total = 0;
-        let stop_index = if self.data.len() < ARRAY_LENGTH as usize {
-            self.data.len()
-        } else {
-            self.start as usize + ARRAY_LENGTH as usize
-        };
-
-        for i in start_index..stop_index - 1 {
-            let true_i = i % (ARRAY_LENGTH as usize);
-            let next_true_i = (i + 1) % (ARRAY_LENGTH as usize);
-            let obs_1 = self.data.get(true_i).unwrap();
-            let obs_2 = self.data.get(next_true_i).unwrap();
-            total += (obs_2.timestamp - obs_1.timestamp) * (obs_1.step as i64);
+    ) -> Option<u16> {
+        if self.is_empty() {
+            return None;
         }
-        let last_obs = self
-            .data
-            .get((stop_index - 1) % (ARRAY_LENGTH as usize))
-            .unwrap();
-        total += (current_timestamp - last_obs.timestamp) * (last_obs.step as i64);
-        (total / (current_timestamp - timestamp)) as u16
-    }
 
-    fn get_first_observation_index_since(&self, timestamp: i64) -> usize {
-        let mut start_index = self.start as usize;
+        // Get index of the observation that held at timestamp
+        let start_index = self.observations.index_at(timestamp);
+        let begin = timestamp.max(self.get(start_index).unwrap().timestamp);
 
-        if timestamp < self.data[start_index].timestamp {
-            return start_index;
+        if current_timestamp <= begin {
+            return Some(self.last().unwrap().step);
         }
 
-        let mut stop_index: usize = self.start as usize + self.data.len();
-        loop {
-            if start_index == stop_index {
-                break;
+        let mut total: i64 = 0;
+        for i in start_index..self.len() {
+            let obs = self.get(i).unwrap();
+            let from = obs.timestamp.max(begin);
+            let to = match self.get(i + 1) {
+                Some(next_obs) => next_obs.timestamp.min(current_timestamp),
+                None => current_timestamp,
             };
-            let check_index_raw = stop_index - start_index;
-            let check_index = check_index_raw % (ARRAY_LENGTH as usize);
-            let found_timestamp = self.data.get(check_index).unwrap().timestamp;
-            if found_timestamp > timestamp {
-                stop_index = check_index_raw;
-            } else if found_timestamp < timestamp {
-                start_index = check_index_raw;
-            } else {
-                break;
+
+            if to > from {
+                total += (to - from) * (obs.step as i64);
             }
         }
 
-        stop_index
+        Some((total / (current_timestamp - begin)) as u16)
     }
 }
@@ -1,40 +1,217 @@
 //! TWAP oracle blueprint for a Pool
+//!
+//! The oracle records two series of observations: the raw steps of the pool and a truncated
+//! series in which the recorded step cannot move by more than `max_step_change_per_minute` steps
+//! per elapsed minute. The truncated series is more robust to single block price manipulations.
+//!
+//! The oracle also keeps snapshots of cumulative pool statistics (traded volumes, fees and
+//! liquidity of the current step) so that they can be queried over any window of time.
 
+use crate::constants::NB_STEP;
+use crate::observation_array::Observation;
 use scrypto::blueprint;
 
-pub const TIME_BETWEEN_OBSERVATIONS: i64 = 20;
+/// Default minimum number of seconds between two observations. Timestamps are precise up to a
+/// minute, so observations are spaced by a multiple of 60 seconds.
+pub const TIME_BETWEEN_OBSERVATIONS: i64 = 60;
+
+/// Caps the change of step since the last truncated observation to `max_step_change_per_minute`
+/// steps per elapsed minute, counting at least one minute.
+///
+/// # Arguments
+/// * `last_observation` - last observation of the truncated series, if any
+/// * `timestamp` - timestamp of the new observation
+/// * `step` - current step of the pool
+/// * `max_step_change_per_minute` - maximum number of steps the series can move per minute
+pub fn truncate_step(
+    last_observation: Option<&Observation>,
+    timestamp: i64,
+    step: u16,
+    max_step_change_per_minute: u16,
+) -> u16 {
+    match last_observation {
+        None => step,
+        Some(last_obs) => {
+            let elapsed_minutes = ((timestamp - last_obs.timestamp) / 60).max(1);
+            let max_change = (max_step_change_per_minute as i64)
+                .saturating_mul(elapsed_minutes)
+                .min(NB_STEP as i64);
+
+            let lowest_step = (last_obs.step as i64 - max_change).max(0);
+            let highest_step = (last_obs.step as i64 + max_change).min(NB_STEP as i64);
+
+            (step as i64).clamp(lowest_step, highest_step) as u16
+        }
+    }
+}
 
 #[blueprint]
 mod oracle {
+    use crate::constants::MAX_STEP_CHANGE_PER_MINUTE;
     use crate::observation_array::ObservationArray;
+    use crate::pool_statistics::{PoolStatistics, StatisticsArray};
 
     pub struct Oracle {
+        /// Observations of the pool step
         observations: ObservationArray,
+
+        /// Observations of the pool step, truncated to `max_step_change_per_minute`
+        truncated_observations: ObservationArray,
+
+        /// Time of the last observation
         last_observation_time: i64,
+
+        /// Minimum number of seconds between two observations
+        min_observation_spacing: i64,
+
+        /// Maximum number of steps the truncated series can move per elapsed minute
+        max_step_change_per_minute: u16,
+
+        /// Snapshots of the cumulative statistics of the pool
+        statistics: StatisticsArray,
     }
 
     impl Oracle {
         pub fn new() -> OracleComponent {
+            let current_time = Clock::current_time(TimePrecision::Minute).seconds_since_unix_epoch;
+            let mut statistics = StatisticsArray::new();
+            statistics.push(PoolStatistics::new(current_time));
+
             Self {
                 observations: ObservationArray::new(),
+                truncated_observations: ObservationArray::new(),
                 last_observation_time: 0,
+                min_observation_spacing: TIME_BETWEEN_OBSERVATIONS,
+                max_step_change_per_minute: MAX_STEP_CHANGE_PER_MINUTE,
+                statistics,
             }
             .instantiate()
         }
 
+        /// Records a new observation of the pool step in both series
+        ///
+        /// # Arguments
+        /// * `timestamp` - timestamp of the observation
+        /// * `step` - current step of the pool
         pub fn new_observation(&mut self, timestamp: i64, step: u16) {
-            if timestamp - self.last_observation_time > TIME_BETWEEN_OBSERVATIONS {
+            if timestamp - self.last_observation_time >= self.min_observation_spacing {
+                let truncated_step = truncate_step(
+                    self.truncated_observations.last(),
+                    timestamp,
+                    step,
+                    self.max_step_change_per_minute,
+                );
+
                 self.observations.push(timestamp, step);
+                self.truncated_observations.push(timestamp, truncated_step);
+                self.last_observation_time = timestamp;
             }
         }
 
+        /// Returns the time weighted average of the raw step series
         pub fn get_time_weighted_average_step_since(
             &self,
             timestamp: i64,
             current_timestamp: i64,
-        ) -> u16 {
+        ) -> Option<u16> {
             self.observations
                 .get_time_weighted_average_step_since(timestamp, current_timestamp)
         }
+
+        /// Returns the time weighted average of the truncated step series
+        pub fn get_truncated_time_weighted_average_step_since(
+            &self,
+            timestamp: i64,
+            current_timestamp: i64,
+        ) -> Option<u16> {
+            self.truncated_observations
+                .get_time_weighted_average_step_since(timestamp, current_timestamp)
+        }
+
+        /// Changes the minimum spacing between two observations and the maximum step change per
+        /// minute of the truncated series
+        ///
+        /// # Arguments
+        /// * `min_observation_spacing` - minimum number of seconds between two observations
+        /// * `max_step_change_per_minute` - maximum number of steps the truncated series can move
+        /// per elapsed minute
+        pub fn change_parameters(
+            &mut self,
+            min_observation_spacing: i64,
+            max_step_change_per_minute: u16,
+        ) {
+            assert!(
+                min_observation_spacing >= 0,
+                "The minimum observation spacing should be non negative"
+            );
+            assert!(
+                max_step_change_per_minute > 0,
+                "The maximum step change per minute should be positive"
+            );
+
+            self.min_observation_spacing = min_observation_spacing;
+            self.max_step_change_per_minute = max_step_change_per_minute;
+        }
+
+        /// Changes the number of observations and statistics snapshots stored by the oracle. The
+        /// whole history is kept when growing the buffers and the most recent entries are kept
+        /// when shrinking them.
+        ///
+        /// # Arguments
+        /// * `capacity` - new maximum number of entries stored by each buffer
+        pub fn resize(&mut self, capacity: u16) {
+            self.observations.resize(capacity);
+            self.truncated_observations.resize(capacity);
+            self.statistics.resize(capacity);
+        }
+
+        /// Returns the minimum observation spacing, the maximum step change per minute and the
+        /// capacity of the oracle
+        pub fn get_parameters(&self) -> (i64, u16, u16) {
+            (
+                self.min_observation_spacing,
+                self.max_step_change_per_minute,
+                self.observations.capacity(),
+            )
+        }
+
+        /// Records a swap or a liquidity change in the cumulative statistics of the pool
+        ///
+        /// # Arguments
+        /// * `timestamp` - timestamp of the update
+        /// * `stable_volume` - amount of stablecoins traded
+        /// * `other_volume` - amount of other tokens traded
+        /// * `stable_fees` - fees paid in stablecoins
+        /// * `other_fees` - fees paid in other tokens
+        /// * `liquidity` - liquidity of the current step after the update
+        pub fn record_statistics(
+            &mut self,
+            timestamp: i64,
+            stable_volume: Decimal,
+            other_volume: Decimal,
+            stable_fees: Decimal,
+            other_fees: Decimal,
+            liquidity: Decimal,
+        ) {
+            let mut statistics = self.statistics.last().unwrap().accumulated_to(timestamp);
+            statistics.stable_volume += stable_volume;
+            statistics.other_volume += other_volume;
+            statistics.stable_fees += stable_fees;
+            statistics.other_fees += other_fees;
+            statistics.liquidity = liquidity;
+
+            self.statistics.push(statistics);
+        }
+
+        /// Returns the stablecoin volume, the other token volume, the stablecoin fees, the other
+        /// token fees and the time-weighted liquidity of the current step since a given timestamp
+        pub fn get_statistics_since(
+            &self,
+            timestamp: i64,
+            current_timestamp: i64,
+        ) -> (Decimal, Decimal, Decimal, Decimal, Decimal) {
+            self.statistics
+                .get_statistics_since(timestamp, current_timestamp)
+        }
     }
 }
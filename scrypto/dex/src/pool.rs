@@ -19,6 +19,16 @@
 //! - [claim_fees](PoolComponent::claim_fees) - Claims fees associated to a [`Position`].
 //! - [swap](PoolComponent::swap) - Swaps stablecoins/other tokens for other tokens/stablecoins.
 //! - [claim_protocol_fees](PoolComponent::claim_protocol_fees) - Claims protocol fees.
+//! - [flash_loan](PoolComponent::flash_loan) - Lends tokens of the liquidity of the current step.
+//! - [repay_flash_loan](PoolComponent::repay_flash_loan) - Gives back flash loaned tokens to the step that lent them.
+//! - [change_flash_loan_fee](PoolComponent::change_flash_loan_fee) - Changes the fee of flash loans.
+//! - [new_observation](PoolComponent::new_observation) - Makes a new oracle observation.
+//! - [change_oracle_parameters](PoolComponent::change_oracle_parameters) - Changes the observation spacing and the maximum step change of the oracle.
+//! - [resize_oracle](PoolComponent::resize_oracle) - Changes the number of entries stored by the oracle.
+//! - [get_oracle_parameters](PoolComponent::get_oracle_parameters) - Returns the parameters of the oracle.
+//! - [get_twap_since](PoolComponent::get_twap_since) - Returns the time-weighted average price since a given time.
+//! - [get_truncated_twap_since](PoolComponent::get_truncated_twap_since) - Returns the truncated time-weighted average price since a given time.
+//! - [get_statistics_since](PoolComponent::get_statistics_since) - Returns the volumes, fees and time-weighted liquidity of the pool since a given time.
 //! - [get_state](PoolComponent::get_state) - Returns the full state of the blueprint.
 //! - [rate_at_step](PoolComponent::rate_at_step) - Returns the exchange rate associated to a given step.
 //! - [step_at_rate](PoolComponent::step_at_rate) - Returns the step associated to a given exchange rate.
@@ -27,7 +37,7 @@ use scrypto::blueprint;
 
 #[blueprint]
 mod pool {
-    use crate::constants::NB_STEP;
+    use crate::constants::{DEFAULT_FLASH_LOAN_FEE, LP_FEE, NB_STEP, PROTOCOL_FEE};
     use crate::decimal_maths::{ln, pow};
     use crate::oracle::OracleComponent;
     use crate::pool_step::PoolStepComponent;
@@ -54,6 +64,13 @@ mod pool {
 
         /// Price oracle
         oracle: OracleComponent,
+
+        /// Share of a flash loan paid as fee to the liquidity providers
+        flash_loan_fee: Decimal,
+
+        /// Whether a flash loan of the pool has not been repaid yet, in which case the steps
+        /// cannot be traded or changed
+        is_locked: bool,
     }
 
     impl Pool {
@@ -108,6 +125,8 @@ mod pool {
                 stable_protocol_fees: Vault::new(stable),
                 other_protocol_fees: Vault::new(other),
                 oracle: OracleComponent::new(),
+                flash_loan_fee: DEFAULT_FLASH_LOAN_FEE,
+                is_locked: false,
             }
             .instantiate();
 
@@ -146,6 +165,8 @@ mod pool {
             step: u16,
             mut position: Position,
         ) -> (Bucket, Bucket, Position) {
+            self.assert_unlocked();
+
             let step_position = position.get_step(step);
             let (bucket_stable, bucket_other) =
                 if bucket_a.resource_address() == self.stable_protocol_fees.resource_address() {
@@ -178,6 +199,10 @@ mod pool {
             );
             position.insert_step(step, new_step);
 
+            if step == self.current_step {
+                self.record_statistics(Decimal::ZERO, Decimal::ZERO, Decimal::ZERO, Decimal::ZERO);
+            }
+
             (stable_return, other_return, position)
         }
 
@@ -225,6 +250,8 @@ mod pool {
             step: u16,
             mut position: Position,
         ) -> (Bucket, Bucket, Position) {
+            self.assert_unlocked();
+
             let step_position = position.remove_step(step);
             let mut bucket_stable = Bucket::new(self.stable_protocol_fees.resource_address());
             let mut bucket_other = Bucket::new(position.token);
@@ -234,6 +261,15 @@ mod pool {
                 let (tmp_stable, tmp_other) = pool_step.remove_liquidity(step_position);
                 bucket_stable.put(tmp_stable);
                 bucket_other.put(tmp_other);
+
+                if step == self.current_step {
+                    self.record_statistics(
+                        Decimal::ZERO,
+                        Decimal::ZERO,
+                        Decimal::ZERO,
+                        Decimal::ZERO,
+                    );
+                }
             }
             (bucket_stable, bucket_other, position)
         }
@@ -282,15 +318,23 @@ mod pool {
         /// # Arguments
         /// * `position` - [`Position`] of the user
         pub fn remove_all_liquidity(&mut self, position: Position) -> (Bucket, Bucket) {
+            self.assert_unlocked();
+
             let step_positions = position.step_positions;
             let mut bucket_stable = Bucket::new(self.stable_protocol_fees.resource_address());
             let mut bucket_other = Bucket::new(position.token);
 
+            let mut in_range_changed = false;
             for (step, step_position) in step_positions {
                 let pool_step = self.steps.get(&step).unwrap();
                 let (tmp_stable, tmp_other) = pool_step.remove_liquidity(step_position);
                 bucket_stable.put(tmp_stable);
                 bucket_other.put(tmp_other);
+                in_range_changed = in_range_changed || step == self.current_step;
+            }
+
+            if in_range_changed {
+                self.record_statistics(Decimal::ZERO, Decimal::ZERO, Decimal::ZERO, Decimal::ZERO);
             }
             (bucket_stable, bucket_other)
         }
@@ -320,6 +364,11 @@ mod pool {
         /// # Arguments
         /// * `input_bucket` - bucket containing stablecoins/other tokens
         pub fn swap(&mut self, input_bucket: Bucket) -> (Bucket, Bucket) {
+            self.assert_unlocked();
+
+            // Record the price before it is moved by the swap
+            self.new_observation();
+
             if input_bucket.resource_address() == self.stable_protocol_fees.resource_address() {
                 self.swap_for_other(input_bucket)
             } else {
@@ -335,6 +384,7 @@ mod pool {
             // Input bucket has stable tokens
 
             let mut other_ret = Bucket::new(self.other_protocol_fees.resource_address());
+            let input_amount = input_bucket.amount();
             let mut stable_ret = Bucket::from(input_bucket);
 
             loop {
@@ -359,6 +409,14 @@ mod pool {
                 self.current_step += 1;
             }
 
+            let stable_traded = input_amount - stable_ret.amount();
+            self.record_statistics(
+                stable_traded,
+                other_ret.amount(),
+                stable_traded * (LP_FEE + PROTOCOL_FEE),
+                Decimal::ZERO,
+            );
+
             (stable_ret, other_ret)
         }
 
@@ -369,6 +427,7 @@ mod pool {
         fn swap_for_stable(&mut self, input_bucket: Bucket) -> (Bucket, Bucket) {
             // Input bucket has other tokens
 
+            let input_amount = input_bucket.amount();
             let mut other_ret = Bucket::from(input_bucket);
             let mut stable_ret = Bucket::new(self.stable_protocol_fees.resource_address());
 
@@ -394,6 +453,14 @@ mod pool {
                 }
             }
 
+            let other_traded = input_amount - other_ret.amount();
+            self.record_statistics(
+                stable_ret.amount(),
+                other_traded,
+                Decimal::ZERO,
+                other_traded * (LP_FEE + PROTOCOL_FEE),
+            );
+
             (stable_ret, other_ret)
         }
 
@@ -405,19 +472,143 @@ mod pool {
             )
         }
 
-        /// Makes a new oracle observations if last observations happened more than 20 seconds ago
+        /// Lends tokens of the liquidity of the current step and returns them with the lending
+        /// step and the fee to pay. The pool is locked until the loan is repaid.
+        ///
+        /// # Arguments
+        /// * `token` - ResourceAddress of the token to lend
+        /// * `amount` - Amount of tokens to lend
+        pub fn flash_loan(
+            &mut self,
+            token: ResourceAddress,
+            amount: Decimal,
+        ) -> (Bucket, u16, Decimal) {
+            self.assert_unlocked();
+            assert!(
+                amount.is_positive(),
+                "The amount to lend should be positive"
+            );
+
+            let step = self.current_step;
+            let tokens = match self.steps.get_mut(&step) {
+                Some(pool_step) => pool_step.flash_loan(token, amount),
+                None => panic!("There is no liquidity to lend"),
+            };
+            self.is_locked = true;
+
+            (tokens, step, amount * self.flash_loan_fee)
+        }
+
+        /// Gives back flash loaned tokens to the step that lent them.
+        ///
+        /// # Arguments
+        /// * `repayment` - Bucket containing the lent tokens and the fee
+        /// * `step` - Step that lent the tokens
+        /// * `amount` - Amount of tokens that were lent
+        pub fn repay_flash_loan(&mut self, repayment: Bucket, step: u16, amount: Decimal) {
+            let fee = repayment.amount() - amount;
+            let is_stable =
+                repayment.resource_address() == self.stable_protocol_fees.resource_address();
+
+            self.steps
+                .get_mut(&step)
+                .unwrap()
+                .repay_flash_loan(repayment, amount);
+            self.is_locked = false;
+
+            if is_stable {
+                self.record_statistics(Decimal::ZERO, Decimal::ZERO, fee, Decimal::ZERO);
+            } else {
+                self.record_statistics(Decimal::ZERO, Decimal::ZERO, Decimal::ZERO, fee);
+            }
+        }
+
+        /// Changes the fee of flash loans.
+        ///
+        /// # Arguments
+        /// * `flash_loan_fee` - Share of a flash loan paid as fee to the liquidity providers
+        pub fn change_flash_loan_fee(&mut self, flash_loan_fee: Decimal) {
+            assert!(
+                !flash_loan_fee.is_negative() && flash_loan_fee < Decimal::ONE,
+                "The flash loan fee should be such that 0<=FLF<1"
+            );
+            self.flash_loan_fee = flash_loan_fee;
+        }
+
+        /// Makes a new oracle observation if the last one is old enough
         pub fn new_observation(&mut self) {
             let current_time = Clock::current_time(TimePrecision::Minute).seconds_since_unix_epoch;
             self.oracle.new_observation(current_time, self.current_step);
         }
 
+        /// Changes the observation spacing and the maximum step change of the oracle.
+        ///
+        /// # Arguments
+        /// * `min_observation_spacing` - Minimum number of seconds between two observations
+        /// * `max_step_change_per_minute` - Maximum step change per minute of the truncated series
+        pub fn change_oracle_parameters(
+            &mut self,
+            min_observation_spacing: i64,
+            max_step_change_per_minute: u16,
+        ) {
+            self.oracle
+                .change_parameters(min_observation_spacing, max_step_change_per_minute);
+        }
+
+        /// Changes the number of entries stored by the oracle.
+        ///
+        /// # Arguments
+        /// * `capacity` - New maximum number of entries stored by the oracle
+        pub fn resize_oracle(&mut self, capacity: u16) {
+            self.oracle.resize(capacity);
+        }
+
+        /// Returns the minimum observation spacing, the maximum step change per minute and the
+        /// capacity of the oracle.
+        pub fn get_oracle_parameters(&self) -> (i64, u16, u16) {
+            self.oracle.get_parameters()
+        }
+
+        /// Returns the time-weighted average price of the pool since a given time.
+        ///
+        /// # Arguments
+        /// * `timestamp` - Time from which to compute the average
         pub fn get_twap_since(&self, timestamp: i64) -> Decimal {
             let current_time = Clock::current_time(TimePrecision::Minute).seconds_since_unix_epoch;
             let twas = self
                 .oracle
-                .get_time_weighted_average_step_since(timestamp, current_time);
-            let twap = self.rate_step.powi(twas as i64);
-            twap
+                .get_time_weighted_average_step_since(timestamp, current_time)
+                .expect("No oracle observation has been made for this pool yet");
+            self.rate_step.powi(twas as i64)
+        }
+
+        /// Returns the time-weighted average price of the pool since a given time, computed from
+        /// the truncated oracle series in the same way as the raw one.
+        ///
+        /// # Arguments
+        /// * `timestamp` - Time from which to compute the average
+        pub fn get_truncated_twap_since(&self, timestamp: i64) -> Decimal {
+            let current_time = Clock::current_time(TimePrecision::Minute).seconds_since_unix_epoch;
+            let twas = self
+                .oracle
+                .get_truncated_time_weighted_average_step_since(timestamp, current_time)
+                .expect("No oracle observation has been made for this pool yet");
+            self.rate_step.powi(twas as i64)
+        }
+
+        /// Returns the statistics of the pool since a given time as a tuple containing the
+        /// stablecoin volume, the other token volume, the fees paid in stablecoins, the fees paid
+        /// in other tokens and the time-weighted liquidity of the current step, expressed in
+        /// stablecoins.
+        ///
+        /// # Arguments
+        /// * `timestamp` - Time from which to compute the statistics
+        pub fn get_statistics_since(
+            &self,
+            timestamp: i64,
+        ) -> (Decimal, Decimal, Decimal, Decimal, Decimal) {
+            let current_time = Clock::current_time(TimePrecision::Minute).seconds_since_unix_epoch;
+            self.oracle.get_statistics_since(timestamp, current_time)
         }
 
         /// Returns the full state of the blueprint.
@@ -464,5 +655,42 @@ mod pool {
             let step_id: u16 = ((dec_step.floor().0) / Decimal::ONE.0).try_into().unwrap();
             step_id
         }
+
+        /// Internal function that checks that no flash loan of the pool is outstanding.
+        #[inline]
+        fn assert_unlocked(&self) {
+            assert!(
+                !self.is_locked,
+                "The pool is locked until its flash loan is repaid"
+            );
+        }
+
+        /// Internal function that records traded volumes and fees, as well as the liquidity of the
+        /// current step, in the oracle statistics.
+        fn record_statistics(
+            &self,
+            stable_volume: Decimal,
+            other_volume: Decimal,
+            stable_fees: Decimal,
+            other_fees: Decimal,
+        ) {
+            let current_time = Clock::current_time(TimePrecision::Minute).seconds_since_unix_epoch;
+            let liquidity = match self.steps.get(&self.current_step) {
+                Some(pool_step) => {
+                    let state = pool_step.get_step_state();
+                    state[0] + state[1] * state[2]
+                }
+                None => Decimal::ZERO,
+            };
+
+            self.oracle.record_statistics(
+                current_time,
+                stable_volume,
+                other_volume,
+                stable_fees,
+                other_fees,
+                liquidity,
+            );
+        }
     }
 }
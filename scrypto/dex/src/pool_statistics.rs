@@ -0,0 +1,181 @@
+//! Circular Array of cumulative pool statistics used by the pool Oracle
+
+use crate::observation_array::ARRAY_LENGTH;
+use crate::timestamped_array::{Timestamped, TimestampedArray};
+use scrypto::prelude::*;
+
+#[derive(ScryptoCategorize, LegacyDescribe, ScryptoEncode, ScryptoDecode, Clone)]
+pub struct PoolStatistics {
+    /// Timestamp of the snapshot: precise up to a minute
+    pub timestamp: i64,
+
+    /// Cumulative amount of stablecoins traded through the pool
+    pub stable_volume: Decimal,
+
+    /// Cumulative amount of other tokens traded through the pool
+    pub other_volume: Decimal,
+
+    /// Cumulative fees paid in stablecoins
+    pub stable_fees: Decimal,
+
+    /// Cumulative fees paid in other tokens
+    pub other_fees: Decimal,
+
+    /// Integral over time of the liquidity of the current step
+    pub liquidity_seconds: Decimal,
+
+    /// Liquidity of the current step at the time of the snapshot
+    pub liquidity: Decimal,
+}
+
+impl PoolStatistics {
+    /// Returns empty statistics at a given timestamp
+    pub fn new(timestamp: i64) -> Self {
+        Self {
+            timestamp,
+            stable_volume: Decimal::ZERO,
+            other_volume: Decimal::ZERO,
+            stable_fees: Decimal::ZERO,
+            other_fees: Decimal::ZERO,
+            liquidity_seconds: Decimal::ZERO,
+            liquidity: Decimal::ZERO,
+        }
+    }
+
+    /// Returns the statistics at a later timestamp, assuming that nothing happened in between
+    pub fn accumulated_to(&self, timestamp: i64) -> Self {
+        let mut statistics = self.clone();
+        if timestamp > self.timestamp {
+            statistics.liquidity_seconds += self.liquidity * (timestamp - self.timestamp);
+            statistics.timestamp = timestamp;
+        }
+        statistics
+    }
+}
+
+impl Timestamped for PoolStatistics {
+    const NAME: &'static str = "Statistics";
+
+    fn timestamp(&self) -> i64 {
+        self.timestamp
+    }
+}
+
+#[derive(ScryptoCategorize, LegacyDescribe, ScryptoEncode, ScryptoDecode, Clone)]
+pub struct StatisticsArray {
+    /// Circular Array that stores the snapshots
+    snapshots: TimestampedArray<PoolStatistics>,
+}
+
+impl StatisticsArray {
+    pub fn new() -> Self {
+        Self::with_capacity(ARRAY_LENGTH)
+    }
+
+    /// Returns an empty array storing at most `capacity` snapshots
+    ///
+    /// # Arguments
+    /// * `capacity` - maximum number of snapshots stored by the array
+    pub fn with_capacity(capacity: u16) -> Self {
+        Self {
+            snapshots: TimestampedArray::with_capacity(capacity),
+        }
+    }
+
+    /// Returns the maximum number of snapshots stored by the array
+    pub fn capacity(&self) -> u16 {
+        self.snapshots.capacity()
+    }
+
+    /// Changes the capacity of the array. Every snapshot is kept if the new capacity is large
+    /// enough, otherwise only the most recent ones are.
+    ///
+    /// # Arguments
+    /// * `capacity` - new maximum number of snapshots stored by the array
+    pub fn resize(&mut self, capacity: u16) {
+        self.snapshots.resize(capacity);
+    }
+
+    /// Returns the number of snapshots stored in the array
+    pub fn len(&self) -> usize {
+        self.snapshots.len()
+    }
+
+    /// Returns whether the array does not contain any snapshot
+    pub fn is_empty(&self) -> bool {
+        self.snapshots.is_empty()
+    }
+
+    /// Pushes a new snapshot in the array, overwriting the oldest one if the array is full. If
+    /// the last snapshot was taken at the same timestamp, it is replaced by the new one.
+    ///
+    /// # Arguments
+    /// * `statistics` - snapshot to push
+    pub fn push(&mut self, statistics: PoolStatistics) {
+        self.snapshots.push(statistics);
+    }
+
+    /// Gets a snapshot at a given index, the index 0 being the oldest snapshot
+    ///
+    /// # Arguments
+    /// * `index` - index of the snapshot to get
+    pub fn get(&self, index: usize) -> Option<&PoolStatistics> {
+        self.snapshots.get(index)
+    }
+
+    /// Returns the most recent snapshot
+    pub fn last(&self) -> Option<&PoolStatistics> {
+        self.snapshots.last()
+    }
+
+    /// Returns the statistics accumulated between a given timestamp and the current timestamp
+    /// as a tuple containing the stablecoin volume, the other token volume, the stablecoin
+    /// fees, the other token fees and the time-weighted liquidity of the current step.
+    ///
+    /// If the oldest snapshot was taken after `timestamp`, the statistics are computed from
+    /// this oldest snapshot.
+    ///
+    /// # Arguments
+    /// * `timestamp` - timestamp to compute the statistics since
+    /// * `current_timestamp` - timestamp at request
+    pub fn get_statistics_since(
+        &self,
+        timestamp: i64,
+        current_timestamp: i64,
+    ) -> (Decimal, Decimal, Decimal, Decimal, Decimal) {
+        let last = match self.last() {
+            None => {
+                return (
+                    Decimal::ZERO,
+                    Decimal::ZERO,
+                    Decimal::ZERO,
+                    Decimal::ZERO,
+                    Decimal::ZERO,
+                )
+            }
+            Some(statistics) => statistics.accumulated_to(current_timestamp),
+        };
+
+        let begin = timestamp
+            .max(self.get(0).unwrap().timestamp)
+            .min(last.timestamp);
+        let base = self
+            .get(self.snapshots.index_at(begin))
+            .unwrap()
+            .accumulated_to(begin);
+
+        let time_weighted_liquidity = if last.timestamp > begin {
+            (last.liquidity_seconds - base.liquidity_seconds) / (last.timestamp - begin)
+        } else {
+            last.liquidity
+        };
+
+        (
+            last.stable_volume - base.stable_volume,
+            last.other_volume - base.other_volume,
+            last.stable_fees - base.stable_fees,
+            last.other_fees - base.other_fees,
+            time_weighted_liquidity,
+        )
+    }
+}
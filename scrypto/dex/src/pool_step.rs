@@ -251,6 +251,51 @@ mod pool_step {
             )
         }
 
+        /// Lends tokens from the liquidity of the [`PoolStep`].
+        ///
+        /// # Arguments
+        /// * `token` - ResourceAddress of the token to lend
+        /// * `amount` - Amount of tokens to lend
+        pub fn flash_loan(&mut self, token: ResourceAddress, amount: Decimal) -> Bucket {
+            let vault = if token == self.stable_vault.resource_address() {
+                &mut self.stable_vault
+            } else {
+                assert!(
+                    token == self.other_vault.resource_address(),
+                    "This pool does not trade the given token"
+                );
+                &mut self.other_vault
+            };
+
+            assert!(
+                amount <= vault.amount(),
+                "Not enough liquidity to lend {} tokens",
+                amount
+            );
+
+            vault.take(amount)
+        }
+
+        /// Gives back flash loaned tokens and shares the fee between the liquidity providers of the
+        /// [`PoolStep`].
+        ///
+        /// # Arguments
+        /// * `repayment` - Bucket containing the lent tokens and the fee
+        /// * `amount` - Amount of tokens that were lent
+        pub fn repay_flash_loan(&mut self, mut repayment: Bucket, amount: Decimal) {
+            if repayment.resource_address() == self.stable_vault.resource_address() {
+                self.stable_vault.put(repayment.take(amount));
+                let l = self.stable_vault.amount() + self.rate * self.other_vault.amount();
+                self.stable_fees_per_liq += repayment.amount() / l;
+                self.stable_fees_vault.put(repayment);
+            } else {
+                self.other_vault.put(repayment.take(amount));
+                let l = self.stable_vault.amount() + self.rate * self.other_vault.amount();
+                self.other_fees_per_liq += repayment.amount() / l;
+                self.other_fees_vault.put(repayment);
+            }
+        }
+
         /// Returns the current state of the [`PoolStep`].
         pub fn get_step_state(&self) -> Vec<Decimal> {
             vec![
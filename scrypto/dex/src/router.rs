@@ -20,6 +20,16 @@
 //! - [claim_fees](RouterComponent::claim_fees) - Claim fees associated to the supplied proof of [`Position`]s.
 //! - [swap](RouterComponent::swap) - Swaps tokens.
 //! - [claim_protocol_fees](RouterComponent::claim_protocol_fees) - Claims protocol fees.
+//! - [flash_loan](RouterComponent::flash_loan) - Lends tokens of the liquidity of a given pool.
+//! - [repay_flash_loan](RouterComponent::repay_flash_loan) - Repays a flash loan.
+//! - [change_flash_loan_fee](RouterComponent::change_flash_loan_fee) - Changes the fee of flash loans of a given pool.
+//! - [new_observation](RouterComponent::new_observation) - Makes a new oracle observation for a given pool.
+//! - [change_oracle_parameters](RouterComponent::change_oracle_parameters) - Changes the observation spacing and the maximum step change of the oracle of a given pool.
+//! - [resize_oracle](RouterComponent::resize_oracle) - Changes the number of entries stored by the oracle of a given pool.
+//! - [get_oracle_parameters](RouterComponent::get_oracle_parameters) - Returns the parameters of the oracle of a given pool.
+//! - [get_twap_since](RouterComponent::get_twap_since) - Returns the time-weighted average price of a given token since a given time.
+//! - [get_truncated_twap_since](RouterComponent::get_truncated_twap_since) - Returns the truncated time-weighted average price of a given token since a given time.
+//! - [get_pool_statistics_since](RouterComponent::get_pool_statistics_since) - Returns the volumes, fees and time-weighted liquidity of a given pool since a given time.
 //! - [get_pool_state](RouterComponent::get_pool_state) - Returns the full state of the blueprint.
 //! - [step_at_rate](RouterComponent::step_at_rate) - Returns the step of a pool associated to a given rate
 
@@ -27,6 +37,7 @@ use scrypto::blueprint;
 
 #[blueprint]
 mod router {
+    use crate::flash_loan::FlashLoan;
     use crate::pool::PoolComponent;
     use crate::position::Position;
 
@@ -37,7 +48,7 @@ mod router {
         /// Pools registered by the router
         pools: HashMap<ResourceAddress, PoolComponent>,
 
-        /// Vault used to mint [`Position`]s
+        /// Vault used to mint [`Position`]s and [`FlashLoan`]s
         position_minter: Vault,
 
         /// ResourceAddress of the [`Position`] NFR.
@@ -48,6 +59,12 @@ mod router {
 
         /// Address of the admin badge controlling the Router and its pools
         admin_badge: ResourceAddress,
+
+        /// ResourceAddress of the [`FlashLoan`] NFR.
+        flash_loan_address: ResourceAddress,
+
+        /// Id of the next flash loan to be minted
+        flash_loan_id: u64,
     }
 
     impl Router {
@@ -82,6 +99,21 @@ mod router {
                 )
                 .create_with_no_initial_supply();
 
+            // Creates the NFR FlashLoan address. Flash loans cannot be deposited so that they have
+            // to be repaid in the transaction that took them.
+            let flash_loan_address = ResourceBuilder::new_integer_non_fungible()
+                .metadata("name", "Stoichiometric Pool Flash Loan")
+                .mintable(
+                    rule!(require(position_minter.resource_address())),
+                    AccessRule::DenyAll,
+                )
+                .burnable(
+                    rule!(require(position_minter.resource_address())),
+                    AccessRule::DenyAll,
+                )
+                .restrict_deposit(rule!(deny_all), AccessRule::DenyAll)
+                .create_with_no_initial_supply();
+
             // Defines the access rules for the methods of the blueprint. For security reasons,
             // the default access rule is set to require the admin badge.
             let router_rules = AccessRules::new()
@@ -118,8 +150,31 @@ mod router {
                 )
                 .method("claim_fees", AccessRule::AllowAll, AccessRule::DenyAll)
                 .method("swap", AccessRule::AllowAll, AccessRule::DenyAll)
+                .method("flash_loan", AccessRule::AllowAll, AccessRule::DenyAll)
+                .method(
+                    "repay_flash_loan",
+                    AccessRule::AllowAll,
+                    AccessRule::DenyAll,
+                )
                 .method("get_pool_state", AccessRule::AllowAll, AccessRule::DenyAll)
                 .method("step_at_rate", AccessRule::AllowAll, AccessRule::DenyAll)
+                .method("new_observation", AccessRule::AllowAll, AccessRule::DenyAll)
+                .method("get_twap_since", AccessRule::AllowAll, AccessRule::DenyAll)
+                .method(
+                    "get_truncated_twap_since",
+                    AccessRule::AllowAll,
+                    AccessRule::DenyAll,
+                )
+                .method(
+                    "get_pool_statistics_since",
+                    AccessRule::AllowAll,
+                    AccessRule::DenyAll,
+                )
+                .method(
+                    "get_oracle_parameters",
+                    AccessRule::AllowAll,
+                    AccessRule::DenyAll,
+                )
                 .default(rule!(require(admin_badge)), AccessRule::DenyAll);
 
             let mut component = Self {
@@ -129,6 +184,8 @@ mod router {
                 position_address: position_resource.clone(),
                 position_id: 0,
                 admin_badge: admin_badge,
+                flash_loan_address,
+                flash_loan_id: 0,
             }
             .instantiate();
 
@@ -456,18 +513,179 @@ mod router {
             buckets
         }
 
-        /// Makes a new oracle observations if last observations happened more than 20 seconds ago
+        /// Lends tokens of the liquidity of the current step of a given pool. The tokens are
+        /// returned with a [`FlashLoan`] NFR that cannot be deposited and has to be given back with
+        /// the tokens and the fee in the same transaction.
+        ///
+        /// # Arguments
+        /// * `pool_token` - ResourceAddress of the other token traded by the pool
+        /// * `token` - ResourceAddress of the token to borrow, either the stablecoin or `pool_token`
+        /// * `amount` - Amount of tokens to borrow
+        pub fn flash_loan(
+            &mut self,
+            pool_token: ResourceAddress,
+            token: ResourceAddress,
+            amount: Decimal,
+        ) -> (Bucket, Bucket) {
+            let pool = self.get_pool(pool_token);
+            let (tokens, step, fee) = pool.flash_loan(token, amount);
+
+            let flash_loan = self.position_minter.authorize(|| {
+                borrow_resource_manager!(self.flash_loan_address).mint_non_fungible(
+                    &NonFungibleLocalId::Integer(self.flash_loan_id.into()),
+                    FlashLoan::from(pool_token, step, token, amount, fee),
+                )
+            });
+            self.flash_loan_id += 1;
+
+            (tokens, flash_loan)
+        }
+
+        /// Repays a flash loan and returns the tokens that were not needed. The fee goes to the
+        /// liquidity providers of the step that lent the tokens.
+        ///
+        /// # Arguments
+        /// * `repayment` - Bucket containing the borrowed tokens and the fee
+        /// * `flash_loan_bucket` - Bucket containing the [`FlashLoan`] NFR to repay
+        pub fn repay_flash_loan(
+            &mut self,
+            mut repayment: Bucket,
+            flash_loan_bucket: Bucket,
+        ) -> Bucket {
+            assert!(
+                flash_loan_bucket.resource_address() == self.flash_loan_address,
+                "Please provide a flash loan to repay"
+            );
+
+            let flash_loan_data: FlashLoan = borrow_resource_manager!(self.flash_loan_address)
+                .get_non_fungible_data(flash_loan_bucket.non_fungible::<FlashLoan>().local_id());
+
+            assert!(
+                repayment.resource_address() == flash_loan_data.token,
+                "Please provide repayment in the borrowed tokens"
+            );
+
+            let amount_due = flash_loan_data.amount + flash_loan_data.fee;
+            assert!(
+                repayment.amount() >= amount_due,
+                "You did not provide enough tokens to repay the flash loan"
+            );
+
+            let pool = self.get_pool(flash_loan_data.pool_token);
+            pool.repay_flash_loan(
+                repayment.take(amount_due),
+                flash_loan_data.step,
+                flash_loan_data.amount,
+            );
+
+            self.position_minter.authorize(|| {
+                borrow_resource_manager!(self.flash_loan_address).burn(flash_loan_bucket);
+            });
+
+            repayment
+        }
+
+        /// Changes the fee of the flash loans of a given pool.
+        ///
+        /// # Access Rule
+        /// Can only be called by the owner of the admin badge
+        ///
+        /// # Arguments
+        /// * `token` - ResourceAddress of the other token traded by the pool
+        /// * `flash_loan_fee` - Share of a flash loan paid as fee to the liquidity providers
+        pub fn change_flash_loan_fee(&mut self, token: ResourceAddress, flash_loan_fee: Decimal) {
+            let pool = self.get_pool(token);
+            pool.change_flash_loan_fee(flash_loan_fee);
+        }
+
+        /// Makes a new oracle observation for a given pool if the last one is old enough. This
+        /// method can be called by anyone so that keepers can feed the oracles.
+        ///
+        /// # Arguments
+        /// * `token` - ResourceAddress of the other token traded by the pool
         pub fn new_observation(&mut self, token: ResourceAddress) {
             let pool = self.get_pool(token);
             pool.new_observation();
         }
 
+        /// Changes the minimum spacing between two observations and the maximum step change per
+        /// minute of the truncated series of the oracle of a given pool.
+        ///
+        /// # Access Rule
+        /// Can only be called by the owner of the admin badge
+        ///
+        /// # Arguments
+        /// * `token` - ResourceAddress of the other token traded by the pool
+        /// * `min_observation_spacing` - Minimum number of seconds between two observations
+        /// * `max_step_change_per_minute` - Maximum step change per minute of the truncated series
+        pub fn change_oracle_parameters(
+            &mut self,
+            token: ResourceAddress,
+            min_observation_spacing: i64,
+            max_step_change_per_minute: u16,
+        ) {
+            let pool = self.get_pool(token);
+            pool.change_oracle_parameters(min_observation_spacing, max_step_change_per_minute);
+        }
+
+        /// Changes the number of observations and statistics snapshots stored by the oracle of a
+        /// given pool. The history is kept when growing the oracle and only the most recent
+        /// entries are kept when shrinking it.
+        ///
+        /// # Access Rule
+        /// Can only be called by the owner of the admin badge
+        ///
+        /// # Arguments
+        /// * `token` - ResourceAddress of the other token traded by the pool
+        /// * `capacity` - New maximum number of entries stored by the oracle
+        pub fn resize_oracle(&mut self, token: ResourceAddress, capacity: u16) {
+            let pool = self.get_pool(token);
+            pool.resize_oracle(capacity);
+        }
+
+        /// Returns the minimum observation spacing, the maximum step change per minute and the
+        /// capacity of the oracle of a given pool.
+        ///
+        /// # Arguments
+        /// * `token` - ResourceAddress of the other token traded by the pool
+        pub fn get_oracle_parameters(&self, token: ResourceAddress) -> (i64, u16, u16) {
+            let pool = self.get_pool(token);
+            pool.get_oracle_parameters()
+        }
+
         /// Returns Time-wieghted average price of a given token since a given time
         pub fn get_twap_since(&self, token: ResourceAddress, timestamp: i64) -> Decimal {
             let pool = self.get_pool(token);
             pool.get_twap_since(timestamp)
         }
 
+        /// Returns Time-weighted average price of a given token since a given time, computed from
+        /// observations whose step change per minute is capped to resist price manipulations.
+        ///
+        /// # Arguments
+        /// * `token` - ResourceAddress of the other token traded by the pool
+        /// * `timestamp` - Time from which to compute the average
+        pub fn get_truncated_twap_since(&self, token: ResourceAddress, timestamp: i64) -> Decimal {
+            let pool = self.get_pool(token);
+            pool.get_truncated_twap_since(timestamp)
+        }
+
+        /// Returns the statistics of a given pool since a given time as a tuple containing the
+        /// stablecoin volume, the other token volume, the fees paid in stablecoins, the fees paid
+        /// in other tokens and the time-weighted liquidity of the current step.
+        ///
+        /// # Arguments
+        /// * `token` - ResourceAddress of the other token traded by the pool
+        /// * `timestamp` - Time from which to compute the statistics
+        pub fn get_pool_statistics_since(
+            &self,
+            token: ResourceAddress,
+            timestamp: i64,
+        ) -> (Decimal, Decimal, Decimal, Decimal, Decimal) {
+            let pool = self.get_pool(token);
+            pool.get_statistics_since(timestamp)
+        }
+
         /// Return the state of the given pool.
         ///
         /// # Arguments
@@ -0,0 +1,154 @@
+//! Generic Circular Array of timestamped entries, shared by the oracles to store their histories
+
+use scrypto::prelude::*;
+
+/// Entry that can be stored in a [`TimestampedArray`]
+pub trait Timestamped {
+    /// Name of the entries, used in error messages
+    const NAME: &'static str;
+
+    /// Timestamp of the entry
+    fn timestamp(&self) -> i64;
+}
+
+#[derive(ScryptoCategorize, LegacyDescribe, ScryptoEncode, ScryptoDecode, Clone)]
+pub struct TimestampedArray<T> {
+    /// Index of the oldest entry of the array
+    start: u16,
+
+    /// Circular Array that stores the entries
+    data: Vec<T>,
+
+    /// Maximum number of entries stored by the array
+    capacity: u16,
+}
+
+impl<T: Timestamped + Clone> TimestampedArray<T> {
+    /// Returns an empty array storing at most `capacity` entries
+    ///
+    /// # Arguments
+    /// * `capacity` - maximum number of entries stored by the array
+    pub fn with_capacity(capacity: u16) -> Self {
+        assert!(capacity > 0, "The capacity of the array should be positive");
+        Self {
+            start: 0,
+            data: Vec::new(),
+            capacity,
+        }
+    }
+
+    /// Returns the maximum number of entries stored by the array
+    pub fn capacity(&self) -> u16 {
+        self.capacity
+    }
+
+    /// Changes the capacity of the array. Every entry is kept if the new capacity is large
+    /// enough, otherwise only the most recent ones are.
+    ///
+    /// # Arguments
+    /// * `capacity` - new maximum number of entries stored by the array
+    pub fn resize(&mut self, capacity: u16) {
+        assert!(capacity > 0, "The capacity of the array should be positive");
+
+        let skipped = self.data.len().saturating_sub(capacity as usize);
+        let data = (skipped..self.data.len())
+            .map(|index| self.get(index).unwrap().clone())
+            .collect();
+
+        self.start = 0;
+        self.data = data;
+        self.capacity = capacity;
+    }
+
+    /// Returns the number of entries stored in the array
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns whether the array does not contain any entry
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Pushes a new entry in the array, overwriting the oldest one if the array is full. If the
+    /// last entry has the same timestamp, it is replaced by the new one.
+    ///
+    /// # Arguments
+    /// * `entry` - entry to push
+    pub fn push(&mut self, entry: T) {
+        match self.last() {
+            None => {}
+            Some(last_entry) => {
+                assert!(
+                    entry.timestamp() >= last_entry.timestamp(),
+                    "{} should be pushed in chronological order",
+                    T::NAME
+                );
+
+                if entry.timestamp() == last_entry.timestamp() {
+                    let last_index = self.raw_index(self.data.len() - 1);
+                    self.data[last_index] = entry;
+                    return;
+                }
+            }
+        }
+
+        if self.data.len() < self.capacity as usize {
+            self.data.push(entry);
+        } else {
+            self.data[self.start as usize] = entry;
+            self.start = (self.start + 1) % self.capacity;
+        }
+    }
+
+    /// Gets an entry at a given index, the index 0 being the oldest entry
+    ///
+    /// # Arguments
+    /// * `index` - index of the entry to get
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index >= self.data.len() {
+            return None;
+        }
+        self.data.get(self.raw_index(index))
+    }
+
+    /// Returns the most recent entry
+    pub fn last(&self) -> Option<&T> {
+        if self.data.is_empty() {
+            None
+        } else {
+            self.get(self.data.len() - 1)
+        }
+    }
+
+    /// Returns the index of the last entry made at or before a given timestamp, or 0 if all
+    /// entries were made after it.
+    ///
+    /// # Arguments
+    /// * `timestamp` - timestamp to look for
+    pub fn index_at(&self, timestamp: i64) -> usize {
+        // Binary search for the first entry made strictly after the given timestamp
+        let mut low = 0;
+        let mut high = self.data.len();
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if self.get(mid).unwrap().timestamp() <= timestamp {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+
+        if low == 0 {
+            0
+        } else {
+            low - 1
+        }
+    }
+
+    /// Converts the index of an entry to its index in the underlying vector
+    #[inline]
+    fn raw_index(&self, index: usize) -> usize {
+        (index + self.start as usize) % (self.capacity as usize)
+    }
+}
@@ -0,0 +1,62 @@
+use scrypto::prelude::*;
+
+#[derive(ScryptoCategorize, LegacyDescribe, ScryptoEncode, ScryptoDecode, Clone)]
+pub struct Auction {
+    /// Amount of collateral left to sell
+    pub collateral_amount: Decimal,
+
+    /// Amount of principal left to raise
+    pub principal: Decimal,
+
+    /// Amount of interests left to raise
+    pub interests: Decimal,
+
+    /// Price of the collateral at the start of the auction
+    pub start_price: Decimal,
+
+    /// Time at the start of the auction
+    pub start_time: i64,
+}
+
+impl Auction {
+    pub fn new(
+        collateral_amount: Decimal,
+        principal: Decimal,
+        interests: Decimal,
+        start_price: Decimal,
+        start_time: i64,
+    ) -> Self {
+        Self {
+            collateral_amount,
+            principal,
+            interests,
+            start_price,
+            start_time,
+        }
+    }
+
+    /// Returns the amount of stablecoins left to raise
+    pub fn debt(&self) -> Decimal {
+        self.principal + self.interests
+    }
+
+    /// Returns the price of the collateral at a given time. The price decreases linearly from the
+    /// start price to zero over the duration of the auction.
+    ///
+    /// # Arguments
+    /// * `current_time` - time at which to compute the price
+    /// * `duration` - duration of the auction in seconds
+    pub fn price_at(&self, current_time: i64, duration: i64) -> Decimal {
+        let elapsed = current_time - self.start_time;
+        if elapsed >= duration {
+            Decimal::ZERO
+        } else {
+            self.start_price * (duration - elapsed) / duration
+        }
+    }
+
+    /// Returns whether the auction has reached its end without raising its debt
+    pub fn has_expired(&self, current_time: i64, duration: i64) -> bool {
+        current_time - self.start_time >= duration
+    }
+}
@@ -0,0 +1,37 @@
+use scrypto::prelude::*;
+
+#[derive(
+    NonFungibleData, ScryptoCategorize, LegacyDescribe, ScryptoEncode, ScryptoDecode, Clone,
+)]
+pub struct BasketLoan {
+    /// Amount of each token given as collateral
+    #[mutable]
+    pub collaterals: HashMap<ResourceAddress, Decimal>,
+
+    /// Amount of stablecoins lent against each collateral token
+    #[mutable]
+    pub amounts_lent: HashMap<ResourceAddress, Decimal>,
+
+    /// Debt owed to the lender of each collateral token divided by its rate accumulator
+    #[mutable]
+    pub normalised_debts: HashMap<ResourceAddress, Decimal>,
+
+    /// Time at the moment of loan
+    pub loan_date: i64,
+}
+
+impl BasketLoan {
+    pub fn new(loan_date: i64) -> Self {
+        Self {
+            collaterals: HashMap::new(),
+            amounts_lent: HashMap::new(),
+            normalised_debts: HashMap::new(),
+            loan_date,
+        }
+    }
+
+    /// Returns the amount of a given token held as collateral
+    pub fn collateral_amount(&self, token: &ResourceAddress) -> Decimal {
+        *self.collaterals.get(token).unwrap_or(&Decimal::ZERO)
+    }
+}
@@ -4,4 +4,40 @@ use scrypto::prelude::Decimal;
 pub const SECONDS_PER_DAY: Decimal =
     Decimal(BnumI256::from_digits([13897502818169782272, 4683, 0, 0]));
 
-pub const FLASH_LOAN_FEE: Decimal = Decimal(BnumI256::from_digits([1000500000000000000, 0, 0, 0]));
+/// Default share of a flash mint paid as fee to the reserves
+pub const DEFAULT_FLASH_MINT_FEE: Decimal =
+    Decimal(BnumI256::from_digits([500000000000000, 0, 0, 0]));
+
+/// Default share of a flash loan of reserve tokens paid as fee to the reserves
+pub const DEFAULT_FLASH_LOAN_FEE: Decimal =
+    Decimal(BnumI256::from_digits([500000000000000, 0, 0, 0]));
+
+/// Default share of the collateral taken by a redemption paid as fee to the reserves
+pub const DEFAULT_REDEMPTION_FEE: Decimal =
+    Decimal(BnumI256::from_digits([5000000000000000, 0, 0, 0]));
+
+/// Maximum number of loans scanned by a redemption
+pub const MAX_REDEEMED_LOANS: usize = 20;
+
+/// Default ratio between the starting price of a liquidation auction and the oracle price
+pub const DEFAULT_AUCTION_START_PREMIUM: Decimal =
+    Decimal(BnumI256::from_digits([1200000000000000000, 0, 0, 0]));
+
+/// Default duration of a liquidation auction in seconds
+pub const DEFAULT_AUCTION_DURATION: i64 = 3600;
+
+/// Default share of the debt of a loan paid in collateral to whoever starts its auction
+pub const DEFAULT_KICK_INCENTIVE: Decimal =
+    Decimal(BnumI256::from_digits([10000000000000000, 0, 0, 0]));
+
+/// Factor by which the running product of the stability pool is multiplied when it gets too small
+pub const STABILITY_POOL_SCALE_FACTOR: Decimal = Decimal(BnumI256::from_digits([
+    11515845246265065472,
+    54210108,
+    0,
+    0,
+]));
+
+/// Value below which the running product of the stability pool is scaled up
+pub const STABILITY_POOL_MIN_PRODUCT: Decimal =
+    Decimal(BnumI256::from_digits([1000000000, 0, 0, 0]));
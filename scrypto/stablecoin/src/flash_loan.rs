@@ -0,0 +1,21 @@
+use scrypto::prelude::*;
+
+#[derive(
+    NonFungibleData, ScryptoCategorize, LegacyDescribe, ScryptoEncode, ScryptoDecode, Clone,
+)]
+pub struct FlashLoan {
+    /// Token lent from the reserves
+    pub token: ResourceAddress,
+
+    /// Amount of tokens lent
+    pub amount: Decimal,
+
+    /// Fee to pay on top of the amount of tokens lent
+    pub fee: Decimal,
+}
+
+impl FlashLoan {
+    pub fn new(token: ResourceAddress, amount: Decimal, fee: Decimal) -> Self {
+        Self { token, amount, fee }
+    }
+}
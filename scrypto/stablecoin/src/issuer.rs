@@ -1,11 +1,35 @@
-use scrypto::blueprint;
+use scrypto::{blueprint, external_component};
+
+// Import the Psm component
+external_component! {
+    PsmLocalComponent {
+        fn get_outstanding_supply(&self) -> Decimal;
+    }
+}
+
+// Import the MinterRegistry component
+external_component! {
+    MinterRegistryLocalComponent {
+        fn get_outstanding_supply(&self) -> Decimal;
+    }
+}
 
 #[blueprint]
 mod issuer {
-    use crate::constants::FLASH_LOAN_FEE;
+    use crate::basket_loan::BasketLoan;
+    use crate::constants::{
+        DEFAULT_AUCTION_DURATION, DEFAULT_AUCTION_START_PREMIUM, DEFAULT_FLASH_LOAN_FEE,
+        DEFAULT_FLASH_MINT_FEE, DEFAULT_REDEMPTION_FEE, MAX_REDEEMED_LOANS,
+    };
+    use crate::flash_loan::FlashLoan;
     use crate::flash_mint::FlashMint;
     use crate::lender::LenderComponent;
     use crate::loan::Loan;
+    use crate::loan_event::{BasketLoanEvent, LoanEvent, LoanEventKind};
+    use crate::savings_vault::SavingsVaultComponent;
+    use crate::stability_deposit::StabilityDeposit;
+    use crate::stability_pool::StabilityPoolComponent;
+    use crate::system_auction::SystemAuction;
 
     pub struct Issuer {
         reserves: HashMap<ResourceAddress, Vault>,
@@ -18,6 +42,99 @@ mod issuer {
         flash_mint_address: ResourceAddress,
         flash_mint_id: u64,
         admin_badge: ResourceAddress,
+        basket_loan_address: ResourceAddress,
+        basket_loan_id: u64,
+        collateral_priority: Vec<ResourceAddress>,
+        stability_pool: StabilityPoolComponent,
+        stability_deposit_address: ResourceAddress,
+        stability_deposit_id: u64,
+
+        /// Principal lent and not yet repaid for each collateral token
+        lender_debts: HashMap<ResourceAddress, Decimal>,
+
+        /// Sum of the normalised debts of the loans of each lender, auctioned debts excluded
+        lender_normalised_debts: HashMap<ResourceAddress, Decimal>,
+
+        /// Collateral backing the loans of each lender, auctioned collateral excluded
+        lender_collaterals: HashMap<ResourceAddress, Decimal>,
+
+        /// Maximum principal that can be lent against each collateral token
+        debt_ceilings: HashMap<ResourceAddress, Decimal>,
+
+        /// Principal lent and not yet repaid for all collateral tokens
+        total_debt: Decimal,
+
+        /// Maximum principal that can be lent for all collateral tokens
+        global_debt_ceiling: Decimal,
+
+        /// Bad debt that could not be covered by the reserves or by the stability pool
+        system_debt: Decimal,
+
+        /// Amount of stablecoin reserves above which the surplus can be auctioned
+        surplus_buffer: Decimal,
+
+        /// Resource minted by debt auctions and burned by surplus auctions
+        reserve_claim_address: ResourceAddress,
+
+        /// Price of a reserve claim in stablecoins at the last auction sale
+        reserve_claim_price: Decimal,
+
+        /// Running auction of reserve claims against stablecoins to repay the system debt
+        debt_auction: Option<SystemAuction>,
+
+        /// Running auction of the stablecoin surplus against reserve claims
+        surplus_auction: Option<SystemAuction>,
+
+        /// Share of a flash mint paid as fee to the reserves
+        flash_mint_fee: Decimal,
+
+        /// Maximum amount of stablecoins that can be flash minted at once
+        flash_mint_max: Decimal,
+
+        flash_loan_address: ResourceAddress,
+        flash_loan_id: u64,
+
+        /// Share of a flash loan paid as fee to the reserves for each token whose fee was set
+        flash_loan_fees: HashMap<ResourceAddress, Decimal>,
+
+        /// Share of the collateral taken by a redemption paid as fee to the reserves
+        redemption_fee: Decimal,
+
+        /// Vault of stablecoins earning the savings rate
+        savings_vault: SavingsVaultComponent,
+
+        /// Fungible shares of the savings vault
+        savings_share_address: ResourceAddress,
+
+        /// Events that changed the collateral or the debt of each loan, oldest first
+        loan_histories: KeyValueStore<NonFungibleLocalId, Vec<LoanEvent>>,
+
+        /// Ids of the loans of each lender that were not burned yet, ordered by the ratio of their
+        /// collateral to their normalised debt, which is the order in which they are redeemed
+        open_loans: HashMap<ResourceAddress, BTreeSet<(Decimal, NonFungibleLocalId)>>,
+
+        /// Events that changed the collaterals or the debts of each basket loan, oldest first
+        basket_loan_histories: KeyValueStore<NonFungibleLocalId, Vec<BasketLoanEvent>>,
+
+        /// Duration in seconds of the debt and surplus auctions
+        system_auction_duration: i64,
+
+        /// Premium applied to the last price of reserve claims at the start of a debt or surplus
+        /// auction
+        system_auction_start_premium: Decimal,
+
+        /// Ids of the basket loans that were not burned yet
+        open_basket_loans: HashSet<NonFungibleLocalId>,
+
+        /// Whether a flash loan of the reserves has not been repaid yet, in which case the
+        /// reserves cannot be spent
+        reserves_locked: bool,
+
+        /// Peg stability modules minting stablecoins outside of the issuer
+        psms: Vec<ComponentAddress>,
+
+        /// Minter registries minting stablecoins outside of the issuer
+        minter_registries: Vec<ComponentAddress>,
     }
 
     impl Issuer {
@@ -66,9 +183,87 @@ mod issuer {
                 .restrict_deposit(rule!(deny_all), AccessRule::DenyAll)
                 .create_with_no_initial_supply();
 
+            // Creates the NFR FlashLoan address
+            let flash_loan_address = ResourceBuilder::new_integer_non_fungible()
+                .metadata("name", "Stoichiometric Flash Loan")
+                .mintable(
+                    rule!(require(resource_minter.resource_address())),
+                    AccessRule::DenyAll,
+                )
+                .burnable(
+                    rule!(require(resource_minter.resource_address())),
+                    AccessRule::DenyAll,
+                )
+                .restrict_deposit(rule!(deny_all), AccessRule::DenyAll)
+                .create_with_no_initial_supply();
+
+            // Creates the NFR BasketLoan address
+            let basket_loan_address = ResourceBuilder::new_integer_non_fungible()
+                .metadata("name", "Stoichiometric Basket Loan")
+                .mintable(
+                    rule!(require(resource_minter.resource_address())),
+                    AccessRule::DenyAll,
+                )
+                .burnable(
+                    rule!(require(resource_minter.resource_address())),
+                    AccessRule::DenyAll,
+                )
+                .updateable_non_fungible_data(
+                    rule!(require(resource_minter.resource_address())),
+                    AccessRule::DenyAll,
+                )
+                .create_with_no_initial_supply();
+
+            // Creates the NFR StabilityDeposit address
+            let stability_deposit_address = ResourceBuilder::new_integer_non_fungible()
+                .metadata("name", "Stoichiometric Stability Deposit")
+                .mintable(
+                    rule!(require(resource_minter.resource_address())),
+                    AccessRule::DenyAll,
+                )
+                .burnable(
+                    rule!(require(resource_minter.resource_address())),
+                    AccessRule::DenyAll,
+                )
+                .updateable_non_fungible_data(
+                    rule!(require(resource_minter.resource_address())),
+                    AccessRule::DenyAll,
+                )
+                .create_with_no_initial_supply();
+
+            // Creates the reserve claims sold by debt auctions and bought back by surplus auctions
+            let reserve_claim_address = ResourceBuilder::new_fungible()
+                .divisibility(18)
+                .metadata("name", "Stoichiometric Reserve Claim")
+                .mintable(
+                    rule!(require(resource_minter.resource_address())),
+                    AccessRule::DenyAll,
+                )
+                .burnable(
+                    rule!(require(resource_minter.resource_address())),
+                    AccessRule::DenyAll,
+                )
+                .create_with_no_initial_supply();
+
+            // Creates the shares of the savings vault
+            let savings_share_address = ResourceBuilder::new_fungible()
+                .divisibility(18)
+                .metadata("name", "Stoichiometric Savings Share")
+                .mintable(
+                    rule!(require(resource_minter.resource_address())),
+                    AccessRule::DenyAll,
+                )
+                .burnable(
+                    rule!(require(resource_minter.resource_address())),
+                    AccessRule::DenyAll,
+                )
+                .create_with_no_initial_supply();
+
             let issuer_rules = AccessRules::new()
                 .method("take_loan", AccessRule::AllowAll, AccessRule::DenyAll)
                 .method("repay_loans", AccessRule::AllowAll, AccessRule::DenyAll)
+                .method("repay_partial", AccessRule::AllowAll, AccessRule::DenyAll)
+                .method("borrow_more", AccessRule::AllowAll, AccessRule::DenyAll)
                 .method("add_collateral", AccessRule::AllowAll, AccessRule::DenyAll)
                 .method(
                     "remove_collateral",
@@ -76,19 +271,180 @@ mod issuer {
                     AccessRule::DenyAll,
                 )
                 .method("liquidate", AccessRule::AllowAll, AccessRule::DenyAll)
+                .method(
+                    "take_basket_loan",
+                    AccessRule::AllowAll,
+                    AccessRule::DenyAll,
+                )
+                .method(
+                    "repay_basket_loan",
+                    AccessRule::AllowAll,
+                    AccessRule::DenyAll,
+                )
+                .method(
+                    "add_basket_collateral",
+                    AccessRule::AllowAll,
+                    AccessRule::DenyAll,
+                )
+                .method(
+                    "liquidate_basket_loan",
+                    AccessRule::AllowAll,
+                    AccessRule::DenyAll,
+                )
+                .method(
+                    "clear_basket_bad_debt",
+                    AccessRule::AllowAll,
+                    AccessRule::DenyAll,
+                )
+                .method("start_auction", AccessRule::AllowAll, AccessRule::DenyAll)
+                .method("take_auction", AccessRule::AllowAll, AccessRule::DenyAll)
+                .method("reset_auction", AccessRule::AllowAll, AccessRule::DenyAll)
+                .method("get_auction", AccessRule::AllowAll, AccessRule::DenyAll)
+                .method(
+                    "deposit_in_stability_pool",
+                    AccessRule::AllowAll,
+                    AccessRule::DenyAll,
+                )
+                .method(
+                    "withdraw_from_stability_pool",
+                    AccessRule::AllowAll,
+                    AccessRule::DenyAll,
+                )
+                .method(
+                    "claim_stability_pool_gains",
+                    AccessRule::AllowAll,
+                    AccessRule::DenyAll,
+                )
+                .method(
+                    "liquidate_with_stability_pool",
+                    AccessRule::AllowAll,
+                    AccessRule::DenyAll,
+                )
+                .method(
+                    "get_stability_deposit",
+                    AccessRule::AllowAll,
+                    AccessRule::DenyAll,
+                )
+                .method(
+                    "get_stability_pool_deposits",
+                    AccessRule::AllowAll,
+                    AccessRule::DenyAll,
+                )
                 .method("clear_bad_debt", AccessRule::AllowAll, AccessRule::DenyAll)
+                .method(
+                    "settle_system_debt",
+                    AccessRule::AllowAll,
+                    AccessRule::DenyAll,
+                )
+                .method(
+                    "start_debt_auction",
+                    AccessRule::AllowAll,
+                    AccessRule::DenyAll,
+                )
+                .method(
+                    "take_debt_auction",
+                    AccessRule::AllowAll,
+                    AccessRule::DenyAll,
+                )
+                .method(
+                    "reset_debt_auction",
+                    AccessRule::AllowAll,
+                    AccessRule::DenyAll,
+                )
+                .method(
+                    "start_surplus_auction",
+                    AccessRule::AllowAll,
+                    AccessRule::DenyAll,
+                )
+                .method(
+                    "take_surplus_auction",
+                    AccessRule::AllowAll,
+                    AccessRule::DenyAll,
+                )
+                .method(
+                    "reset_surplus_auction",
+                    AccessRule::AllowAll,
+                    AccessRule::DenyAll,
+                )
+                .method("get_system_debt", AccessRule::AllowAll, AccessRule::DenyAll)
+                .method(
+                    "get_debt_auction",
+                    AccessRule::AllowAll,
+                    AccessRule::DenyAll,
+                )
+                .method(
+                    "get_surplus_auction",
+                    AccessRule::AllowAll,
+                    AccessRule::DenyAll,
+                )
                 .method(
                     "burn_fully_liquidated_loans",
                     AccessRule::AllowAll,
                     AccessRule::DenyAll,
                 )
+                .method("deposit_savings", AccessRule::AllowAll, AccessRule::DenyAll)
+                .method(
+                    "withdraw_savings",
+                    AccessRule::AllowAll,
+                    AccessRule::DenyAll,
+                )
+                .method("drip_savings", AccessRule::AllowAll, AccessRule::DenyAll)
+                .method(
+                    "get_savings_state",
+                    AccessRule::AllowAll,
+                    AccessRule::DenyAll,
+                )
                 .method("flash_mint", AccessRule::AllowAll, AccessRule::DenyAll)
                 .method(
                     "repay_flash_mint",
                     AccessRule::AllowAll,
                     AccessRule::DenyAll,
                 )
+                .method("flash_loan", AccessRule::AllowAll, AccessRule::DenyAll)
+                .method("redeem", AccessRule::AllowAll, AccessRule::DenyAll)
+                .method(
+                    "repay_flash_loan",
+                    AccessRule::AllowAll,
+                    AccessRule::DenyAll,
+                )
                 .method("give_tokens", AccessRule::AllowAll, AccessRule::DenyAll)
+                .method("get_loan_health", AccessRule::AllowAll, AccessRule::DenyAll)
+                .method(
+                    "get_basket_loan_health",
+                    AccessRule::AllowAll,
+                    AccessRule::DenyAll,
+                )
+                .method(
+                    "get_loan_history",
+                    AccessRule::AllowAll,
+                    AccessRule::DenyAll,
+                )
+                .method(
+                    "get_basket_loan_history",
+                    AccessRule::AllowAll,
+                    AccessRule::DenyAll,
+                )
+                .method(
+                    "get_liquidatable_loans",
+                    AccessRule::AllowAll,
+                    AccessRule::DenyAll,
+                )
+                .method(
+                    "get_liquidatable_basket_loans",
+                    AccessRule::AllowAll,
+                    AccessRule::DenyAll,
+                )
+                .method("get_global_debt", AccessRule::AllowAll, AccessRule::DenyAll)
+                .method(
+                    "get_lender_totals",
+                    AccessRule::AllowAll,
+                    AccessRule::DenyAll,
+                )
+                .method(
+                    "get_protocol_state",
+                    AccessRule::AllowAll,
+                    AccessRule::DenyAll,
+                )
                 .method(
                     "get_lender_state",
                     AccessRule::AllowAll,
@@ -107,6 +463,41 @@ mod issuer {
                 flash_mint_address,
                 flash_mint_id: 0,
                 admin_badge,
+                basket_loan_address,
+                basket_loan_id: 0,
+                collateral_priority: Vec::new(),
+                stability_pool: StabilityPoolComponent::new(stablecoin_address),
+                stability_deposit_address,
+                stability_deposit_id: 0,
+                lender_debts: HashMap::new(),
+                lender_normalised_debts: HashMap::new(),
+                lender_collaterals: HashMap::new(),
+                debt_ceilings: HashMap::new(),
+                total_debt: Decimal::ZERO,
+                global_debt_ceiling: Decimal::MAX,
+                system_debt: Decimal::ZERO,
+                surplus_buffer: Decimal::MAX,
+                reserve_claim_address,
+                reserve_claim_price: Decimal::ONE,
+                debt_auction: None,
+                surplus_auction: None,
+                flash_mint_fee: DEFAULT_FLASH_MINT_FEE,
+                flash_mint_max: Decimal::MAX,
+                flash_loan_address,
+                flash_loan_id: 0,
+                flash_loan_fees: HashMap::new(),
+                redemption_fee: DEFAULT_REDEMPTION_FEE,
+                savings_vault: SavingsVaultComponent::new(stablecoin_address),
+                savings_share_address,
+                loan_histories: KeyValueStore::new(),
+                open_loans: HashMap::new(),
+                basket_loan_histories: KeyValueStore::new(),
+                system_auction_duration: DEFAULT_AUCTION_DURATION,
+                system_auction_start_premium: DEFAULT_AUCTION_START_PREMIUM,
+                open_basket_loans: HashSet::new(),
+                reserves_locked: false,
+                psms: Vec::new(),
+                minter_registries: Vec::new(),
             }
             .instantiate();
 
@@ -121,7 +512,9 @@ mod issuer {
             loan_to_value: Decimal,
             interest_rate: Decimal,
             liquidation_threshold: Decimal,
+            liquidator_bonus: Decimal,
             protocol_liquidation_share: Decimal,
+            close_factor: Decimal,
             oracle: ComponentAddress,
         ) {
             assert!(
@@ -134,11 +527,21 @@ mod issuer {
                 loan_to_value,
                 interest_rate,
                 liquidation_threshold,
+                liquidator_bonus,
                 protocol_liquidation_share,
+                close_factor,
                 oracle,
             );
 
             self.lenders.insert(collateral_address.clone(), new_lender);
+            self.lender_debts.insert(collateral_address, Decimal::ZERO);
+            self.lender_normalised_debts
+                .insert(collateral_address, Decimal::ZERO);
+            self.lender_collaterals
+                .insert(collateral_address, Decimal::ZERO);
+            self.debt_ceilings.insert(collateral_address, Decimal::MAX);
+            self.open_loans.insert(collateral_address, BTreeSet::new());
+            self.collateral_priority.push(collateral_address);
         }
 
         pub fn take_loan(
@@ -146,11 +549,30 @@ mod issuer {
             collateral: Bucket,
             amount_to_loan: Decimal,
         ) -> (Bucket, Bucket) {
+            self.increase_debt(collateral.resource_address(), amount_to_loan);
+
             let lender = self.get_lender(&collateral.resource_address());
             let loan = lender.take_loan(collateral, amount_to_loan);
+            self.update_lender_totals(
+                loan.collateral_token,
+                loan.normalised_debt,
+                loan.collateral_amount,
+            );
+
+            let loan_id = NonFungibleLocalId::Integer(self.loan_id.into());
+            self.log_loan_event(
+                &loan_id,
+                LoanEventKind::Open,
+                loan.collateral_amount,
+                amount_to_loan,
+                amount_to_loan,
+            );
+            self.open_loans
+                .get_mut(&loan.collateral_token)
+                .unwrap()
+                .insert((Self::redemption_order(&loan), loan_id.clone()));
             let loan_bucket = self.resource_minter.authorize(|| {
-                borrow_resource_manager!(self.loan_address)
-                    .mint_non_fungible(&NonFungibleLocalId::Integer(self.loan_id.into()), loan)
+                borrow_resource_manager!(self.loan_address).mint_non_fungible(&loan_id, loan)
             });
 
             self.loan_id += 1;
@@ -177,64 +599,169 @@ mod issuer {
             let mut stablecoins_to_burn = Bucket::new(self.stablecoin_address);
             for loan_nfr in loans.non_fungibles::<Loan>() {
                 let loan = self.get_loan_data(&loan_nfr);
+                self.assert_not_auctioned(loan_nfr.local_id(), &loan.collateral_token);
                 let lender = self.lenders.get(&loan.collateral_token).unwrap();
+                let collateral_token = loan.collateral_token;
                 let amount_lent = loan.amount_lent;
+                let normalised_debt = loan.normalised_debt;
+                let collateral_amount = loan.collateral_amount;
 
                 let (interests, collateral) = lender.repay_loan(repayment.amount(), loan);
 
+                self.decrease_debt(collateral_token, amount_lent);
+                self.update_lender_totals(collateral_token, -normalised_debt, -collateral_amount);
+                self.log_loan_event(
+                    loan_nfr.local_id(),
+                    LoanEventKind::Closing,
+                    -collateral_amount,
+                    -amount_lent,
+                    interests + amount_lent,
+                );
                 self.put_in_reserves(repayment.take(interests));
                 stablecoins_to_burn.put(repayment.take(amount_lent));
                 buckets.push(collateral);
             }
 
             self.burn_bucket(stablecoins_to_burn);
-            self.resource_minter
-                .authorize(|| borrow_resource_manager!(self.loan_address).burn(loans));
+            self.burn_loans(loans);
 
             (repayment, buckets)
         }
 
-        pub fn add_collateral(&mut self, collateral: Bucket, loan_proof: Proof) {
+        pub fn repay_partial(&mut self, loan_proof: Proof, mut repayment: Bucket) -> Bucket {
+            assert!(
+                repayment.resource_address() == self.stablecoin_address,
+                "Repayment should be provided in stablecoins tokens"
+            );
+
             let valid_proof = self.check_single_loan_proof(loan_proof);
 
             let loan_nfr = valid_proof.non_fungible::<Loan>();
             let loan = self.get_loan_data(&loan_nfr);
+            self.assert_not_auctioned(loan_nfr.local_id(), &loan.collateral_token);
 
-            let lender = self.get_lender(&loan.collateral_token);
+            let collateral_token = loan.collateral_token;
+            let lender = self.get_lender(&collateral_token);
 
-            let new_loan_data = lender.add_collateral(collateral, loan);
+            let (interests, principal, new_loan_data) =
+                lender.repay_partial(repayment.amount(), loan.clone());
+
+            self.decrease_debt(collateral_token, principal);
+            self.record_loan_update(loan_nfr.local_id(), &loan, &new_loan_data);
+            self.log_loan_event(
+                loan_nfr.local_id(),
+                LoanEventKind::Repayment,
+                Decimal::ZERO,
+                -principal,
+                interests + principal,
+            );
+            self.put_in_reserves(repayment.take(interests));
+            self.burn_bucket(repayment.take(principal));
             self.update_loan_data(loan_nfr, new_loan_data);
+
+            repayment
         }
 
-        pub fn remove_collateral(&mut self, amount: Decimal, loan_proof: Proof) -> Bucket {
+        pub fn borrow_more(&mut self, loan_proof: Proof, amount: Decimal) -> Bucket {
+            assert!(
+                amount.is_positive(),
+                "The amount to borrow should be positive"
+            );
+
             let valid_proof = self.check_single_loan_proof(loan_proof);
 
             let loan_nfr = valid_proof.non_fungible::<Loan>();
             let loan = self.get_loan_data(&loan_nfr);
+            self.assert_not_auctioned(loan_nfr.local_id(), &loan.collateral_token);
+
+            self.increase_debt(loan.collateral_token, amount);
 
             let lender = self.get_lender(&loan.collateral_token);
 
-            let (new_loan_data, collateral) = lender.remove_collateral(amount, loan);
+            let new_loan_data = lender.borrow_more(amount, loan.clone());
+            self.record_loan_update(loan_nfr.local_id(), &loan, &new_loan_data);
+            self.log_loan_event(
+                loan_nfr.local_id(),
+                LoanEventKind::BorrowMore,
+                Decimal::ZERO,
+                amount,
+                amount,
+            );
             self.update_loan_data(loan_nfr, new_loan_data);
 
-            collateral
+            self.mint(amount)
         }
 
-        pub fn liquidate(
-            &mut self,
-            mut repayment: Bucket,
-            loan_id: NonFungibleLocalId,
-        ) -> (Bucket, Bucket) {
-            let loan: Loan =
-                borrow_resource_manager!(self.loan_address).get_non_fungible_data(&loan_id);
+        pub fn add_collateral(&mut self, collateral: Bucket, loan_proof: Proof) {
+            let valid_proof = self.check_single_loan_proof(loan_proof);
 
-            let lender = self.get_lender(&loan.collateral_token);
+            let loan_nfr = valid_proof.non_fungible::<Loan>();
+            let loan = self.get_loan_data(&loan_nfr);
+            self.assert_not_auctioned(loan_nfr.local_id(), &loan.collateral_token);
 
-            let (amount_to_burn, liquidator_bucket, reserve_bucket, new_loan_data) =
-                lender.liquidate(repayment.amount(), loan);
+            let lender = self.get_lender(&loan.collateral_token);
 
-            let bucket_to_burn = repayment.take(amount_to_burn);
-            self.burn_bucket(bucket_to_burn);
+            let new_loan_data = lender.add_collateral(collateral, loan.clone());
+            self.record_loan_update(loan_nfr.local_id(), &loan, &new_loan_data);
+            self.log_loan_event(
+                loan_nfr.local_id(),
+                LoanEventKind::AddCollateral,
+                new_loan_data.collateral_amount - loan.collateral_amount,
+                Decimal::ZERO,
+                Decimal::ZERO,
+            );
+            self.update_loan_data(loan_nfr, new_loan_data);
+        }
+
+        pub fn remove_collateral(&mut self, amount: Decimal, loan_proof: Proof) -> Bucket {
+            let valid_proof = self.check_single_loan_proof(loan_proof);
+
+            let loan_nfr = valid_proof.non_fungible::<Loan>();
+            let loan = self.get_loan_data(&loan_nfr);
+            self.assert_not_auctioned(loan_nfr.local_id(), &loan.collateral_token);
+
+            let lender = self.get_lender(&loan.collateral_token);
+
+            let (new_loan_data, collateral) = lender.remove_collateral(amount, loan.clone());
+            self.record_loan_update(loan_nfr.local_id(), &loan, &new_loan_data);
+            self.log_loan_event(
+                loan_nfr.local_id(),
+                LoanEventKind::RemoveCollateral,
+                -amount,
+                Decimal::ZERO,
+                Decimal::ZERO,
+            );
+            self.update_loan_data(loan_nfr, new_loan_data);
+
+            collateral
+        }
+
+        pub fn liquidate(
+            &mut self,
+            mut repayment: Bucket,
+            loan_id: NonFungibleLocalId,
+        ) -> (Bucket, Bucket) {
+            let loan: Loan =
+                borrow_resource_manager!(self.loan_address).get_non_fungible_data(&loan_id);
+
+            let collateral_token = loan.collateral_token;
+            let lender = self.get_lender(&collateral_token);
+
+            let (interests, principal, liquidator_bucket, reserve_bucket, new_loan_data) =
+                lender.liquidate(repayment.amount(), loan.clone());
+
+            self.decrease_debt(collateral_token, principal);
+            self.record_loan_update(&loan_id, &loan, &new_loan_data);
+            self.log_loan_event(
+                &loan_id,
+                LoanEventKind::Liquidation,
+                new_loan_data.collateral_amount - loan.collateral_amount,
+                new_loan_data.amount_lent - loan.amount_lent,
+                interests + principal,
+            );
+            self.put_in_reserves(repayment.take(interests));
+            let bucket_to_burn = repayment.take(principal);
+            self.burn_bucket(bucket_to_burn);
 
             match reserve_bucket {
                 Some(buck) => self.put_in_reserves(buck),
@@ -249,50 +776,1145 @@ mod issuer {
             (repayment, liquidator_bucket)
         }
 
+        pub fn start_auction(&mut self, loan_id: NonFungibleLocalId) -> Bucket {
+            let loan: Loan =
+                borrow_resource_manager!(self.loan_address).get_non_fungible_data(&loan_id);
+            let lender = self.get_lender(&loan.collateral_token);
+
+            let (incentive_bucket, new_loan_data) =
+                lender.start_auction(loan_id.clone(), loan.clone());
+            self.record_loan_update(&loan_id, &loan, &new_loan_data);
+            self.log_loan_event(
+                &loan_id,
+                LoanEventKind::AuctionStart,
+                -loan.collateral_amount,
+                -loan.amount_lent,
+                Decimal::ZERO,
+            );
+            self.update_loan_data_from_id(&loan_id, new_loan_data);
+
+            incentive_bucket
+        }
+
+        pub fn take_auction(
+            &mut self,
+            mut repayment: Bucket,
+            loan_id: NonFungibleLocalId,
+        ) -> (Bucket, Bucket) {
+            assert!(
+                repayment.resource_address() == self.stablecoin_address,
+                "Repayment should be provided in stablecoins tokens"
+            );
+
+            let loan: Loan =
+                borrow_resource_manager!(self.loan_address).get_non_fungible_data(&loan_id);
+            let lender = self.get_lender(&loan.collateral_token);
+
+            let (interests, principal, collateral_bucket, collateral_left, bad_debt) =
+                lender.take_auction(loan_id.clone(), repayment.amount());
+
+            self.decrease_debt(loan.collateral_token, principal + bad_debt);
+            self.put_in_reserves(repayment.take(interests));
+            self.burn_bucket(repayment.take(principal));
+
+            // The principal that the auction could not raise becomes system debt
+            if bad_debt.is_positive() {
+                self.system_debt += bad_debt;
+                self.settle_system_debt();
+            }
+            self.log_loan_event(
+                &loan_id,
+                LoanEventKind::AuctionSale,
+                collateral_left,
+                Decimal::ZERO,
+                interests + principal,
+            );
+
+            // Give the collateral that was not sold back to the loan owner
+            if collateral_left.is_positive() {
+                let mut new_loan_data = loan.clone();
+                new_loan_data.collateral_amount += collateral_left;
+                self.record_loan_update(&loan_id, &loan, &new_loan_data);
+                self.update_loan_data_from_id(&loan_id, new_loan_data);
+            }
+
+            (repayment, collateral_bucket)
+        }
+
+        pub fn reset_auction(&mut self, loan_id: NonFungibleLocalId) {
+            let loan: Loan =
+                borrow_resource_manager!(self.loan_address).get_non_fungible_data(&loan_id);
+            let lender = self.get_lender(&loan.collateral_token);
+            lender.reset_auction(loan_id);
+        }
+
+        pub fn get_auction(&self, loan_id: NonFungibleLocalId) -> (Decimal, Decimal, Decimal) {
+            let loan: Loan =
+                borrow_resource_manager!(self.loan_address).get_non_fungible_data(&loan_id);
+            let lender = self.get_lender(&loan.collateral_token);
+            lender.get_auction(loan_id)
+        }
+
         pub fn clear_bad_debt(&mut self, loan_id: NonFungibleLocalId) {
+            self.assert_reserves_unlocked();
+
             // Get the information about the bad debt from the lender
             let loan: Loan =
                 borrow_resource_manager!(self.loan_address).get_non_fungible_data(&loan_id);
-            let lender = self.get_lender(&loan.collateral_token);
-            let (amount_to_clear, collateral, new_loan_data) = lender.clear_bad_debt(loan);
+            let collateral_token = loan.collateral_token;
+            let lender = self.get_lender(&collateral_token);
+            let (amount_to_clear, collateral, new_loan_data) = lender.clear_bad_debt(loan.clone());
+            self.decrease_debt(collateral_token, amount_to_clear);
+            self.record_loan_update(&loan_id, &loan, &new_loan_data);
+            self.log_loan_event(
+                &loan_id,
+                LoanEventKind::BadDebtClearing,
+                -loan.collateral_amount,
+                -loan.amount_lent,
+                Decimal::ZERO,
+            );
+
+            // Try to repay bad debt from reserves and then from the stability pool
+            if self.stablecoin_reserves() >= amount_to_clear {
+                let reserves_input = self
+                    .reserves
+                    .get_mut(&self.stablecoin_address)
+                    .unwrap()
+                    .take(amount_to_clear);
+                self.burn_bucket(reserves_input);
+                self.put_in_reserves(collateral);
+            } else if self.stability_pool.get_total_deposits() >= amount_to_clear {
+                let pool_input = self.stability_pool.absorb(amount_to_clear, collateral);
+                self.burn_bucket(pool_input);
+            } else {
+                // Otherwise the debt becomes system debt, repaid by the reserves as they grow or
+                // by debt auctions
+                self.put_in_reserves(collateral);
+                self.system_debt += amount_to_clear;
+                self.settle_system_debt();
+            }
+
+            self.update_loan_data_from_id(&loan_id, new_loan_data);
+        }
+
+        /// Redeems stablecoins against the loans of a given lender with the lowest
+        /// collateralization ratio, at the oracle price minus the redemption fee. At most
+        /// [`MAX_REDEEMED_LOANS`] loans are scanned. Returns the collateral and the stablecoins
+        /// that could not be redeemed.
+        pub fn redeem(
+            &mut self,
+            mut stablecoins: Bucket,
+            collateral_token: ResourceAddress,
+        ) -> (Bucket, Bucket) {
+            assert!(
+                stablecoins.resource_address() == self.stablecoin_address,
+                "Please provide stablecoins to redeem"
+            );
+
+            let loans = self.get_redeemable_loans(collateral_token);
+            let lender = self.get_lender(&collateral_token);
+            let (interests, principal, mut collateral, redeemed_loans) =
+                lender.redeem(stablecoins.amount(), loans);
+
+            assert!(
+                !redeemed_loans.is_empty(),
+                "There are no loans to redeem stablecoins against"
+            );
+
+            for (loan_id, loan, new_loan_data, repayment) in redeemed_loans {
+                self.record_loan_update(&loan_id, &loan, &new_loan_data);
+                self.log_loan_event(
+                    &loan_id,
+                    LoanEventKind::Redemption,
+                    new_loan_data.collateral_amount - loan.collateral_amount,
+                    new_loan_data.amount_lent - loan.amount_lent,
+                    repayment,
+                );
+                self.update_loan_data_from_id(&loan_id, new_loan_data);
+            }
+
+            self.decrease_debt(collateral_token, principal);
+            self.put_in_reserves(stablecoins.take(interests));
+            self.burn_bucket(stablecoins.take(principal));
+
+            let fee = collateral.amount() * self.redemption_fee;
+            self.put_in_reserves(collateral.take(fee));
+
+            (collateral, stablecoins)
+        }
+
+        /// Burns stablecoin reserves to repay the system debt and returns the system debt left
+        pub fn settle_system_debt(&mut self) -> Decimal {
+            self.assert_reserves_unlocked();
+
+            let settled = self.stablecoin_reserves().min(self.system_debt);
+            if settled.is_positive() {
+                let reserves_input = self
+                    .reserves
+                    .get_mut(&self.stablecoin_address)
+                    .unwrap()
+                    .take(settled);
+                self.burn_bucket(reserves_input);
+                self.system_debt -= settled;
+            }
+
+            // A running debt auction cannot raise more than the system debt left
+            let system_debt = self.system_debt;
+            let auction_over = match self.debt_auction.as_mut() {
+                Some(auction) => {
+                    auction.amount = auction.amount.min(system_debt);
+                    auction.amount.is_zero()
+                }
+                None => false,
+            };
+            if auction_over {
+                self.debt_auction = None;
+            }
+
+            self.system_debt
+        }
+
+        /// Starts an auction of reserve claims against stablecoins to repay the system debt left
+        /// after settling it with the reserves
+        pub fn start_debt_auction(&mut self) {
+            assert!(
+                self.debt_auction.is_none(),
+                "A debt auction is already running"
+            );
+            assert!(
+                self.surplus_auction.is_none(),
+                "The system debt cannot be auctioned while a surplus auction is running"
+            );
+
+            let system_debt = self.settle_system_debt();
+            assert!(
+                system_debt.is_positive(),
+                "There is no system debt to auction"
+            );
+
+            let current_time = Clock::current_time(TimePrecision::Minute).seconds_since_unix_epoch;
+            self.debt_auction = Some(SystemAuction::new(
+                system_debt,
+                self.reserve_claim_price * self.system_auction_start_premium,
+                current_time,
+            ));
+        }
+
+        /// Buys reserve claims from the running debt auction at its current price. The stablecoins
+        /// paid are burned to repay the system debt.
+        pub fn take_debt_auction(&mut self, mut stablecoins: Bucket) -> (Bucket, Bucket) {
+            self.assert_reserves_unlocked();
+
+            assert!(
+                stablecoins.resource_address() == self.stablecoin_address,
+                "Please provide stablecoins to buy reserve claims"
+            );
+
+            let current_time = Clock::current_time(TimePrecision::Minute).seconds_since_unix_epoch;
+            let auction = match self.debt_auction.as_mut() {
+                Some(auction) => auction,
+                None => panic!("There is no debt auction running"),
+            };
+
+            assert!(
+                !auction.has_expired(current_time, self.system_auction_duration),
+                "This auction has expired and should be reset"
+            );
+
+            let price = auction.price_at(current_time, self.system_auction_duration);
+            let paid = stablecoins.amount().min(auction.amount);
+            auction.amount -= paid;
+            if auction.amount.is_zero() {
+                self.debt_auction = None;
+            }
+
+            self.system_debt -= paid;
+            self.reserve_claim_price = price;
+            self.burn_bucket(stablecoins.take(paid));
+
+            let claims = self.resource_minter.authorize(|| {
+                borrow_resource_manager!(self.reserve_claim_address).mint(paid / price)
+            });
+
+            (claims, stablecoins)
+        }
+
+        /// Restarts an expired debt auction from the last price of reserve claims
+        pub fn reset_debt_auction(&mut self) {
+            let current_time = Clock::current_time(TimePrecision::Minute).seconds_since_unix_epoch;
+            let start_price = self.reserve_claim_price * self.system_auction_start_premium;
+            let auction = match self.debt_auction.as_mut() {
+                Some(auction) => auction,
+                None => panic!("There is no debt auction running"),
+            };
+
+            assert!(
+                auction.has_expired(current_time, self.system_auction_duration),
+                "This auction has not expired yet"
+            );
+
+            auction.start_price = start_price;
+            auction.start_time = current_time;
+        }
+
+        /// Starts an auction of the stablecoin reserves exceeding the surplus buffer against
+        /// reserve claims
+        pub fn start_surplus_auction(&mut self) {
+            assert!(
+                self.surplus_auction.is_none(),
+                "A surplus auction is already running"
+            );
+            assert!(
+                self.debt_auction.is_none(),
+                "The surplus cannot be auctioned while a debt auction is running"
+            );
+
+            let system_debt = self.settle_system_debt();
+            assert!(
+                system_debt.is_zero(),
+                "The system debt should be repaid before auctioning the surplus"
+            );
+
+            let surplus = self.stablecoin_reserves() - self.surplus_buffer;
+            assert!(surplus.is_positive(), "There is no surplus to auction");
+
+            // The price of the surplus is expressed in reserve claims per stablecoin
+            let current_time = Clock::current_time(TimePrecision::Minute).seconds_since_unix_epoch;
+            self.surplus_auction = Some(SystemAuction::new(
+                surplus,
+                self.system_auction_start_premium / self.reserve_claim_price,
+                current_time,
+            ));
+        }
+
+        /// Buys stablecoins from the running surplus auction at its current price. The reserve
+        /// claims paid are burned.
+        pub fn take_surplus_auction(&mut self, mut claims: Bucket) -> (Bucket, Bucket) {
+            assert!(
+                claims.resource_address() == self.reserve_claim_address,
+                "Please provide reserve claims to buy the surplus"
+            );
+
+            let system_debt = self.settle_system_debt();
+            assert!(
+                system_debt.is_zero(),
+                "The system debt should be repaid before auctioning the surplus"
+            );
+
+            // The surplus auction cannot take the stablecoin reserves below the surplus buffer
+            let surplus = (self.stablecoin_reserves() - self.surplus_buffer).max(Decimal::ZERO);
+            assert!(surplus.is_positive(), "There is no surplus to auction");
+
+            let current_time = Clock::current_time(TimePrecision::Minute).seconds_since_unix_epoch;
+            let auction = match self.surplus_auction.as_mut() {
+                Some(auction) => auction,
+                None => panic!("There is no surplus auction running"),
+            };
+
+            assert!(
+                !auction.has_expired(current_time, self.system_auction_duration),
+                "This auction has expired and should be reset"
+            );
+
+            let price = auction.price_at(current_time, self.system_auction_duration);
+            let bought = (claims.amount() / price).min(auction.amount).min(surplus);
+            auction.amount -= bought;
+            if auction.amount.is_zero() {
+                self.surplus_auction = None;
+            }
+
+            self.reserve_claim_price = Decimal::ONE / price;
+            let claims_to_burn = claims.take(bought * price);
+            self.resource_minter.authorize(|| {
+                borrow_resource_manager!(self.reserve_claim_address).burn(claims_to_burn)
+            });
+
+            let surplus = self
+                .reserves
+                .get_mut(&self.stablecoin_address)
+                .unwrap()
+                .take(bought);
+
+            (surplus, claims)
+        }
+
+        /// Restarts an expired surplus auction from the last price of reserve claims
+        pub fn reset_surplus_auction(&mut self) {
+            let current_time = Clock::current_time(TimePrecision::Minute).seconds_since_unix_epoch;
+            let start_price = self.system_auction_start_premium / self.reserve_claim_price;
+            let auction = match self.surplus_auction.as_mut() {
+                Some(auction) => auction,
+                None => panic!("There is no surplus auction running"),
+            };
+
+            assert!(
+                auction.has_expired(current_time, self.system_auction_duration),
+                "This auction has not expired yet"
+            );
+
+            auction.start_price = start_price;
+            auction.start_time = current_time;
+        }
+
+        /// Returns the system debt, the surplus buffer and the last price of reserve claims
+        pub fn get_system_debt(&self) -> (Decimal, Decimal, Decimal) {
+            (
+                self.system_debt,
+                self.surplus_buffer,
+                self.reserve_claim_price,
+            )
+        }
+
+        /// Returns the stablecoins left to raise and the current price of reserve claims of the
+        /// running debt auction
+        pub fn get_debt_auction(&self) -> (Decimal, Decimal) {
+            let current_time = Clock::current_time(TimePrecision::Minute).seconds_since_unix_epoch;
+            match &self.debt_auction {
+                Some(auction) => (
+                    auction.amount,
+                    auction.price_at(current_time, self.system_auction_duration),
+                ),
+                None => panic!("There is no debt auction running"),
+            }
+        }
+
+        /// Returns the stablecoins left to sell and their current price in reserve claims of the
+        /// running surplus auction
+        pub fn get_surplus_auction(&self) -> (Decimal, Decimal) {
+            let current_time = Clock::current_time(TimePrecision::Minute).seconds_since_unix_epoch;
+            match &self.surplus_auction {
+                Some(auction) => (
+                    auction.amount,
+                    auction.price_at(current_time, self.system_auction_duration),
+                ),
+                None => panic!("There is no surplus auction running"),
+            }
+        }
+
+        pub fn deposit_in_stability_pool(&mut self, stablecoins: Bucket) -> Bucket {
+            assert!(
+                stablecoins.resource_address() == self.stablecoin_address,
+                "Please provide stablecoins to deposit"
+            );
+
+            let deposit = self.stability_pool.deposit(stablecoins);
+            let deposit_bucket = self.resource_minter.authorize(|| {
+                borrow_resource_manager!(self.stability_deposit_address).mint_non_fungible(
+                    &NonFungibleLocalId::Integer(self.stability_deposit_id.into()),
+                    deposit,
+                )
+            });
+            self.stability_deposit_id += 1;
+
+            deposit_bucket
+        }
+
+        pub fn withdraw_from_stability_pool(
+            &mut self,
+            deposit_proof: Proof,
+            amount: Decimal,
+        ) -> (Bucket, Vec<Bucket>) {
+            let deposit_nfr = self.check_single_deposit_proof(deposit_proof);
+            let deposit: StabilityDeposit =
+                borrow_resource_manager!(self.stability_deposit_address)
+                    .get_non_fungible_data(deposit_nfr.local_id());
+
+            let (stablecoins, gains, new_deposit) = self.stability_pool.withdraw(amount, deposit);
+            self.resource_minter
+                .authorize(|| deposit_nfr.update_data(new_deposit));
+
+            (stablecoins, gains)
+        }
+
+        pub fn claim_stability_pool_gains(&mut self, deposit_proof: Proof) -> Vec<Bucket> {
+            let deposit_nfr = self.check_single_deposit_proof(deposit_proof);
+            let deposit: StabilityDeposit =
+                borrow_resource_manager!(self.stability_deposit_address)
+                    .get_non_fungible_data(deposit_nfr.local_id());
+
+            let (gains, new_deposit) = self.stability_pool.claim(deposit);
+            self.resource_minter
+                .authorize(|| deposit_nfr.update_data(new_deposit));
+
+            gains
+        }
+
+        pub fn liquidate_with_stability_pool(&mut self, loan_id: NonFungibleLocalId) {
+            let loan: Loan =
+                borrow_resource_manager!(self.loan_address).get_non_fungible_data(&loan_id);
+
+            let collateral_token = loan.collateral_token;
+            let lender = self.get_lender(&collateral_token);
+
+            let (interests, principal, collateral_bucket, reserve_bucket, new_loan_data) =
+                lender.liquidate(self.stability_pool.get_total_deposits(), loan.clone());
+            self.decrease_debt(collateral_token, principal);
+            self.record_loan_update(&loan_id, &loan, &new_loan_data);
+            self.log_loan_event(
+                &loan_id,
+                LoanEventKind::Liquidation,
+                new_loan_data.collateral_amount - loan.collateral_amount,
+                new_loan_data.amount_lent - loan.amount_lent,
+                interests + principal,
+            );
+
+            // The stability pool pays the debt and receives the seized collateral
+            let mut pool_input = self
+                .stability_pool
+                .absorb(interests + principal, collateral_bucket);
+            self.put_in_reserves(pool_input.take(interests));
+            self.burn_bucket(pool_input);
+
+            match reserve_bucket {
+                Some(buck) => self.put_in_reserves(buck),
+                None => {}
+            }
+
+            self.update_loan_data_from_id(&loan_id, new_loan_data);
+        }
+
+        pub fn get_stability_deposit(
+            &self,
+            deposit_id: NonFungibleLocalId,
+        ) -> (Decimal, HashMap<ResourceAddress, Decimal>) {
+            let deposit: StabilityDeposit =
+                borrow_resource_manager!(self.stability_deposit_address)
+                    .get_non_fungible_data(&deposit_id);
+            self.stability_pool.get_deposit_value(deposit)
+        }
+
+        pub fn get_stability_pool_deposits(&self) -> Decimal {
+            self.stability_pool.get_total_deposits()
+        }
+
+        pub fn take_basket_loan(
+            &mut self,
+            collaterals: Vec<Bucket>,
+            amount_to_loan: Decimal,
+        ) -> (Bucket, Bucket) {
+            assert!(
+                amount_to_loan.is_positive(),
+                "The amount to loan should be positive"
+            );
+
+            let current_time = Clock::current_time(TimePrecision::Minute).seconds_since_unix_epoch;
+            let mut loan = BasketLoan::new(current_time);
+
+            // Compute how much can be borrowed against each collateral token
+            let mut capacities: Vec<(ResourceAddress, Decimal, Decimal)> = Vec::new();
+            let mut total_capacity = Decimal::ZERO;
+            for collateral in collaterals {
+                let token = collateral.resource_address();
+                let lender = self.get_lender(&token);
+                let (price, loan_to_value, _, _) = lender.get_valuation();
+                let capacity = collateral.amount() * price * loan_to_value;
+
+                match capacities
+                    .iter_mut()
+                    .find(|(address, _, _)| *address == token)
+                {
+                    Some((_, token_capacity, _)) => *token_capacity += capacity,
+                    None => capacities.push((token, capacity, lender.get_rate_accumulator())),
+                }
+                total_capacity += capacity;
+
+                let new_amount = loan.collateral_amount(&token) + collateral.amount();
+                loan.collaterals.insert(token, new_amount);
+                let amount = collateral.amount();
+                lender.deposit_collateral(collateral);
+                self.update_lender_totals(token, Decimal::ZERO, amount);
+            }
+
+            assert!(
+                amount_to_loan <= total_capacity,
+                "You need to provide more collateral to loan {}",
+                amount_to_loan
+            );
+
+            // Split the debt between the lenders proportionally to the borrowing capacity of their
+            // collateral, the last one taking the rounding remainder
+            let mut amount_left = amount_to_loan;
+            let nb_tokens = capacities.len();
+            for (index, (token, capacity, rate_accumulator)) in capacities.into_iter().enumerate() {
+                let amount_lent = if index + 1 == nb_tokens {
+                    amount_left
+                } else {
+                    amount_to_loan * capacity / total_capacity
+                };
+                amount_left -= amount_lent;
+
+                let minimum_debt = self.get_lender(&token).get_minimum_debt();
+                assert!(
+                    amount_lent >= minimum_debt,
+                    "The amount to loan should be at least {}",
+                    minimum_debt
+                );
+
+                self.increase_debt(token, amount_lent);
+                self.update_lender_totals(token, amount_lent / rate_accumulator, Decimal::ZERO);
+                loan.amounts_lent.insert(token, amount_lent);
+                loan.normalised_debts
+                    .insert(token, amount_lent / rate_accumulator);
+            }
+
+            let loan_id = NonFungibleLocalId::Integer(self.basket_loan_id.into());
+            self.log_basket_loan_event(
+                &loan_id,
+                LoanEventKind::Open,
+                loan.collaterals.clone(),
+                loan.amounts_lent.clone(),
+                amount_to_loan,
+            );
+
+            self.open_basket_loans.insert(loan_id.clone());
+            let loan_bucket = self.resource_minter.authorize(|| {
+                borrow_resource_manager!(self.basket_loan_address).mint_non_fungible(&loan_id, loan)
+            });
+            self.basket_loan_id += 1;
+
+            let stablecoin_bucket = self.mint(amount_to_loan);
+            (stablecoin_bucket, loan_bucket)
+        }
+
+        pub fn repay_basket_loan(
+            &mut self,
+            mut repayment: Bucket,
+            loan: Bucket,
+        ) -> (Bucket, Vec<Bucket>) {
+            assert!(
+                loan.resource_address() == self.basket_loan_address
+                    && loan.amount() == Decimal::ONE,
+                "Please provide a single basket loan to repay"
+            );
+            assert!(
+                repayment.resource_address() == self.stablecoin_address,
+                "Repayment should be provided in stablecoins tokens"
+            );
+
+            let loan_id = loan.non_fungible::<BasketLoan>().local_id().clone();
+            let loan_data: BasketLoan =
+                borrow_resource_manager!(self.basket_loan_address).get_non_fungible_data(&loan_id);
+
+            let mut debt = Decimal::ZERO;
+            let mut principal = Decimal::ZERO;
+            for (token, normalised_debt) in &loan_data.normalised_debts {
+                let lender = self.get_lender(token);
+                let amount_lent = *loan_data.amounts_lent.get(token).unwrap();
+                debt += *normalised_debt * lender.get_rate_accumulator();
+                principal += amount_lent;
+                self.decrease_debt(*token, amount_lent);
+                self.update_lender_totals(*token, -*normalised_debt, Decimal::ZERO);
+            }
+
+            assert!(
+                repayment.amount() >= debt,
+                "You need to provide {} stablecoins to repay your loan",
+                debt
+            );
+
+            self.put_in_reserves(repayment.take(debt - principal));
+            self.burn_bucket(repayment.take(principal));
+
+            self.log_basket_loan_event(
+                &loan_id,
+                LoanEventKind::Closing,
+                loan_data
+                    .collaterals
+                    .iter()
+                    .map(|(token, amount)| (*token, -*amount))
+                    .collect(),
+                loan_data
+                    .amounts_lent
+                    .iter()
+                    .map(|(token, amount)| (*token, -*amount))
+                    .collect(),
+                debt,
+            );
+
+            let mut buckets: Vec<Bucket> = Vec::new();
+            for (token, amount) in loan_data.collaterals {
+                let lender = self.get_lender(&token);
+                buckets.push(lender.withdraw_collateral(amount));
+                self.update_lender_totals(token, Decimal::ZERO, -amount);
+            }
+
+            self.open_basket_loans.remove(&loan_id);
+            self.resource_minter
+                .authorize(|| borrow_resource_manager!(self.basket_loan_address).burn(loan));
+
+            (repayment, buckets)
+        }
+
+        pub fn add_basket_collateral(&mut self, collaterals: Vec<Bucket>, loan_proof: Proof) {
+            let valid_proof = loan_proof
+                .validate_proof(ProofValidationMode::ValidateContainsAmount(
+                    self.basket_loan_address,
+                    Decimal::ONE,
+                ))
+                .expect("Please provide a valid proof of a single basket loan");
+
+            let loan_nfr = valid_proof.non_fungible::<BasketLoan>();
+            let mut loan: BasketLoan = borrow_resource_manager!(self.basket_loan_address)
+                .get_non_fungible_data(loan_nfr.local_id());
+
+            let mut collateral_changes: HashMap<ResourceAddress, Decimal> = HashMap::new();
+            for collateral in collaterals {
+                let token = collateral.resource_address();
+                let new_amount = loan.collateral_amount(&token) + collateral.amount();
+                loan.collaterals.insert(token, new_amount);
+                *collateral_changes.entry(token).or_insert(Decimal::ZERO) += collateral.amount();
+                self.update_lender_totals(token, Decimal::ZERO, collateral.amount());
+                self.get_lender(&token).deposit_collateral(collateral);
+            }
+
+            self.log_basket_loan_event(
+                loan_nfr.local_id(),
+                LoanEventKind::AddCollateral,
+                collateral_changes,
+                HashMap::new(),
+                Decimal::ZERO,
+            );
+
+            self.resource_minter
+                .authorize(|| loan_nfr.update_data(loan));
+        }
+
+        /// Liquidates a basket loan following the rules of single collateral loans: a partial
+        /// liquidation cannot repay more than the smallest close factor of the lenders of the
+        /// loan, the whole debt is repaid if a partial liquidation would leave one of its debts
+        /// below the minimum debt of its lender and the loan is fully liquidated if its collateral
+        /// is worth less than its debt. Loans whose collateral is worth less than their principal
+        /// have bad debt to clear with `clear_basket_bad_debt` instead.
+        pub fn liquidate_basket_loan(
+            &mut self,
+            mut repayment: Bucket,
+            loan_id: NonFungibleLocalId,
+        ) -> (Bucket, Vec<Bucket>) {
+            assert!(
+                repayment.resource_address() == self.stablecoin_address,
+                "Repayment should be provided in stablecoins tokens"
+            );
+
+            let mut loan: BasketLoan =
+                borrow_resource_manager!(self.basket_loan_address).get_non_fungible_data(&loan_id);
+
+            // Value the collateral and the debt of the loan
+            let mut valuations: HashMap<ResourceAddress, (Decimal, Decimal)> = HashMap::new();
+            let mut collateral_value = Decimal::ZERO;
+            let mut threshold_value = Decimal::ZERO;
+            for (token, amount) in &loan.collaterals {
+                let (price, _, liquidation_threshold, liquidator_bonus) =
+                    self.get_lender(token).get_valuation();
+                collateral_value += *amount * price;
+                threshold_value += *amount * price * liquidation_threshold;
+                valuations.insert(*token, (price, liquidator_bonus));
+            }
+
+            let mut liquidation_parameters: HashMap<ResourceAddress, (Decimal, Decimal, Decimal)> =
+                HashMap::new();
+            let mut debt = Decimal::ZERO;
+            let mut principal = Decimal::ZERO;
+            let mut close_factor = Decimal::ONE;
+            for (token, normalised_debt) in &loan.normalised_debts {
+                let lender = self.get_lender(token);
+                let rate_accumulator = lender.get_rate_accumulator();
+                let (protocol_liquidation_share, token_close_factor, minimum_debt) =
+                    lender.get_liquidation_parameters();
+                debt += *normalised_debt * rate_accumulator;
+                principal += *loan.amounts_lent.get(token).unwrap();
+                close_factor = close_factor.min(token_close_factor);
+                liquidation_parameters.insert(
+                    *token,
+                    (rate_accumulator, protocol_liquidation_share, minimum_debt),
+                );
+            }
 
-            // Try to repay bad debt from reserves
-            match self.reserves.get_mut(&self.stablecoin_address) {
-                Some(vault) => {
-                    if vault.amount() < amount_to_clear {
-                        panic!("Not enough stablecoin reserves to clear debt");
+            assert!(debt.is_positive(), "This loan has no debt to liquidate");
+
+            // The loan can be liquidated if its collateralization ratio is below the liquidation
+            // threshold of its collateral, weighted by value
+            let collateralization_ratio = collateral_value / debt;
+            let liquidation_threshold = if collateral_value.is_zero() {
+                Decimal::ONE
+            } else {
+                threshold_value / collateral_value
+            };
+            assert!(
+                collateralization_ratio <= liquidation_threshold,
+                "Cannot liquidate this loan: the collateralization ratio is {} >= {}",
+                collateralization_ratio,
+                liquidation_threshold
+            );
+            assert!(
+                collateral_value >= principal,
+                "This loan has bad debt that should be cleared"
+            );
+
+            let mut buckets: Vec<Bucket> = Vec::new();
+            let mut interests_paid = Decimal::ZERO;
+            let mut principal_paid = Decimal::ZERO;
+            let mut principal_changes: HashMap<ResourceAddress, Decimal> = HashMap::new();
+
+            if collateralization_ratio < Decimal::ONE {
+                // As for single collateral loans, the loan is fully liquidated against its
+                // principal and the protocol only claims a share of the interests that the
+                // collateral can pay
+                assert!(
+                    repayment.amount() >= principal,
+                    "Please provide at least {} SUSD to liquidate this loan",
+                    principal
+                );
+
+                let mut protocol_interests = Decimal::ZERO;
+                for (token, normalised_debt) in loan.normalised_debts.iter_mut() {
+                    let (rate_accumulator, protocol_liquidation_share, _) =
+                        *liquidation_parameters.get(token).unwrap();
+                    let amount_lent = loan.amounts_lent.get_mut(token).unwrap();
+                    let interests =
+                        (*normalised_debt * rate_accumulator - *amount_lent).max(Decimal::ZERO);
+                    protocol_interests += protocol_liquidation_share * interests;
+
+                    principal_paid += *amount_lent;
+                    principal_changes.insert(*token, -*amount_lent);
+                    self.decrease_debt(*token, *amount_lent);
+                    self.update_lender_totals(*token, -*normalised_debt, Decimal::ZERO);
+                    *normalised_debt = Decimal::ZERO;
+                    *amount_lent = Decimal::ZERO;
+                }
+
+                // Each collateral token pays its share of the protocol interests
+                let interests = debt - principal;
+                let protocol_share = if interests.is_positive() && collateral_value.is_positive() {
+                    let claimable_interests = interests.min(collateral_value - principal);
+                    protocol_interests * claimable_interests / interests / collateral_value
+                } else {
+                    Decimal::ZERO
+                };
+                for (token, amount) in loan.collaterals.iter_mut() {
+                    if amount.is_zero() {
+                        continue;
+                    }
+                    let lender = self.get_lender(token);
+                    let protocol_amount = *amount * protocol_share;
+                    let protocol_bucket = lender.withdraw_collateral(protocol_amount);
+                    buckets.push(lender.withdraw_collateral(*amount - protocol_amount));
+                    self.put_in_reserves(protocol_bucket);
+                    self.update_lender_totals(*token, Decimal::ZERO, -*amount);
+                    *amount = Decimal::ZERO;
+                }
+            } else {
+                // A single liquidation cannot repay more than the close factor of the debt, unless
+                // it would leave one of the debts of the loan below the minimum debt of its lender,
+                // in which case the liquidator repays the whole debt
+                let partial_input = repayment.amount().min(debt * close_factor);
+                let leaves_dust = loan
+                    .normalised_debts
+                    .iter()
+                    .any(|(token, normalised_debt)| {
+                        let (rate_accumulator, _, minimum_debt) =
+                            *liquidation_parameters.get(token).unwrap();
+                        let amount_lent = *loan.amounts_lent.get(token).unwrap();
+                        let token_debt = *normalised_debt * rate_accumulator;
+                        let repaid = partial_input * token_debt / debt;
+                        let interests = repaid.min((token_debt - amount_lent).max(Decimal::ZERO));
+                        let principal_left = amount_lent - (repaid - interests).min(amount_lent);
+                        principal_left.is_positive() && principal_left < minimum_debt
+                    });
+
+                let input = if leaves_dust {
+                    assert!(
+                        repayment.amount() >= debt,
+                        "Please provide at least {} SUSD to liquidate this loan",
+                        debt
+                    );
+                    debt
+                } else {
+                    partial_input
+                };
+
+                // Seize the collateral following the collateral priority order, the liquidator
+                // earning the liquidator bonus of each seized token
+                let mut left_to_cover = input;
+                for token in &self.collateral_priority.clone() {
+                    let amount = loan.collateral_amount(token);
+                    if amount.is_zero() || left_to_cover.is_zero() {
+                        continue;
+                    }
+
+                    let (price, liquidator_bonus) = *valuations.get(token).unwrap();
+                    let seized =
+                        (left_to_cover * (Decimal::ONE + liquidator_bonus) / price).min(amount);
+                    left_to_cover -=
+                        (seized * price / (Decimal::ONE + liquidator_bonus)).min(left_to_cover);
+
+                    loan.collaterals.insert(*token, amount - seized);
+                    buckets.push(self.get_lender(token).withdraw_collateral(seized));
+                    self.update_lender_totals(*token, Decimal::ZERO, -seized);
+                }
+
+                // When the whole debt is repaid, the liquidator pays it even if the bonus could not
+                // be fully paid in collateral
+                let covered = if leaves_dust {
+                    debt
+                } else {
+                    input - left_to_cover
+                };
+
+                // Repay every debt proportionally to its size, interests first and then principal
+                for (token, normalised_debt) in loan.normalised_debts.iter_mut() {
+                    let (rate_accumulator, _, _) = *liquidation_parameters.get(token).unwrap();
+                    let amount_lent = loan.amounts_lent.get_mut(token).unwrap();
+                    let token_debt = *normalised_debt * rate_accumulator;
+                    let repaid = covered * token_debt / debt;
+
+                    let interests = repaid.min((token_debt - *amount_lent).max(Decimal::ZERO));
+                    let principal = (repaid - interests).min(*amount_lent);
+                    let old_normalised_debt = *normalised_debt;
+
+                    if principal == *amount_lent {
+                        *normalised_debt = Decimal::ZERO;
+                    } else {
+                        *normalised_debt -= repaid / rate_accumulator;
                     }
-                    let reserves_input = vault.take(amount_to_clear);
-                    self.burn_bucket(reserves_input);
+                    *amount_lent -= principal;
+
+                    interests_paid += interests;
+                    principal_paid += principal;
+                    principal_changes.insert(*token, -principal);
+                    self.decrease_debt(*token, principal);
+                    self.update_lender_totals(
+                        *token,
+                        *normalised_debt - old_normalised_debt,
+                        Decimal::ZERO,
+                    );
+                }
+            }
+
+            let mut collateral_changes: HashMap<ResourceAddress, Decimal> = HashMap::new();
+            for bucket in &buckets {
+                *collateral_changes
+                    .entry(bucket.resource_address())
+                    .or_insert(Decimal::ZERO) -= bucket.amount();
+            }
+
+            self.put_in_reserves(repayment.take(interests_paid));
+            self.burn_bucket(repayment.take(principal_paid));
+
+            self.log_basket_loan_event(
+                &loan_id,
+                LoanEventKind::Liquidation,
+                collateral_changes,
+                principal_changes,
+                interests_paid + principal_paid,
+            );
+
+            self.resource_minter.authorize(|| {
+                borrow_resource_manager!(self.basket_loan_address)
+                    .update_non_fungible_data(&loan_id, loan);
+            });
+
+            (repayment, buckets)
+        }
+
+        /// Clears the bad debt of a basket loan whose collateral is worth less than its principal.
+        /// As for single collateral loans, the principal is repaid by the reserves, then by the
+        /// stability pool whose depositors share the collateral, and otherwise becomes system
+        /// debt.
+        pub fn clear_basket_bad_debt(&mut self, loan_id: NonFungibleLocalId) {
+            self.assert_reserves_unlocked();
+
+            let mut loan: BasketLoan =
+                borrow_resource_manager!(self.basket_loan_address).get_non_fungible_data(&loan_id);
+
+            let mut collateral_value = Decimal::ZERO;
+            let mut prices: HashMap<ResourceAddress, Decimal> = HashMap::new();
+            for (token, amount) in &loan.collaterals {
+                let (price, _, _, _) = self.get_lender(token).get_valuation();
+                collateral_value += *amount * price;
+                prices.insert(*token, price);
+            }
 
-                    self.update_loan_data_from_id(&loan_id, new_loan_data);
+            let mut amount_to_clear = Decimal::ZERO;
+            for amount_lent in loan.amounts_lent.values() {
+                amount_to_clear += *amount_lent;
+            }
+
+            assert!(
+                collateral_value < amount_to_clear,
+                "There is no bad debt to clear!"
+            );
+
+            // Fully liquidate the loan, its interests being forgiven
+            let mut principal_changes: HashMap<ResourceAddress, Decimal> = HashMap::new();
+            for (token, normalised_debt) in loan.normalised_debts.iter_mut() {
+                let amount_lent = loan.amounts_lent.get_mut(token).unwrap();
+                principal_changes.insert(*token, -*amount_lent);
+                self.decrease_debt(*token, *amount_lent);
+                self.update_lender_totals(*token, -*normalised_debt, Decimal::ZERO);
+                *normalised_debt = Decimal::ZERO;
+                *amount_lent = Decimal::ZERO;
+            }
+
+            let mut collaterals: Vec<(Bucket, Decimal)> = Vec::new();
+            let mut collateral_changes: HashMap<ResourceAddress, Decimal> = HashMap::new();
+            for (token, amount) in loan.collaterals.iter_mut() {
+                if amount.is_zero() {
+                    continue;
+                }
+                let value = *amount * *prices.get(token).unwrap();
+                collaterals.push((self.get_lender(token).withdraw_collateral(*amount), value));
+                collateral_changes.insert(*token, -*amount);
+                self.update_lender_totals(*token, Decimal::ZERO, -*amount);
+                *amount = Decimal::ZERO;
+            }
+
+            self.log_basket_loan_event(
+                &loan_id,
+                LoanEventKind::BadDebtClearing,
+                collateral_changes,
+                principal_changes,
+                Decimal::ZERO,
+            );
+
+            if self.stablecoin_reserves() >= amount_to_clear {
+                let reserves_input = self
+                    .reserves
+                    .get_mut(&self.stablecoin_address)
+                    .unwrap()
+                    .take(amount_to_clear);
+                self.burn_bucket(reserves_input);
+                for (collateral, _) in collaterals {
                     self.put_in_reserves(collateral);
                 }
-                None => {
-                    panic!("Cannot clear debt");
+            } else if self.stability_pool.get_total_deposits() >= amount_to_clear
+                && collateral_value.is_positive()
+            {
+                // The pool absorbs the debt backed by each collateral token in proportion to its
+                // value, the last one taking the rounding remainder
+                let mut left_to_absorb = amount_to_clear;
+                let nb_collaterals = collaterals.len();
+                for (index, (collateral, value)) in collaterals.into_iter().enumerate() {
+                    let absorbed = if index + 1 == nb_collaterals {
+                        left_to_absorb
+                    } else {
+                        amount_to_clear * value / collateral_value
+                    };
+                    left_to_absorb -= absorbed;
+
+                    let pool_input = self.stability_pool.absorb(absorbed, collateral);
+                    self.burn_bucket(pool_input);
+                }
+            } else {
+                for (collateral, _) in collaterals {
+                    self.put_in_reserves(collateral);
                 }
+                self.system_debt += amount_to_clear;
+                self.settle_system_debt();
             }
+
+            self.resource_minter.authorize(|| {
+                borrow_resource_manager!(self.basket_loan_address)
+                    .update_non_fungible_data(&loan_id, loan);
+            });
+        }
+
+        pub fn burn_fully_liquidated_loans(&mut self, loans: Bucket) {
+            assert!(
+                loans.resource_address() == self.loan_address,
+                "Please provide loans to liquidate"
+            );
+
+            for loan in loans.non_fungibles::<Loan>() {
+                let loan_data = self.get_loan_data(&loan);
+
+                assert!(
+                    loan_data.amount_lent.is_zero()
+                        && loan_data.collateral_amount.is_zero()
+                        && !self
+                            .get_lender(&loan_data.collateral_token)
+                            .is_auctioned(loan.local_id().clone()),
+                    "One of the supplied loans is not fully liquidated"
+                );
+            }
+
+            self.burn_loans(loans);
+        }
+
+        /// Deposits stablecoins in the savings vault and returns savings shares
+        pub fn deposit_savings(&mut self, stablecoins: Bucket) -> Bucket {
+            assert!(
+                stablecoins.resource_address() == self.stablecoin_address,
+                "Please provide stablecoins to deposit"
+            );
+
+            self.drip_savings();
+            let shares = self.savings_vault.deposit(stablecoins);
+
+            self.resource_minter
+                .authorize(|| borrow_resource_manager!(self.savings_share_address).mint(shares))
+        }
+
+        /// Burns savings shares and returns the stablecoins they are worth
+        pub fn withdraw_savings(&mut self, shares: Bucket) -> Bucket {
+            assert!(
+                shares.resource_address() == self.savings_share_address,
+                "Please provide savings shares to withdraw"
+            );
+
+            self.drip_savings();
+            let stablecoins = self.savings_vault.withdraw(shares.amount());
+
+            self.resource_minter
+                .authorize(|| borrow_resource_manager!(self.savings_share_address).burn(shares));
+
+            stablecoins
         }
 
-        pub fn burn_fully_liquidated_loans(&self, loans: Bucket) {
-            assert!(
-                loans.resource_address() == self.loan_address,
-                "Please provide loans to liquidate"
-            );
+        /// Accrues the interests of the savings vault and funds them with the stablecoin
+        /// reserves. Interests that the reserves cannot fund are minted and recorded as system
+        /// debt. Returns the amount of stablecoins funded.
+        pub fn drip_savings(&mut self) -> Decimal {
+            self.assert_reserves_unlocked();
 
-            for loan in loans.non_fungibles::<Loan>() {
-                let loan_data = self.get_loan_data(&loan);
+            let interests = self.savings_vault.drip();
+            if interests.is_zero() {
+                return Decimal::ZERO;
+            }
 
-                assert!(
-                    loan_data.amount_lent.is_zero() && loan_data.collateral_amount.is_zero(),
-                    "One of the supplied loans is not fully liquidated"
-                );
+            let from_reserves = self.stablecoin_reserves().min(interests);
+            let mut funding = if from_reserves.is_positive() {
+                self.reserves
+                    .get_mut(&self.stablecoin_address)
+                    .unwrap()
+                    .take(from_reserves)
+            } else {
+                Bucket::new(self.stablecoin_address)
+            };
+
+            let shortfall = interests - from_reserves;
+            if shortfall.is_positive() {
+                funding.put(self.mint(shortfall));
+                self.system_debt += shortfall;
             }
 
-            self.burn_bucket(loans);
+            self.savings_vault.fund(funding);
+
+            interests
+        }
+
+        /// Returns the amount of stablecoins in the savings vault, the savings rate and the
+        /// current value of a savings share in stablecoins
+        pub fn get_savings_state(&self) -> (Decimal, Decimal, Decimal) {
+            self.savings_vault.get_state()
         }
 
         pub fn flash_mint(&mut self, amount_to_mint: Decimal) -> (Bucket, Bucket) {
+            assert!(
+                amount_to_mint <= self.flash_mint_max,
+                "Cannot flash mint more than {} stablecoins",
+                self.flash_mint_max
+            );
+
             let stablecoin_amount = self.mint(amount_to_mint);
 
             let flash_loan = self.resource_minter.authorize(|| {
@@ -325,7 +1947,8 @@ mod issuer {
             let flash_mint_data: FlashMint = borrow_resource_manager!(self.flash_mint_address)
                 .get_non_fungible_data(flash_mint_nfr.local_id());
 
-            let amount_due = flash_mint_data.amount_minted * FLASH_LOAN_FEE;
+            let fee = flash_mint_data.amount_minted * self.flash_mint_fee;
+            let amount_due = flash_mint_data.amount_minted + fee;
             assert!(
                 repayment.amount() >= amount_due,
                 "You did not provide enough stablecoins to repay the flash loan"
@@ -335,7 +1958,78 @@ mod issuer {
                 borrow_resource_manager!(self.flash_mint_address).burn(flash_mint_bucket);
             });
 
-            self.burn_bucket(repayment.take(amount_due));
+            // The minted stablecoins are burned and the fee goes to the reserves
+            self.burn_bucket(repayment.take(flash_mint_data.amount_minted));
+            self.put_in_reserves(repayment.take(fee));
+
+            repayment
+        }
+
+        /// Lends tokens from the reserves. The tokens are returned with a flash loan that cannot be
+        /// deposited and has to be repaid with a fee in the same transaction. The reserves cannot
+        /// be spent until the loan is repaid.
+        pub fn flash_loan(&mut self, token: ResourceAddress, amount: Decimal) -> (Bucket, Bucket) {
+            self.assert_reserves_unlocked();
+
+            assert!(
+                amount.is_positive(),
+                "The amount to lend should be positive"
+            );
+
+            let fee = amount
+                * *self
+                    .flash_loan_fees
+                    .get(&token)
+                    .unwrap_or(&DEFAULT_FLASH_LOAN_FEE);
+
+            let tokens = match self.reserves.get_mut(&token) {
+                Some(vault) if vault.amount() >= amount => vault.take(amount),
+                _ => panic!("The reserves do not hold enough tokens to lend {}", amount),
+            };
+
+            let flash_loan = self.resource_minter.authorize(|| {
+                borrow_resource_manager!(self.flash_loan_address).mint_non_fungible(
+                    &NonFungibleLocalId::Integer(self.flash_loan_id.into()),
+                    FlashLoan::new(token, amount, fee),
+                )
+            });
+            self.flash_loan_id += 1;
+            self.reserves_locked = true;
+
+            (tokens, flash_loan)
+        }
+
+        pub fn repay_flash_loan(
+            &mut self,
+            mut repayment: Bucket,
+            flash_loan_bucket: Bucket,
+        ) -> Bucket {
+            assert!(
+                flash_loan_bucket.resource_address() == self.flash_loan_address,
+                "Please provide a flash loan to repay"
+            );
+
+            let flash_loan_data: FlashLoan = borrow_resource_manager!(self.flash_loan_address)
+                .get_non_fungible_data(flash_loan_bucket.non_fungible::<FlashLoan>().local_id());
+
+            assert!(
+                repayment.resource_address() == flash_loan_data.token,
+                "Please provide repayment in the borrowed tokens"
+            );
+
+            let amount_due = flash_loan_data.amount + flash_loan_data.fee;
+            assert!(
+                repayment.amount() >= amount_due,
+                "You did not provide enough tokens to repay the flash loan"
+            );
+
+            self.resource_minter.authorize(|| {
+                borrow_resource_manager!(self.flash_loan_address).burn(flash_loan_bucket);
+            });
+
+            // The lent tokens and the fee go back to the reserves
+            self.put_in_reserves(repayment.take(amount_due));
+            self.reserves_locked = false;
 
             repayment
         }
@@ -347,6 +2041,8 @@ mod issuer {
         }
 
         pub fn take_tokens(&mut self, token: ResourceAddress, amount: Decimal) -> Bucket {
+            self.assert_reserves_unlocked();
+
             self.reserves.get_mut(&token).unwrap().take(amount)
         }
 
@@ -356,30 +2052,47 @@ mod issuer {
             loan_to_value: Decimal,
             interest_rate: Decimal,
             liquidation_threshold: Decimal,
-            liquidation_incentive: Decimal,
+            liquidator_bonus: Decimal,
+            protocol_liquidation_share: Decimal,
+            close_factor: Decimal,
         ) {
-            assert!(
-                loan_to_value.is_positive() && loan_to_value < Decimal::ONE,
-                "LTV should be such that 0<LTV<1"
+            let lender = self.get_lender(&lender_collateral);
+            lender.change_parameters(
+                loan_to_value,
+                interest_rate,
+                liquidation_threshold,
+                liquidator_bonus,
+                protocol_liquidation_share,
+                close_factor,
             );
+        }
+
+        pub fn change_lender_liquidation_mode(
+            &mut self,
+            lender_collateral: ResourceAddress,
+            use_auction_liquidation: bool,
+            auction_start_premium: Decimal,
+            auction_duration: i64,
+            kick_incentive: Decimal,
+        ) {
             assert!(
-                interest_rate.is_positive() && interest_rate < Decimal::ONE,
-                "The daily interest rate should be such that 0<DIR<1"
+                auction_start_premium >= Decimal::ONE,
+                "The auction start premium should be at least one"
             );
             assert!(
-                liquidation_threshold > Decimal::ONE,
-                "The liquidation threshold should be greater than one"
+                auction_duration > 0,
+                "The auction duration should be positive"
             );
             assert!(
-                liquidation_incentive.is_positive(),
-                "The liquidation incentive should be positive"
+                !kick_incentive.is_negative() && kick_incentive < Decimal::ONE,
+                "The kick incentive should be such that 0<=KI<1"
             );
             let lender = self.get_lender(&lender_collateral);
-            lender.change_parameters(
-                loan_to_value,
-                interest_rate,
-                liquidation_threshold,
-                liquidation_incentive,
+            lender.change_liquidation_mode(
+                use_auction_liquidation,
+                auction_start_premium,
+                auction_duration,
+                kick_incentive,
             );
         }
 
@@ -387,15 +2100,304 @@ mod issuer {
             &mut self,
             lender_collateral: ResourceAddress,
             oracle: ComponentAddress,
+            use_truncated_twap: bool,
+        ) {
+            let lender = self.get_lender(&lender_collateral);
+            lender.change_oracle(oracle, use_truncated_twap);
+        }
+
+        pub fn set_minimum_debt(
+            &mut self,
+            lender_collateral: ResourceAddress,
+            minimum_debt: Decimal,
         ) {
             let lender = self.get_lender(&lender_collateral);
-            AccessRules::new();
-            lender.change_oracle(oracle);
+            lender.change_minimum_debt(minimum_debt);
+        }
+
+        pub fn set_debt_ceiling(
+            &mut self,
+            lender_collateral: ResourceAddress,
+            debt_ceiling: Decimal,
+        ) {
+            assert!(
+                !debt_ceiling.is_negative(),
+                "The debt ceiling should be non negative"
+            );
+            self.get_lender(&lender_collateral);
+            self.debt_ceilings.insert(lender_collateral, debt_ceiling);
+        }
+
+        pub fn set_global_debt_ceiling(&mut self, global_debt_ceiling: Decimal) {
+            assert!(
+                !global_debt_ceiling.is_negative(),
+                "The debt ceiling should be non negative"
+            );
+            self.global_debt_ceiling = global_debt_ceiling;
+        }
+
+        pub fn set_flash_mint_parameters(
+            &mut self,
+            flash_mint_fee: Decimal,
+            flash_mint_max: Decimal,
+        ) {
+            assert!(
+                !flash_mint_fee.is_negative() && flash_mint_fee < Decimal::ONE,
+                "The flash mint fee should be such that 0<=FMF<1"
+            );
+            assert!(
+                !flash_mint_max.is_negative(),
+                "The maximum flash mint amount should be non negative"
+            );
+            self.flash_mint_fee = flash_mint_fee;
+            self.flash_mint_max = flash_mint_max;
+        }
+
+        pub fn set_flash_loan_fee(&mut self, token: ResourceAddress, flash_loan_fee: Decimal) {
+            assert!(
+                !flash_loan_fee.is_negative() && flash_loan_fee < Decimal::ONE,
+                "The flash loan fee should be such that 0<=FLF<1"
+            );
+            self.flash_loan_fees.insert(token, flash_loan_fee);
+        }
+
+        pub fn set_savings_rate(&mut self, savings_rate: Decimal) {
+            assert!(
+                !savings_rate.is_negative() && savings_rate < Decimal::ONE,
+                "The daily savings rate should be such that 0<=DSR<1"
+            );
+
+            // Accrue the interests at the previous rate before changing it
+            self.drip_savings();
+            self.savings_vault.set_savings_rate(savings_rate);
+        }
+
+        pub fn set_redemption_fee(&mut self, redemption_fee: Decimal) {
+            assert!(
+                !redemption_fee.is_negative() && redemption_fee < Decimal::ONE,
+                "The redemption fee should be such that 0<=RF<1"
+            );
+            self.redemption_fee = redemption_fee;
+        }
+
+        pub fn set_surplus_buffer(&mut self, surplus_buffer: Decimal) {
+            assert!(
+                !surplus_buffer.is_negative(),
+                "The surplus buffer should be non negative"
+            );
+            self.surplus_buffer = surplus_buffer;
+        }
+
+        pub fn set_system_auction_parameters(
+            &mut self,
+            auction_start_premium: Decimal,
+            auction_duration: i64,
+        ) {
+            assert!(
+                auction_start_premium >= Decimal::ONE,
+                "The auction start premium should be at least one"
+            );
+            assert!(
+                auction_duration > 0,
+                "The auction duration should be positive"
+            );
+            self.system_auction_start_premium = auction_start_premium;
+            self.system_auction_duration = auction_duration;
+        }
+
+        pub fn set_external_minters(
+            &mut self,
+            psms: Vec<ComponentAddress>,
+            minter_registries: Vec<ComponentAddress>,
+        ) {
+            self.psms = psms;
+            self.minter_registries = minter_registries;
+        }
+
+        pub fn set_collateral_priority(&mut self, collateral_priority: Vec<ResourceAddress>) {
+            assert!(
+                collateral_priority.len() == self.lenders.len()
+                    && collateral_priority
+                        .iter()
+                        .all(|token| self.lenders.contains_key(token))
+                    && collateral_priority.iter().collect::<HashSet<_>>().len()
+                        == collateral_priority.len(),
+                "The priority should contain every collateral token exactly once"
+            );
+
+            self.collateral_priority = collateral_priority;
+        }
+
+        pub fn get_loan_health(
+            &self,
+            loan_id: NonFungibleLocalId,
+        ) -> (Decimal, Decimal, Decimal, Decimal) {
+            let loan: Loan =
+                borrow_resource_manager!(self.loan_address).get_non_fungible_data(&loan_id);
+            let lender = self.get_lender(&loan.collateral_token);
+            lender.get_loan_health(loan)
+        }
+
+        /// Returns the events that changed the collateral or the debt of a loan, oldest first
+        pub fn get_loan_history(&self, loan_id: NonFungibleLocalId) -> Vec<LoanEvent> {
+            match self.loan_histories.get(&loan_id) {
+                Some(history) => (*history).clone(),
+                None => panic!("There is no history for this loan"),
+            }
+        }
+
+        /// Returns the events that changed the collaterals or the debts of a basket loan, oldest
+        /// first
+        pub fn get_basket_loan_history(&self, loan_id: NonFungibleLocalId) -> Vec<BasketLoanEvent> {
+            match self.basket_loan_histories.get(&loan_id) {
+                Some(history) => (*history).clone(),
+                None => panic!("There is no history for this basket loan"),
+            }
+        }
+
+        pub fn get_liquidatable_loans(
+            &self,
+            collateral_token: ResourceAddress,
+        ) -> Vec<NonFungibleLocalId> {
+            let lender = self.get_lender(&collateral_token);
+            let loans = self.get_lender_loans(collateral_token);
+
+            lender.get_liquidatable_loans(loans)
+        }
+
+        /// Returns the collateralization ratio of a basket loan, its accrued interests, the amount
+        /// of stablecoins that can still be borrowed against it and its liquidation threshold,
+        /// weighted by the value of each collateral token
+        pub fn get_basket_loan_health(
+            &self,
+            loan_id: NonFungibleLocalId,
+        ) -> (Decimal, Decimal, Decimal, Decimal) {
+            let loan: BasketLoan =
+                borrow_resource_manager!(self.basket_loan_address).get_non_fungible_data(&loan_id);
+            let (collateral_value, liquidation_threshold, borrowing_capacity, debt) =
+                self.value_basket_loan(&loan);
+
+            let mut principal = Decimal::ZERO;
+            for amount_lent in loan.amounts_lent.values() {
+                principal += *amount_lent;
+            }
+
+            let collateralization_ratio = if debt.is_zero() {
+                Decimal::MAX
+            } else {
+                collateral_value / debt
+            };
+            let interests = (debt - principal).max(Decimal::ZERO);
+            let max_borrowable = (borrowing_capacity - debt).max(Decimal::ZERO);
+
+            (
+                collateralization_ratio,
+                interests,
+                max_borrowable,
+                liquidation_threshold,
+            )
+        }
+
+        /// Returns the ids of the basket loans that can be liquidated
+        pub fn get_liquidatable_basket_loans(&self) -> Vec<NonFungibleLocalId> {
+            let loan_manager = borrow_resource_manager!(self.basket_loan_address);
+
+            self.open_basket_loans
+                .iter()
+                .filter(|loan_id| {
+                    let loan: BasketLoan = loan_manager.get_non_fungible_data(loan_id);
+                    let (collateral_value, liquidation_threshold, _, debt) =
+                        self.value_basket_loan(&loan);
+                    debt.is_positive() && collateral_value / debt <= liquidation_threshold
+                })
+                .cloned()
+                .collect()
         }
 
+        /// Returns the state of a lender followed by the principal lent against its collateral
+        /// and its debt ceiling
         pub fn get_lender_state(&self, collateral_token: ResourceAddress) -> Vec<Decimal> {
             let lender = self.get_lender(&collateral_token);
-            lender.get_state()
+            let mut state = lender.get_state();
+            state.push(*self.lender_debts.get(&collateral_token).unwrap());
+            state.push(*self.debt_ceilings.get(&collateral_token).unwrap());
+            state
+        }
+
+        /// Returns the principal lent for all collateral tokens and the global debt ceiling
+        pub fn get_global_debt(&self) -> (Decimal, Decimal) {
+            (self.total_debt, self.global_debt_ceiling)
+        }
+
+        /// Returns the principal lent against a given collateral token, the interests accrued and
+        /// not yet paid on it and the amount of collateral backing it, basket loans and auctions
+        /// included
+        pub fn get_lender_totals(
+            &self,
+            collateral_token: ResourceAddress,
+        ) -> (Decimal, Decimal, Decimal) {
+            let lender = self.get_lender(&collateral_token);
+            let (auction_collateral, auction_principal, auction_interests) =
+                lender.get_auction_totals();
+
+            let principal = *self.lender_debts.get(&collateral_token).unwrap();
+            let debt = *self.lender_normalised_debts.get(&collateral_token).unwrap()
+                * lender.get_rate_accumulator();
+            let interests =
+                (debt - (principal - auction_principal)).max(Decimal::ZERO) + auction_interests;
+            let collateral =
+                *self.lender_collaterals.get(&collateral_token).unwrap() + auction_collateral;
+
+            (principal, interests, collateral)
+        }
+
+        /// Returns the supply of stablecoins backed by loans, the supply minted by the peg
+        /// stability modules and the minter registries, the value of the reserves and the
+        /// solvency ratio of the protocol. The solvency ratio is the value of the collateral and
+        /// of the reserves minus the system debt, divided by the supply backed by loans.
+        pub fn get_protocol_state(&self) -> (Decimal, Decimal, Decimal, Decimal) {
+            let loan_backed_supply = self.total_debt;
+
+            let mut external_supply = Decimal::ZERO;
+            for psm in &self.psms {
+                external_supply += PsmLocalComponent::at(*psm).get_outstanding_supply();
+            }
+            for minter_registry in &self.minter_registries {
+                external_supply +=
+                    MinterRegistryLocalComponent::at(*minter_registry).get_outstanding_supply();
+            }
+
+            // Reserves tokens that are not used as collateral cannot be priced and are ignored
+            let mut reserves_value = Decimal::ZERO;
+            for (token, vault) in &self.reserves {
+                if *token == self.stablecoin_address {
+                    reserves_value += vault.amount();
+                } else if let Some(lender) = self.lenders.get(token) {
+                    let (price, _, _, _) = lender.get_valuation();
+                    reserves_value += vault.amount() * price;
+                }
+            }
+
+            let mut collateral_value = Decimal::ZERO;
+            for token in self.lenders.keys() {
+                let (price, _, _, _) = self.get_lender(token).get_valuation();
+                let (_, _, collateral) = self.get_lender_totals(*token);
+                collateral_value += collateral * price;
+            }
+
+            let solvency_ratio = if loan_backed_supply.is_zero() {
+                Decimal::MAX
+            } else {
+                (collateral_value + reserves_value - self.system_debt).max(Decimal::ZERO)
+                    / loan_backed_supply
+            };
+
+            (
+                loan_backed_supply,
+                external_supply,
+                reserves_value,
+                solvency_ratio,
+            )
         }
 
         #[inline]
@@ -404,6 +2406,164 @@ mod issuer {
                 .authorize(|| borrow_resource_manager!(self.stablecoin_address).mint(amount))
         }
 
+        /// Records new principal lent against a given collateral token, checking that no debt
+        /// ceiling is exceeded
+        fn increase_debt(&mut self, collateral_token: ResourceAddress, amount: Decimal) {
+            let lender_debt = *self
+                .lender_debts
+                .get(&collateral_token)
+                .unwrap_or(&Decimal::ZERO)
+                + amount;
+            let debt_ceiling = *self
+                .debt_ceilings
+                .get(&collateral_token)
+                .unwrap_or(&Decimal::MAX);
+
+            assert!(
+                lender_debt <= debt_ceiling,
+                "Cannot lend {} because the debt ceiling of this collateral would be exceeded",
+                amount
+            );
+            assert!(
+                self.total_debt + amount <= self.global_debt_ceiling,
+                "Cannot lend {} because the global debt ceiling would be exceeded",
+                amount
+            );
+
+            self.lender_debts.insert(collateral_token, lender_debt);
+            self.total_debt += amount;
+        }
+
+        /// Records principal repaid for a given collateral token
+        fn decrease_debt(&mut self, collateral_token: ResourceAddress, amount: Decimal) {
+            let lender_debt = *self
+                .lender_debts
+                .get(&collateral_token)
+                .unwrap_or(&Decimal::ZERO);
+            let repaid = amount.min(lender_debt);
+
+            self.lender_debts
+                .insert(collateral_token, lender_debt - repaid);
+            self.total_debt -= repaid;
+        }
+
+        /// Records the changes of the normalised debt and of the collateral of the loans of a lender
+        fn update_lender_totals(
+            &mut self,
+            collateral_token: ResourceAddress,
+            normalised_debt_change: Decimal,
+            collateral_change: Decimal,
+        ) {
+            let normalised_debt = *self
+                .lender_normalised_debts
+                .get(&collateral_token)
+                .unwrap_or(&Decimal::ZERO)
+                + normalised_debt_change;
+            let collateral = *self
+                .lender_collaterals
+                .get(&collateral_token)
+                .unwrap_or(&Decimal::ZERO)
+                + collateral_change;
+
+            self.lender_normalised_debts
+                .insert(collateral_token, normalised_debt.max(Decimal::ZERO));
+            self.lender_collaterals
+                .insert(collateral_token, collateral.max(Decimal::ZERO));
+        }
+
+        /// Updates the totals of the lender of a loan and the redemption order of the loan
+        fn record_loan_update(
+            &mut self,
+            loan_id: &NonFungibleLocalId,
+            old_loan: &Loan,
+            new_loan: &Loan,
+        ) {
+            self.update_lender_totals(
+                old_loan.collateral_token,
+                new_loan.normalised_debt - old_loan.normalised_debt,
+                new_loan.collateral_amount - old_loan.collateral_amount,
+            );
+
+            let open_loans = self.open_loans.get_mut(&old_loan.collateral_token).unwrap();
+            open_loans.remove(&(Self::redemption_order(old_loan), loan_id.clone()));
+            open_loans.insert((Self::redemption_order(new_loan), loan_id.clone()));
+        }
+
+        /// Returns the ratio of the collateral of a loan to its normalised debt. Since the price
+        /// and the rate accumulator are the same for all the loans of a lender, the loans with
+        /// the smallest ratio are the least collateralized ones.
+        #[inline]
+        fn redemption_order(loan: &Loan) -> Decimal {
+            if loan.normalised_debt.is_zero() {
+                Decimal::MAX
+            } else {
+                loan.collateral_amount / loan.normalised_debt
+            }
+        }
+
+        /// Appends an event to the history of a loan
+        fn log_loan_event(
+            &mut self,
+            loan_id: &NonFungibleLocalId,
+            kind: LoanEventKind,
+            collateral_change: Decimal,
+            principal_change: Decimal,
+            stablecoins: Decimal,
+        ) {
+            let current_time = Clock::current_time(TimePrecision::Minute).seconds_since_unix_epoch;
+            let event = LoanEvent::new(
+                kind,
+                current_time,
+                collateral_change,
+                principal_change,
+                stablecoins,
+            );
+
+            if self.loan_histories.get(loan_id).is_some() {
+                self.loan_histories.get_mut(loan_id).unwrap().push(event);
+            } else {
+                self.loan_histories.insert(loan_id.clone(), vec![event]);
+            }
+        }
+
+        /// Appends an event to the history of a basket loan
+        fn log_basket_loan_event(
+            &mut self,
+            loan_id: &NonFungibleLocalId,
+            kind: LoanEventKind,
+            collateral_changes: HashMap<ResourceAddress, Decimal>,
+            principal_changes: HashMap<ResourceAddress, Decimal>,
+            stablecoins: Decimal,
+        ) {
+            let current_time = Clock::current_time(TimePrecision::Minute).seconds_since_unix_epoch;
+            let event = BasketLoanEvent::new(
+                kind,
+                current_time,
+                collateral_changes,
+                principal_changes,
+                stablecoins,
+            );
+
+            if self.basket_loan_histories.get(loan_id).is_some() {
+                self.basket_loan_histories
+                    .get_mut(loan_id)
+                    .unwrap()
+                    .push(event);
+            } else {
+                self.basket_loan_histories
+                    .insert(loan_id.clone(), vec![event]);
+            }
+        }
+
+        /// Internal function that checks that no flash loan of the reserves is outstanding.
+        #[inline]
+        fn assert_reserves_unlocked(&self) {
+            assert!(
+                !self.reserves_locked,
+                "The reserves are locked until their flash loan is repaid"
+            );
+        }
+
         #[inline]
         fn put_in_reserves(&mut self, bucket: Bucket) {
             match self.reserves.get_mut(&bucket.resource_address()) {
@@ -416,6 +2576,14 @@ mod issuer {
             };
         }
 
+        #[inline]
+        fn stablecoin_reserves(&self) -> Decimal {
+            match self.reserves.get(&self.stablecoin_address) {
+                Some(vault) => vault.amount(),
+                None => Decimal::ZERO,
+            }
+        }
+
         #[inline]
         fn burn_bucket(&self, bucket: Bucket) {
             self.stablecoin_minter.authorize(|| {
@@ -423,6 +2591,20 @@ mod issuer {
             });
         }
 
+        /// Burns loan NFRs and removes them from the loans of their lender
+        fn burn_loans(&mut self, loans: Bucket) {
+            for loan_nfr in loans.non_fungibles::<Loan>() {
+                let loan = self.get_loan_data(&loan_nfr);
+                self.open_loans
+                    .get_mut(&loan.collateral_token)
+                    .unwrap()
+                    .remove(&(Self::redemption_order(&loan), loan_nfr.local_id().clone()));
+            }
+
+            self.resource_minter
+                .authorize(|| borrow_resource_manager!(self.loan_address).burn(loans));
+        }
+
         #[inline]
         fn check_single_loan_proof(&self, loan_proof: Proof) -> ValidatedProof {
             loan_proof
@@ -433,6 +2615,36 @@ mod issuer {
                 .expect("Please provide a valid proof of a single loan")
         }
 
+        #[inline]
+        fn check_single_deposit_proof(
+            &self,
+            deposit_proof: Proof,
+        ) -> NonFungible<StabilityDeposit> {
+            deposit_proof
+                .validate_proof(ProofValidationMode::ValidateContainsAmount(
+                    self.stability_deposit_address,
+                    Decimal::ONE,
+                ))
+                .expect("Please provide a valid proof of a single stability deposit")
+                .non_fungible::<StabilityDeposit>()
+        }
+
+        /// Checks that a loan is not being auctioned, in which case its NFR holds no debt and no
+        /// collateral but is still needed to take the auction
+        #[inline]
+        fn assert_not_auctioned(
+            &self,
+            loan_id: &NonFungibleLocalId,
+            collateral_token: &ResourceAddress,
+        ) {
+            assert!(
+                !self
+                    .get_lender(collateral_token)
+                    .is_auctioned(loan_id.clone()),
+                "This loan is being auctioned"
+            );
+        }
+
         #[inline]
         fn get_loan_data(&self, loan_nfr: &NonFungible<Loan>) -> Loan {
             borrow_resource_manager!(self.loan_address).get_non_fungible_data(loan_nfr.local_id())
@@ -452,6 +2664,78 @@ mod issuer {
             });
         }
 
+        /// Returns the value of the collateral of a basket loan, its liquidation threshold weighted
+        /// by the value of each collateral token, the amount of stablecoins that can be borrowed
+        /// against its collateral and its debt
+        fn value_basket_loan(&self, loan: &BasketLoan) -> (Decimal, Decimal, Decimal, Decimal) {
+            let mut collateral_value = Decimal::ZERO;
+            let mut threshold_value = Decimal::ZERO;
+            let mut borrowing_capacity = Decimal::ZERO;
+            for (token, amount) in &loan.collaterals {
+                let (price, loan_to_value, liquidation_threshold, _) =
+                    self.get_lender(token).get_valuation();
+                collateral_value += *amount * price;
+                threshold_value += *amount * price * liquidation_threshold;
+                borrowing_capacity += *amount * price * loan_to_value;
+            }
+
+            let mut debt = Decimal::ZERO;
+            for (token, normalised_debt) in &loan.normalised_debts {
+                debt += *normalised_debt * self.get_lender(token).get_rate_accumulator();
+            }
+
+            let liquidation_threshold = if collateral_value.is_zero() {
+                Decimal::ONE
+            } else {
+                threshold_value / collateral_value
+            };
+
+            (
+                collateral_value,
+                liquidation_threshold,
+                borrowing_capacity,
+                debt,
+            )
+        }
+
+        /// Returns the ids and data of the existing loans of a given lender
+        fn get_lender_loans(
+            &self,
+            collateral_token: ResourceAddress,
+        ) -> Vec<(NonFungibleLocalId, Loan)> {
+            let loan_manager = borrow_resource_manager!(self.loan_address);
+
+            match self.open_loans.get(&collateral_token) {
+                Some(loan_ids) => loan_ids
+                    .iter()
+                    .map(|(_, loan_id)| {
+                        (loan_id.clone(), loan_manager.get_non_fungible_data(loan_id))
+                    })
+                    .collect(),
+                None => panic!("There is no lenders for this token"),
+            }
+        }
+
+        /// Returns the loans of a given lender with the smallest ratio of collateral to normalised
+        /// debt, up to the maximum number of loans scanned by a redemption
+        fn get_redeemable_loans(
+            &self,
+            collateral_token: ResourceAddress,
+        ) -> Vec<(NonFungibleLocalId, Loan)> {
+            let loan_manager = borrow_resource_manager!(self.loan_address);
+
+            match self.open_loans.get(&collateral_token) {
+                Some(loan_ids) => loan_ids
+                    .iter()
+                    .take(MAX_REDEEMED_LOANS)
+                    .map(|(_, loan_id)| {
+                        (loan_id.clone(), loan_manager.get_non_fungible_data(loan_id))
+                    })
+                    .collect(),
+                None => panic!("There is no lenders for this token"),
+            }
+        }
+
         #[inline]
         fn get_lender(&self, resource_address: &ResourceAddress) -> &LenderComponent {
             match self.lenders.get(resource_address) {
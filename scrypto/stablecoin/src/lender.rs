@@ -3,22 +3,62 @@ use scrypto::{blueprint, external_component};
 external_component! {
     OracleComponent {
         fn get_twap_since(&self, token: ResourceAddress, timestamp: i64) -> Decimal;
-        fn new_observation(&mut self, token: ResourceAddress);
+        fn get_truncated_twap_since(&self, token: ResourceAddress, timestamp: i64) -> Decimal;
     }
 }
 
 #[blueprint]
 mod lender {
-    use crate::constants::SECONDS_PER_DAY;
+    use crate::auction::Auction;
+    use crate::constants::{
+        DEFAULT_AUCTION_DURATION, DEFAULT_AUCTION_START_PREMIUM, DEFAULT_KICK_INCENTIVE,
+        SECONDS_PER_DAY,
+    };
     use crate::loan::Loan;
+    use stoichiometric_dex::decimal_maths::exp;
 
     pub struct Lender {
         collateral: Vault,
         loan_to_value: Decimal,
         interest_rate: Decimal,
         liquidation_threshold: Decimal,
-        liquidation_penalty: Decimal,
+
+        /// Share of the repaid debt earned in collateral by liquidators
+        liquidator_bonus: Decimal,
+
         oracle: ComponentAddress,
+        use_truncated_twap: bool,
+
+        /// Index by which normalised debts are multiplied to get the debt of a loan. It compounds
+        /// continuously at the daily interest rate.
+        rate_accumulator: Decimal,
+
+        /// Time of the last update of the rate accumulator
+        last_accumulator_update: i64,
+
+        /// Whether loans are liquidated through descending price auctions instead of instantly
+        use_auction_liquidation: bool,
+
+        /// Ratio between the starting price of an auction and the oracle price
+        auction_start_premium: Decimal,
+
+        /// Number of seconds for the auction price to decrease to zero
+        auction_duration: i64,
+
+        /// Share of the debt of a loan paid in collateral to whoever starts its auction
+        kick_incentive: Decimal,
+
+        /// Running liquidation auctions, indexed by the id of the auctioned loan
+        auctions: HashMap<NonFungibleLocalId, Auction>,
+
+        /// Share of the claimable interests taken by the protocol when a loan is fully liquidated
+        protocol_liquidation_share: Decimal,
+
+        /// Maximum share of the debt of a loan that can be repaid by a single partial liquidation
+        close_factor: Decimal,
+
+        /// Minimum amount of stablecoins that an open loan should owe
+        minimum_debt: Decimal,
     }
 
     impl Lender {
@@ -27,28 +67,18 @@ mod lender {
             loan_to_value: Decimal,
             interest_rate: Decimal,
             liquidation_threshold: Decimal,
-            liquidation_penalty: Decimal,
+            liquidator_bonus: Decimal,
+            protocol_liquidation_share: Decimal,
+            close_factor: Decimal,
             oracle: ComponentAddress,
         ) -> LenderComponent {
-            assert!(
-                loan_to_value.is_positive() && loan_to_value < Decimal::ONE,
-                "LTV should be such that 0<LTV<1"
-            );
-            assert!(
-                interest_rate.is_positive() && interest_rate < Decimal::ONE,
-                "The daily interest rate should be such that 0<DIR<1"
-            );
-            assert!(
-                liquidation_threshold > Decimal::ONE,
-                "The liquidation threshold should be greater than one"
-            );
-            assert!(
-                liquidation_threshold * loan_to_value < Decimal::ONE,
-                "The LTV-liquidation threshold product should be smaller than one"
-            );
-            assert!(
-                liquidation_penalty.is_positive(),
-                "The liquidation incentive should be positive"
+            Self::assert_parameters(
+                loan_to_value,
+                interest_rate,
+                liquidation_threshold,
+                liquidator_bonus,
+                protocol_liquidation_share,
+                close_factor,
             );
 
             Self {
@@ -56,13 +86,31 @@ mod lender {
                 loan_to_value,
                 interest_rate,
                 liquidation_threshold,
-                liquidation_penalty,
+                liquidator_bonus,
                 oracle,
+                use_truncated_twap: false,
+                rate_accumulator: Decimal::ONE,
+                last_accumulator_update: Clock::current_time(TimePrecision::Minute)
+                    .seconds_since_unix_epoch,
+                use_auction_liquidation: false,
+                auction_start_premium: DEFAULT_AUCTION_START_PREMIUM,
+                auction_duration: DEFAULT_AUCTION_DURATION,
+                kick_incentive: DEFAULT_KICK_INCENTIVE,
+                auctions: HashMap::new(),
+                protocol_liquidation_share,
+                close_factor,
+                minimum_debt: Decimal::ZERO,
             }
             .instantiate()
         }
 
         pub fn take_loan(&mut self, collateral: Bucket, amount_to_loan: Decimal) -> Loan {
+            assert!(
+                amount_to_loan >= self.minimum_debt,
+                "The amount to loan should be at least {}",
+                self.minimum_debt
+            );
+
             let price = self.get_oracle_price();
 
             let collateral_needed = amount_to_loan / (self.loan_to_value * price);
@@ -79,26 +127,88 @@ mod lender {
                 collateral.resource_address(),
                 collateral.amount(),
                 amount_to_loan,
+                amount_to_loan / self.current_rate_accumulator(),
                 current_time,
-                self.interest_rate,
             );
             self.collateral.put(collateral);
             loan
         }
 
         pub fn repay_loan(&mut self, repayment: Decimal, loan: Loan) -> (Decimal, Bucket) {
-            let interests = self.compute_interests(&loan);
+            let debt = self.compute_debt(&loan);
             assert!(
-                repayment >= loan.amount_lent + interests,
+                repayment >= debt,
                 "You need to provide {} stablecoins to repay your loan",
-                loan.amount_lent + interests
+                debt
             );
+            let interests = debt - loan.amount_lent;
 
             let collateral = self.collateral.take(loan.collateral_amount);
 
             (interests, collateral)
         }
 
+        /// Repays part of a loan, interests first and then principal, and returns the amount of
+        /// interests paid, the amount of principal paid and the updated loan
+        pub fn repay_partial(
+            &mut self,
+            repayment: Decimal,
+            mut loan: Loan,
+        ) -> (Decimal, Decimal, Loan) {
+            assert!(
+                repayment.is_positive(),
+                "The amount to repay should be positive"
+            );
+
+            let rate_accumulator = self.current_rate_accumulator();
+            let debt = loan.normalised_debt * rate_accumulator;
+            assert!(debt.is_positive(), "This loan is already fully repaid");
+            let interests = (debt - loan.amount_lent).max(Decimal::ZERO);
+
+            let interests_paid = repayment.min(interests);
+            let principal_paid = (repayment - interests_paid).min(loan.amount_lent);
+
+            let principal_left = loan.amount_lent - principal_paid;
+            assert!(
+                principal_left.is_zero() || principal_left >= self.minimum_debt,
+                "The loan should be fully repaid or keep a debt of at least {}",
+                self.minimum_debt
+            );
+
+            if principal_paid == loan.amount_lent {
+                loan.normalised_debt = Decimal::ZERO;
+            } else {
+                loan.normalised_debt -= (interests_paid + principal_paid) / rate_accumulator;
+            }
+            loan.amount_lent -= principal_paid;
+
+            (interests_paid, principal_paid, loan)
+        }
+
+        /// Increases the debt of a loan if its collateral allows it and returns the updated loan
+        pub fn borrow_more(&mut self, amount: Decimal, mut loan: Loan) -> Loan {
+            let price = self.get_oracle_price();
+            let rate_accumulator = self.current_rate_accumulator();
+            let new_debt = loan.normalised_debt * rate_accumulator + amount;
+
+            assert!(
+                loan.amount_lent + amount >= self.minimum_debt,
+                "The amount to loan should be at least {}",
+                self.minimum_debt
+            );
+
+            assert!(
+                new_debt <= loan.collateral_amount * price * self.loan_to_value,
+                "Cannot borrow {} because the loan to value would exceed {}",
+                amount,
+                self.loan_to_value
+            );
+
+            loan.amount_lent += amount;
+            loan.normalised_debt += amount / rate_accumulator;
+            loan
+        }
+
         pub fn add_collateral(&mut self, collateral: Bucket, mut loan: Loan) -> Loan {
             assert!(
                 loan.collateral_token == collateral.resource_address(),
@@ -111,16 +221,25 @@ mod lender {
         }
 
         pub fn remove_collateral(&mut self, amount: Decimal, mut loan: Loan) -> (Loan, Bucket) {
+            // Loans whose debt became smaller than the minimum debt can only be repaid
+            assert!(
+                loan.amount_lent.is_zero() || loan.amount_lent >= self.minimum_debt,
+                "The debt of this loan is smaller than {}, please repay it",
+                self.minimum_debt
+            );
+
             let new_collateral_amount = loan.collateral_amount - amount;
             let collateral_price = self.get_oracle_price();
-            let interests = self.compute_interests(&loan);
+            let debt = self.compute_debt(&loan);
 
             // Check that after removing collateral, the (collateral value)/(loan value) is still
-            // greater than the liquidation threshold
+            // greater than the liquidation threshold. A fully repaid loan can withdraw all of its
+            // collateral.
 
             assert!(
-                new_collateral_amount * collateral_price / (loan.amount_lent + interests)
-                    >= self.liquidation_threshold,
+                debt.is_zero()
+                    || new_collateral_amount * collateral_price / debt
+                        >= self.liquidation_threshold,
                 "Cannot remove {} because it would make the loan liquidatable",
                 amount
             );
@@ -129,14 +248,23 @@ mod lender {
             (loan, self.collateral.take(amount))
         }
 
+        /// Liquidates a loan and returns the amount of interests paid, the amount of principal
+        /// paid, the collateral earned by the liquidator, the collateral earned by the protocol
+        /// and the updated loan
         pub fn liquidate(
             &mut self,
             stabelcoin_input: Decimal,
             mut loan: Loan,
-        ) -> (Decimal, Bucket, Option<Bucket>, Loan) {
+        ) -> (Decimal, Decimal, Bucket, Option<Bucket>, Loan) {
+            assert!(
+                !self.use_auction_liquidation,
+                "This lender liquidates loans through auctions"
+            );
+
             // First check that the loan can indeed be liquidated
-            let accrued_interests = self.compute_interests(&loan);
-            let total_lent = loan.amount_lent + accrued_interests;
+            let rate_accumulator = self.current_rate_accumulator();
+            let total_lent = loan.normalised_debt * rate_accumulator;
+            let accrued_interests = total_lent - loan.amount_lent;
             let collateral_price = self.get_oracle_price();
             let collateralization_ratio = loan.collateral_amount * collateral_price / total_lent;
 
@@ -148,6 +276,12 @@ mod lender {
             );
 
             // If the previous assert worked, then it means that the loan can be partially or fully liquidated
+            let partial_input = self.partial_liquidation_input(
+                &loan,
+                total_lent,
+                collateral_price,
+                stabelcoin_input,
+            );
 
             // In the case where the collateralization ratio is smaller than 1, we liquidate everything
             // Note that there is not necessarily bad debt because without counting interests, the ratio could be > 1
@@ -161,8 +295,8 @@ mod lender {
                     loan.amount_lent
                 );
 
-                // We only claim 10% of the interests that can be claimed
-                let real_interests = dec!("0.1")
+                // The protocol only claims a share of the interests that can be claimed
+                let real_interests = self.protocol_liquidation_share
                     * accrued_interests
                         .min(collateral_price * loan.collateral_amount - loan.amount_lent);
                 let stablecoin_interest = real_interests / collateral_price;
@@ -173,26 +307,45 @@ mod lender {
                 let stablecoins_needed = loan.amount_lent;
 
                 loan.amount_lent = Decimal::ZERO;
+                loan.normalised_debt = Decimal::ZERO;
                 loan.collateral_amount = Decimal::ZERO;
 
                 (
+                    Decimal::ZERO,
                     stablecoins_needed,
                     liquidator_share,
                     Some(protocol_share),
                     loan,
                 )
+            } else if loan.amount_lent - partial_input < self.minimum_debt {
+                // A partial liquidation would leave dust, so the liquidator repays the whole debt
+                // and earns the liquidator bonus while the rest of the collateral stays in the loan
+
+                assert!(
+                    stabelcoin_input >= total_lent,
+                    "Please provide at least {} SUSD to liquidate this loan",
+                    total_lent
+                );
+
+                let collateral_output = (total_lent * (Decimal::ONE + self.liquidator_bonus)
+                    / collateral_price)
+                    .min(loan.collateral_amount);
+                let principal_paid = loan.amount_lent;
+
+                loan.amount_lent = Decimal::ZERO;
+                loan.normalised_debt = Decimal::ZERO;
+                loan.collateral_amount -= collateral_output;
+
+                let liquidator_bucket = self.collateral.take(collateral_output);
+                (
+                    accrued_interests.max(Decimal::ZERO),
+                    principal_paid,
+                    liquidator_bucket,
+                    None,
+                    loan,
+                )
             } else {
-                //In the other case, we compute the maximum amount that can be liquidated
-                let virtual_collateral =
-                    (Decimal::ONE - self.liquidation_penalty) * loan.collateral_amount;
-                let virtual_collateralization_ratio =
-                    virtual_collateral * collateral_price / total_lent;
-                let max_input = total_lent
-                    * (Decimal::ONE - virtual_collateralization_ratio / self.liquidation_threshold)
-                        .sqrt()
-                        .unwrap();
-
-                let actual_input = max_input.min(stabelcoin_input);
+                let actual_input = partial_input;
                 let collateral_output =
                     loan.collateral_amount * actual_input / (total_lent + actual_input);
 
@@ -200,11 +353,248 @@ mod lender {
                 let new_collateral_amount = loan.collateral_amount - collateral_output;
 
                 loan.amount_lent = new_amount_lent;
+                loan.normalised_debt -= actual_input / rate_accumulator;
                 loan.collateral_amount = new_collateral_amount;
 
                 let liquidator_bucket = self.collateral.take(collateral_output);
-                (actual_input, liquidator_bucket, None, loan)
+                (Decimal::ZERO, actual_input, liquidator_bucket, None, loan)
+            }
+        }
+
+        /// Starts a descending price auction of the collateral of a liquidatable loan. The debt
+        /// and the collateral of the loan are moved to the auction and the caller receives the
+        /// kick incentive in collateral.
+        pub fn start_auction(
+            &mut self,
+            loan_id: NonFungibleLocalId,
+            mut loan: Loan,
+        ) -> (Bucket, Loan) {
+            assert!(
+                self.use_auction_liquidation,
+                "This lender does not liquidate loans through auctions"
+            );
+            assert!(
+                !self.auctions.contains_key(&loan_id),
+                "This loan is already being auctioned"
+            );
+
+            let debt = self.compute_debt(&loan);
+            let collateral_price = self.get_oracle_price();
+            assert!(debt.is_positive(), "This loan has no debt to liquidate");
+
+            let collateralization_ratio = loan.collateral_amount * collateral_price / debt;
+            assert!(
+                collateralization_ratio <= self.liquidation_threshold,
+                "Cannot liquidate this loan: the collateralization ratio is {} >= {}",
+                collateralization_ratio,
+                self.liquidation_threshold
+            );
+
+            let incentive =
+                (debt * self.kick_incentive / collateral_price).min(loan.collateral_amount);
+            let current_time = Clock::current_time(TimePrecision::Minute).seconds_since_unix_epoch;
+            let auction = Auction::new(
+                loan.collateral_amount - incentive,
+                loan.amount_lent,
+                (debt - loan.amount_lent).max(Decimal::ZERO),
+                collateral_price * self.auction_start_premium,
+                current_time,
+            );
+            self.auctions.insert(loan_id, auction);
+
+            loan.collateral_amount = Decimal::ZERO;
+            loan.amount_lent = Decimal::ZERO;
+            loan.normalised_debt = Decimal::ZERO;
+
+            (self.collateral.take(incentive), loan)
+        }
+
+        /// Buys collateral from a running auction at its current price and returns the amount of
+        /// interests paid, the amount of principal paid, the bought collateral, the collateral
+        /// left to give back to the loan owner if the auction is over and the principal left
+        /// unpaid if the auction ran out of collateral
+        pub fn take_auction(
+            &mut self,
+            loan_id: NonFungibleLocalId,
+            stablecoin_input: Decimal,
+        ) -> (Decimal, Decimal, Bucket, Decimal, Decimal) {
+            let current_time = Clock::current_time(TimePrecision::Minute).seconds_since_unix_epoch;
+            let auction = match self.auctions.get_mut(&loan_id) {
+                Some(auction) => auction,
+                None => panic!("There is no auction for this loan"),
+            };
+
+            assert!(
+                !auction.has_expired(current_time, self.auction_duration),
+                "This auction has expired and should be reset"
+            );
+
+            let price = auction.price_at(current_time, self.auction_duration);
+            let mut paid = stablecoin_input.min(auction.debt());
+            let mut collateral_bought = paid / price;
+            if collateral_bought >= auction.collateral_amount {
+                collateral_bought = auction.collateral_amount;
+                paid = collateral_bought * price;
+            }
+
+            // Interests are paid first
+            let interests_paid = paid.min(auction.interests);
+            let principal_paid = (paid - interests_paid).min(auction.principal);
+            auction.interests -= interests_paid;
+            auction.principal -= principal_paid;
+            auction.collateral_amount -= collateral_bought;
+
+            // The auction is over when its debt is raised or when there is nothing left to sell, in
+            // which case the principal left is bad debt
+            let (collateral_left, bad_debt) =
+                if auction.debt().is_zero() || auction.collateral_amount.is_zero() {
+                    let collateral_left = auction.collateral_amount;
+                    let bad_debt = auction.principal;
+                    self.auctions.remove(&loan_id);
+                    (collateral_left, bad_debt)
+                } else {
+                    (Decimal::ZERO, Decimal::ZERO)
+                };
+
+            (
+                interests_paid,
+                principal_paid,
+                self.collateral.take(collateral_bought),
+                collateral_left,
+                bad_debt,
+            )
+        }
+
+        /// Restarts an expired auction from the current oracle price
+        pub fn reset_auction(&mut self, loan_id: NonFungibleLocalId) {
+            let current_time = Clock::current_time(TimePrecision::Minute).seconds_since_unix_epoch;
+            let start_price = self.get_oracle_price() * self.auction_start_premium;
+            let auction = match self.auctions.get_mut(&loan_id) {
+                Some(auction) => auction,
+                None => panic!("There is no auction for this loan"),
+            };
+
+            assert!(
+                auction.has_expired(current_time, self.auction_duration),
+                "This auction has not expired yet"
+            );
+
+            auction.start_price = start_price;
+            auction.start_time = current_time;
+        }
+
+        /// Returns the collateral left to sell, the debt left to raise and the current price of
+        /// the auction of a given loan
+        pub fn get_auction(&self, loan_id: NonFungibleLocalId) -> (Decimal, Decimal, Decimal) {
+            let current_time = Clock::current_time(TimePrecision::Minute).seconds_since_unix_epoch;
+            match self.auctions.get(&loan_id) {
+                Some(auction) => (
+                    auction.collateral_amount,
+                    auction.debt(),
+                    auction.price_at(current_time, self.auction_duration),
+                ),
+                None => panic!("There is no auction for this loan"),
+            }
+        }
+
+        /// Returns the collateral left to sell, the principal left to raise and the interests left
+        /// to raise by all running auctions
+        pub fn get_auction_totals(&self) -> (Decimal, Decimal, Decimal) {
+            let mut collateral = Decimal::ZERO;
+            let mut principal = Decimal::ZERO;
+            let mut interests = Decimal::ZERO;
+            for auction in self.auctions.values() {
+                collateral += auction.collateral_amount;
+                principal += auction.principal;
+                interests += auction.interests;
+            }
+
+            (collateral, principal, interests)
+        }
+
+        /// Repays the debt of the given loans with stablecoins, in the given order, against
+        /// collateral taken at the oracle price. Loans being auctioned or whose
+        /// collateral is worth less than their debt are skipped. Returns the interests and the
+        /// principal repaid, the collateral taken and the loans before and after the redemption
+        /// along with the stablecoins redeemed against each of them.
+        pub fn redeem(
+            &mut self,
+            amount: Decimal,
+            mut loans: Vec<(NonFungibleLocalId, Loan)>,
+        ) -> (
+            Decimal,
+            Decimal,
+            Bucket,
+            Vec<(NonFungibleLocalId, Loan, Loan, Decimal)>,
+        ) {
+            let price = self.get_oracle_price();
+            let rate_accumulator = self.current_rate_accumulator();
+
+            loans.retain(|(loan_id, loan)| {
+                let debt = loan.normalised_debt * rate_accumulator;
+                debt.is_positive()
+                    && loan.collateral_amount * price >= debt
+                    && !self.auctions.contains_key(loan_id)
+            });
+
+            let mut amount_left = amount;
+            let mut interests_paid = Decimal::ZERO;
+            let mut principal_paid = Decimal::ZERO;
+            let mut collateral_taken = Decimal::ZERO;
+            let mut redeemed_loans = Vec::new();
+
+            for (loan_id, loan) in loans {
+                if amount_left.is_zero() {
+                    break;
+                }
+
+                let debt = loan.normalised_debt * rate_accumulator;
+                let mut repayment = amount_left.min(debt);
+                let loan_interests = repayment.min((debt - loan.amount_lent).max(Decimal::ZERO));
+                let mut loan_principal = (repayment - loan_interests).min(loan.amount_lent);
+
+                // A redemption cannot leave a loan with a debt smaller than the minimum debt, so
+                // such a loan is only redeemed down to the minimum debt
+                if loan_principal < loan.amount_lent
+                    && loan.amount_lent - loan_principal < self.minimum_debt
+                {
+                    loan_principal = (loan.amount_lent - self.minimum_debt).max(Decimal::ZERO);
+                    repayment = loan_interests + loan_principal;
+                }
+                if repayment.is_zero() {
+                    continue;
+                }
+                let principal_left = loan.amount_lent - loan_principal;
+
+                let loan_collateral = (repayment / price).min(loan.collateral_amount);
+
+                let mut new_loan = loan.clone();
+                if principal_left.is_zero() {
+                    new_loan.normalised_debt = Decimal::ZERO;
+                } else {
+                    new_loan.normalised_debt -= repayment / rate_accumulator;
+                }
+                new_loan.amount_lent = principal_left;
+                new_loan.collateral_amount -= loan_collateral;
+
+                amount_left -= repayment;
+                interests_paid += loan_interests;
+                principal_paid += loan_principal;
+                collateral_taken += loan_collateral;
+                redeemed_loans.push((loan_id, loan, new_loan, repayment));
             }
+
+            (
+                interests_paid,
+                principal_paid,
+                self.collateral.take(collateral_taken),
+                redeemed_loans,
+            )
+        }
+
+        /// Returns whether a given loan is being auctioned
+        pub fn is_auctioned(&self, loan_id: NonFungibleLocalId) -> bool {
+            self.auctions.contains_key(&loan_id)
         }
 
         pub fn clear_bad_debt(&mut self, mut loan: Loan) -> (Decimal, Bucket, Loan) {
@@ -222,54 +612,263 @@ mod lender {
             let amount_to_clear = loan.amount_lent;
             loan.collateral_amount = Decimal::ZERO;
             loan.amount_lent = Decimal::ZERO;
+            loan.normalised_debt = Decimal::ZERO;
 
             (amount_to_clear, collateral, loan)
         }
 
+        /// Deposits collateral backing a basket loan
+        pub fn deposit_collateral(&mut self, collateral: Bucket) {
+            self.collateral.put(collateral);
+        }
+
+        /// Withdraws collateral backing a basket loan
+        pub fn withdraw_collateral(&mut self, amount: Decimal) -> Bucket {
+            self.collateral.take(amount)
+        }
+
+        /// Returns the collateral price, the loan to value, the liquidation threshold and the
+        /// liquidator bonus used to value basket loans
+        pub fn get_valuation(&self) -> (Decimal, Decimal, Decimal, Decimal) {
+            (
+                self.get_oracle_price(),
+                self.loan_to_value,
+                self.liquidation_threshold,
+                self.liquidator_bonus,
+            )
+        }
+
+        /// Returns the rate accumulator compounded up to the current time
+        pub fn get_rate_accumulator(&self) -> Decimal {
+            self.current_rate_accumulator()
+        }
+
+        /// Returns the minimum amount of stablecoins that a loan should owe to the lender
+        pub fn get_minimum_debt(&self) -> Decimal {
+            self.minimum_debt
+        }
+
+        /// Returns the protocol liquidation share, the close factor and the minimum debt used to
+        /// liquidate basket loans
+        pub fn get_liquidation_parameters(&self) -> (Decimal, Decimal, Decimal) {
+            (
+                self.protocol_liquidation_share,
+                self.close_factor,
+                self.minimum_debt,
+            )
+        }
+
         pub fn change_parameters(
             &mut self,
             loan_to_value: Decimal,
             interest_rate: Decimal,
             liquidation_threshold: Decimal,
-            liquidation_penalty: Decimal,
+            liquidator_bonus: Decimal,
+            protocol_liquidation_share: Decimal,
+            close_factor: Decimal,
         ) {
+            Self::assert_parameters(
+                loan_to_value,
+                interest_rate,
+                liquidation_threshold,
+                liquidator_bonus,
+                protocol_liquidation_share,
+                close_factor,
+            );
+
+            // Accrue the interests at the previous rate before changing it
+            self.rate_accumulator = self.current_rate_accumulator();
+            self.last_accumulator_update =
+                Clock::current_time(TimePrecision::Minute).seconds_since_unix_epoch;
+
             self.loan_to_value = loan_to_value;
             self.interest_rate = interest_rate;
             self.liquidation_threshold = liquidation_threshold;
-            self.liquidation_penalty = liquidation_penalty;
+            self.liquidator_bonus = liquidator_bonus;
+            self.protocol_liquidation_share = protocol_liquidation_share;
+            self.close_factor = close_factor;
         }
 
-        pub fn change_oracle(&mut self, oracle: ComponentAddress) {
+        pub fn change_minimum_debt(&mut self, minimum_debt: Decimal) {
+            assert!(
+                !minimum_debt.is_negative(),
+                "The minimum debt should be non negative"
+            );
+            self.minimum_debt = minimum_debt;
+        }
+
+        pub fn change_liquidation_mode(
+            &mut self,
+            use_auction_liquidation: bool,
+            auction_start_premium: Decimal,
+            auction_duration: i64,
+            kick_incentive: Decimal,
+        ) {
+            self.use_auction_liquidation = use_auction_liquidation;
+            self.auction_start_premium = auction_start_premium;
+            self.auction_duration = auction_duration;
+            self.kick_incentive = kick_incentive;
+        }
+
+        pub fn change_oracle(&mut self, oracle: ComponentAddress, use_truncated_twap: bool) {
             self.oracle = oracle;
+            self.use_truncated_twap = use_truncated_twap;
         }
 
+        /// Returns the collateralization ratio, the accrued interests, the amount that can still
+        /// be borrowed and the liquidation price of a loan
+        pub fn get_loan_health(&self, loan: Loan) -> (Decimal, Decimal, Decimal, Decimal) {
+            let price = self.get_oracle_price();
+            let debt = self.compute_debt(&loan);
+            let collateral_value = loan.collateral_amount * price;
+
+            let collateralization_ratio = if debt.is_zero() {
+                Decimal::MAX
+            } else {
+                collateral_value / debt
+            };
+            let interests = (debt - loan.amount_lent).max(Decimal::ZERO);
+            let max_borrowable = (collateral_value * self.loan_to_value - debt).max(Decimal::ZERO);
+            let liquidation_price = if loan.collateral_amount.is_zero() {
+                Decimal::ZERO
+            } else {
+                debt * self.liquidation_threshold / loan.collateral_amount
+            };
+
+            (
+                collateralization_ratio,
+                interests,
+                max_borrowable,
+                liquidation_price,
+            )
+        }
+
+        /// Returns the ids of the given loans that can currently be liquidated
+        pub fn get_liquidatable_loans(
+            &self,
+            loans: Vec<(NonFungibleLocalId, Loan)>,
+        ) -> Vec<NonFungibleLocalId> {
+            let price = self.get_oracle_price();
+            let rate_accumulator = self.current_rate_accumulator();
+
+            loans
+                .into_iter()
+                .filter(|(_, loan)| {
+                    let debt = loan.normalised_debt * rate_accumulator;
+                    debt.is_positive()
+                        && loan.collateral_amount * price / debt <= self.liquidation_threshold
+                })
+                .map(|(loan_id, _)| loan_id)
+                .collect()
+        }
+
+        /// Returns the collateral held, the loan to value, the interest rate, the liquidation
+        /// threshold, the liquidator bonus, the protocol liquidation share and the close factor
         pub fn get_state(&self) -> Vec<Decimal> {
             vec![
                 self.collateral.amount(),
                 self.loan_to_value,
                 self.interest_rate,
                 self.liquidation_threshold,
-                self.liquidation_penalty,
+                self.liquidator_bonus,
+                self.protocol_liquidation_share,
+                self.close_factor,
             ]
         }
 
         fn get_oracle_price(&self) -> Decimal {
-            let mut oracle = OracleComponent::at(self.oracle);
+            let oracle = OracleComponent::at(self.oracle);
 
-            // Look at the TWAP with as much data as possible
-            let price = oracle.get_twap_since(self.collateral.resource_address(), 0);
+            // Look at the TWAP with as much data as possible, using the manipulation resistant
+            // series if the lender was configured to do so. Reading the price does not modify the
+            // oracle: observations are recorded by swaps and keepers.
+            if self.use_truncated_twap {
+                oracle.get_truncated_twap_since(self.collateral.resource_address(), 0)
+            } else {
+                oracle.get_twap_since(self.collateral.resource_address(), 0)
+            }
+        }
 
-            // Make a new observation to have more input to the oracle for future requests
-            oracle.new_observation(self.collateral.resource_address());
+        /// Returns the amount of stablecoins used by a partial liquidation of a loan
+        fn partial_liquidation_input(
+            &self,
+            loan: &Loan,
+            total_lent: Decimal,
+            collateral_price: Decimal,
+            stablecoin_input: Decimal,
+        ) -> Decimal {
+            // We compute the maximum amount that can be liquidated
+            let virtual_collateral =
+                (Decimal::ONE - self.liquidator_bonus) * loan.collateral_amount;
+            let virtual_collateralization_ratio =
+                virtual_collateral * collateral_price / total_lent;
+            let max_input = total_lent
+                * (Decimal::ONE - virtual_collateralization_ratio / self.liquidation_threshold)
+                    .sqrt()
+                    .unwrap();
+
+            // A single liquidation cannot repay more than the close factor of the debt
+            max_input
+                .min(stablecoin_input)
+                .min(total_lent * self.close_factor)
+        }
 
-            price
+        /// Checks that the parameters of a lender are consistent
+        fn assert_parameters(
+            loan_to_value: Decimal,
+            interest_rate: Decimal,
+            liquidation_threshold: Decimal,
+            liquidator_bonus: Decimal,
+            protocol_liquidation_share: Decimal,
+            close_factor: Decimal,
+        ) {
+            assert!(
+                loan_to_value.is_positive() && loan_to_value < Decimal::ONE,
+                "LTV should be such that 0<LTV<1"
+            );
+            assert!(
+                interest_rate.is_positive() && interest_rate < Decimal::ONE,
+                "The daily interest rate should be such that 0<DIR<1"
+            );
+            assert!(
+                liquidation_threshold > Decimal::ONE,
+                "The liquidation threshold should be greater than one"
+            );
+            assert!(
+                liquidation_threshold * loan_to_value < Decimal::ONE,
+                "The LTV-liquidation threshold product should be smaller than one"
+            );
+            assert!(
+                liquidator_bonus.is_positive(),
+                "The liquidator bonus should be positive"
+            );
+            assert!(
+                liquidator_bonus < Decimal::ONE,
+                "The liquidator bonus should be smaller than one"
+            );
+            assert!(
+                !protocol_liquidation_share.is_negative()
+                    && protocol_liquidation_share <= Decimal::ONE,
+                "The protocol liquidation share should be such that 0<=PLS<=1"
+            );
+            assert!(
+                close_factor.is_positive() && close_factor <= Decimal::ONE,
+                "The close factor should be such that 0<CF<=1"
+            );
         }
 
-        fn compute_interests(&self, loan: &Loan) -> Decimal {
+        /// Returns the rate accumulator compounded up to the current time
+        fn current_rate_accumulator(&self) -> Decimal {
             let current_time = Clock::current_time(TimePrecision::Minute).seconds_since_unix_epoch;
-            let loan_days_duration = Decimal::from(current_time - loan.loan_date) / SECONDS_PER_DAY;
+            let days_elapsed =
+                Decimal::from(current_time - self.last_accumulator_update) / SECONDS_PER_DAY;
+
+            self.rate_accumulator * exp(self.interest_rate * days_elapsed)
+        }
 
-            loan.amount_lent * loan_days_duration * loan.interest_rate
+        /// Returns the current debt of a loan, interests included
+        fn compute_debt(&self, loan: &Loan) -> Decimal {
+            loan.normalised_debt * self.current_rate_accumulator()
         }
     }
 }
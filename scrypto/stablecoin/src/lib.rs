@@ -1,5 +1,19 @@
+pub mod auction;
+pub mod basket_loan;
 pub mod constants;
+pub mod flash_loan;
 pub mod flash_mint;
 pub mod issuer;
 pub mod lender;
 pub mod loan;
+pub mod loan_event;
+pub mod minter_badge;
+pub mod minter_registry;
+pub mod price_feed;
+pub mod price_report;
+pub mod psm;
+pub mod reporter_badge;
+pub mod savings_vault;
+pub mod stability_deposit;
+pub mod stability_pool;
+pub mod system_auction;
@@ -15,11 +15,12 @@ pub struct Loan {
     #[mutable]
     pub amount_lent: Decimal,
 
+    /// Debt of the loan divided by the rate accumulator of the lender
+    #[mutable]
+    pub normalised_debt: Decimal,
+
     /// Time at the moment of loan,
     pub loan_date: i64,
-
-    /// Daily interest rate at the moment of loan
-    pub interest_rate: Decimal,
 }
 
 impl Loan {
@@ -27,15 +28,15 @@ impl Loan {
         collateral_token: ResourceAddress,
         collateral_amount: Decimal,
         amount_lent: Decimal,
+        normalised_debt: Decimal,
         loan_date: i64,
-        interest_rate: Decimal,
     ) -> Self {
         Self {
             collateral_token,
             collateral_amount,
             amount_lent,
+            normalised_debt,
             loan_date,
-            interest_rate,
         }
     }
 }
@@ -0,0 +1,111 @@
+//! Definition of the events recorded in the history of a loan or of a basket loan
+
+use scrypto::prelude::*;
+
+#[derive(ScryptoCategorize, LegacyDescribe, ScryptoEncode, ScryptoDecode, Clone, PartialEq)]
+pub enum LoanEventKind {
+    /// The loan was taken
+    Open,
+
+    /// Collateral was added to the loan
+    AddCollateral,
+
+    /// Collateral was removed from the loan
+    RemoveCollateral,
+
+    /// More stablecoins were borrowed against the loan
+    BorrowMore,
+
+    /// Part of the debt of the loan was repaid
+    Repayment,
+
+    /// The debt of the loan was fully repaid and its collateral given back
+    Closing,
+
+    /// The loan was liquidated, by a liquidator or by the stability pool
+    Liquidation,
+
+    /// The collateral of the loan was put up for auction
+    AuctionStart,
+
+    /// The collateral of the loan was bought at auction
+    AuctionSale,
+
+    /// The bad debt of the loan was cleared
+    BadDebtClearing,
+
+    /// Stablecoins were redeemed against the loan
+    Redemption,
+}
+
+#[derive(ScryptoCategorize, LegacyDescribe, ScryptoEncode, ScryptoDecode, Clone)]
+pub struct LoanEvent {
+    /// Kind of event
+    pub kind: LoanEventKind,
+
+    /// Time of the event
+    pub timestamp: i64,
+
+    /// Change of the amount of collateral of the loan
+    pub collateral_change: Decimal,
+
+    /// Change of the amount of stablecoins lent by the loan
+    pub principal_change: Decimal,
+
+    /// Stablecoins received by the borrower or paid towards the debt of the loan
+    pub stablecoins: Decimal,
+}
+
+impl LoanEvent {
+    pub fn new(
+        kind: LoanEventKind,
+        timestamp: i64,
+        collateral_change: Decimal,
+        principal_change: Decimal,
+        stablecoins: Decimal,
+    ) -> Self {
+        Self {
+            kind,
+            timestamp,
+            collateral_change,
+            principal_change,
+            stablecoins,
+        }
+    }
+}
+
+#[derive(ScryptoCategorize, LegacyDescribe, ScryptoEncode, ScryptoDecode, Clone)]
+pub struct BasketLoanEvent {
+    /// Kind of event
+    pub kind: LoanEventKind,
+
+    /// Time of the event
+    pub timestamp: i64,
+
+    /// Change of the amount of each collateral token of the basket loan
+    pub collateral_changes: HashMap<ResourceAddress, Decimal>,
+
+    /// Change of the amount of stablecoins lent against each collateral token
+    pub principal_changes: HashMap<ResourceAddress, Decimal>,
+
+    /// Stablecoins received by the borrower or paid towards the debt of the basket loan
+    pub stablecoins: Decimal,
+}
+
+impl BasketLoanEvent {
+    pub fn new(
+        kind: LoanEventKind,
+        timestamp: i64,
+        collateral_changes: HashMap<ResourceAddress, Decimal>,
+        principal_changes: HashMap<ResourceAddress, Decimal>,
+        stablecoins: Decimal,
+    ) -> Self {
+        Self {
+            kind,
+            timestamp,
+            collateral_changes,
+            principal_changes,
+            stablecoins,
+        }
+    }
+}
@@ -0,0 +1,11 @@
+//! Definition of the MinterBadge NFR which allows its owner to mint stablecoins through a MinterRegistry
+
+use scrypto::prelude::*;
+
+#[derive(
+    NonFungibleData, ScryptoCategorize, LegacyDescribe, ScryptoEncode, ScryptoDecode, Clone,
+)]
+pub struct MinterBadge {
+    /// Name of the minter
+    pub name: String,
+}
@@ -0,0 +1,272 @@
+//! # MinterRegistry Blueprint
+//!
+//! Implements a registry through which external protocols mint and burn stablecoins. The registry
+//! owns a stablecoin minter granted by the DAO and each external protocol owns a [`MinterBadge`]
+//! NFR. The stablecoins minted and not yet burned by each minter cannot exceed its cap, which can
+//! be lowered or set to zero by revoking the minter.
+//!
+//! # Functions & Methods
+//!
+//! ### Function
+//! - [new](MinterRegistryComponent::new) - Instantiates and globalizes a new [`MinterRegistryComponent`] and returns its address and the address of the minter badge.
+//!
+//! ### Methods
+//! - [new_minter](MinterRegistryComponent::new_minter) - Mints a new whitelisted [`MinterBadge`] with a given cap.
+//! - [change_minter_cap](MinterRegistryComponent::change_minter_cap) - Changes the cap of a minter.
+//! - [revoke_minter](MinterRegistryComponent::revoke_minter) - Removes a [`MinterBadge`] from the whitelist.
+//! - [mint](MinterRegistryComponent::mint) - Mints stablecoins for a whitelisted minter.
+//! - [burn](MinterRegistryComponent::burn) - Burns stablecoins minted by a minter.
+//! - [get_minter_usage](MinterRegistryComponent::get_minter_usage) - Returns the cap and the outstanding mint of a minter.
+//! - [get_outstanding_supply](MinterRegistryComponent::get_outstanding_supply) - Returns the stablecoins minted and not yet burned by all minters.
+
+use scrypto::blueprint;
+
+#[blueprint]
+mod minter_registry {
+    use crate::minter_badge::MinterBadge;
+
+    pub struct MinterRegistry {
+        /// Vault holding the stablecoin minter granted by the DAO
+        stablecoin_minter: Vault,
+
+        /// ResourceAddress of the stablecoin
+        stablecoin_address: ResourceAddress,
+
+        /// Ids of the [`MinterBadge`]s allowed to mint stablecoins
+        whitelisted_minters: HashSet<NonFungibleLocalId>,
+
+        /// Maximum amount of stablecoins minted and not yet burned by each minter
+        minter_caps: HashMap<NonFungibleLocalId, Decimal>,
+
+        /// Amount of stablecoins minted and not yet burned by each minter
+        outstanding_mints: HashMap<NonFungibleLocalId, Decimal>,
+
+        /// Vault used to mint [`MinterBadge`]s
+        minter_badge_minter: Vault,
+
+        /// ResourceAddress of the [`MinterBadge`] NFR
+        minter_badge_address: ResourceAddress,
+
+        /// Id of the next minter badge to be minted
+        minter_id: u64,
+    }
+
+    impl MinterRegistry {
+        /// Instantiates and globalizes a new [`MinterRegistryComponent`] and returns its address
+        /// and the address of the minter badge.
+        ///
+        /// # Arguments
+        /// * `admin_badge` - ResourceAddress of the admin badge controlling the registry
+        /// * `stablecoin_minter` - Stablecoin minter granted by the DAO
+        /// * `stablecoin_address` - ResourceAddress of the stablecoin
+        pub fn new(
+            admin_badge: ResourceAddress,
+            stablecoin_minter: Bucket,
+            stablecoin_address: ResourceAddress,
+        ) -> (ComponentAddress, ResourceAddress) {
+            // Creates the minter badge minter
+            let minter_badge_minter = ResourceBuilder::new_fungible()
+                .divisibility(DIVISIBILITY_NONE)
+                .mint_initial_supply(Decimal::ONE);
+
+            // Creates the NFR MinterBadge address
+            let minter_badge_address = ResourceBuilder::new_integer_non_fungible()
+                .metadata("name", "Stoichiometric Stablecoin Minter Badge")
+                .mintable(
+                    rule!(require(minter_badge_minter.resource_address())),
+                    AccessRule::DenyAll,
+                )
+                .burnable(
+                    rule!(require(minter_badge_minter.resource_address())),
+                    AccessRule::DenyAll,
+                )
+                .create_with_no_initial_supply();
+
+            let minter_registry_rules = AccessRules::new()
+                .method("mint", AccessRule::AllowAll, AccessRule::DenyAll)
+                .method("burn", AccessRule::AllowAll, AccessRule::DenyAll)
+                .method(
+                    "get_minter_usage",
+                    AccessRule::AllowAll,
+                    AccessRule::DenyAll,
+                )
+                .method(
+                    "get_outstanding_supply",
+                    AccessRule::AllowAll,
+                    AccessRule::DenyAll,
+                )
+                .default(rule!(require(admin_badge)), AccessRule::DenyAll);
+
+            let mut component = Self {
+                stablecoin_minter: Vault::with_bucket(stablecoin_minter),
+                stablecoin_address,
+                whitelisted_minters: HashSet::new(),
+                minter_caps: HashMap::new(),
+                outstanding_mints: HashMap::new(),
+                minter_badge_minter: Vault::with_bucket(minter_badge_minter),
+                minter_badge_address,
+                minter_id: 0,
+            }
+            .instantiate();
+
+            component.add_access_check(minter_registry_rules);
+            (component.globalize(), minter_badge_address)
+        }
+
+        /// Mints a new whitelisted [`MinterBadge`] with a given cap.
+        ///
+        /// # Access Rule
+        /// Can only be called by the owner of the admin badge
+        ///
+        /// # Arguments
+        /// * `name` - Name of the new minter
+        /// * `cap` - Maximum amount of stablecoins minted and not yet burned by the minter
+        pub fn new_minter(&mut self, name: String, cap: Decimal) -> Bucket {
+            Self::check_cap(cap);
+
+            let minter_id = NonFungibleLocalId::Integer(self.minter_id.into());
+            let minter_badge = self.minter_badge_minter.authorize(|| {
+                borrow_resource_manager!(self.minter_badge_address)
+                    .mint_non_fungible(&minter_id, MinterBadge { name })
+            });
+
+            self.whitelisted_minters.insert(minter_id.clone());
+            self.minter_caps.insert(minter_id.clone(), cap);
+            self.outstanding_mints.insert(minter_id, Decimal::ZERO);
+            self.minter_id += 1;
+
+            minter_badge
+        }
+
+        /// Changes the cap of a minter. A cap lower than the outstanding mint of the minter
+        /// prevents it from minting until it burns enough stablecoins.
+        ///
+        /// # Access Rule
+        /// Can only be called by the owner of the admin badge
+        ///
+        /// # Arguments
+        /// * `minter_id` - Id of the [`MinterBadge`]
+        /// * `cap` - New maximum amount of stablecoins minted and not yet burned by the minter
+        pub fn change_minter_cap(&mut self, minter_id: NonFungibleLocalId, cap: Decimal) {
+            Self::check_cap(cap);
+
+            match self.minter_caps.get_mut(&minter_id) {
+                None => panic!("There is no minter with this id"),
+                Some(minter_cap) => *minter_cap = cap,
+            }
+        }
+
+        /// Removes a [`MinterBadge`] from the whitelist. The minter can still burn the
+        /// stablecoins it minted.
+        ///
+        /// # Access Rule
+        /// Can only be called by the owner of the admin badge
+        ///
+        /// # Arguments
+        /// * `minter_id` - Id of the [`MinterBadge`] to revoke
+        pub fn revoke_minter(&mut self, minter_id: NonFungibleLocalId) {
+            assert!(
+                self.whitelisted_minters.remove(&minter_id),
+                "This minter is not whitelisted"
+            );
+            self.minter_caps.insert(minter_id, Decimal::ZERO);
+        }
+
+        /// Mints stablecoins for a whitelisted minter.
+        ///
+        /// # Arguments
+        /// * `minter_proof` - Proof of a whitelisted [`MinterBadge`]
+        /// * `amount` - Amount of stablecoins to mint
+        pub fn mint(&mut self, minter_proof: Proof, amount: Decimal) -> Bucket {
+            let minter_id = self.check_minter_proof(minter_proof);
+            assert!(
+                self.whitelisted_minters.contains(&minter_id),
+                "This minter is not allowed to mint stablecoins"
+            );
+            assert!(
+                amount.is_positive(),
+                "The amount to mint should be positive"
+            );
+
+            let cap = *self.minter_caps.get(&minter_id).unwrap();
+            let outstanding_mint = self.outstanding_mints.get_mut(&minter_id).unwrap();
+            assert!(
+                *outstanding_mint + amount <= cap,
+                "Cannot mint more than {} stablecoins",
+                (cap - *outstanding_mint).max(Decimal::ZERO)
+            );
+            *outstanding_mint += amount;
+
+            self.stablecoin_minter
+                .authorize(|| borrow_resource_manager!(self.stablecoin_address).mint(amount))
+        }
+
+        /// Burns stablecoins minted by a minter.
+        ///
+        /// # Arguments
+        /// * `minter_proof` - Proof of a [`MinterBadge`], whitelisted or not
+        /// * `stablecoins` - Stablecoins to burn
+        pub fn burn(&mut self, minter_proof: Proof, stablecoins: Bucket) {
+            let minter_id = self.check_minter_proof(minter_proof);
+            assert!(
+                stablecoins.resource_address() == self.stablecoin_address,
+                "Please provide stablecoins to burn"
+            );
+
+            let outstanding_mint = self.outstanding_mints.get_mut(&minter_id).unwrap();
+            assert!(
+                stablecoins.amount() <= *outstanding_mint,
+                "Cannot burn more than {} stablecoins",
+                *outstanding_mint
+            );
+            *outstanding_mint -= stablecoins.amount();
+
+            self.stablecoin_minter
+                .authorize(|| borrow_resource_manager!(self.stablecoin_address).burn(stablecoins));
+        }
+
+        /// Returns the cap and the outstanding mint of a minter and whether it is whitelisted.
+        ///
+        /// # Arguments
+        /// * `minter_id` - Id of the [`MinterBadge`]
+        pub fn get_minter_usage(&self, minter_id: NonFungibleLocalId) -> (Decimal, Decimal, bool) {
+            match self.outstanding_mints.get(&minter_id) {
+                None => panic!("There is no minter with this id"),
+                Some(outstanding_mint) => (
+                    *self.minter_caps.get(&minter_id).unwrap(),
+                    *outstanding_mint,
+                    self.whitelisted_minters.contains(&minter_id),
+                ),
+            }
+        }
+
+        /// Returns the amount of stablecoins minted and not yet burned by all minters, revoked
+        /// minters included.
+        pub fn get_outstanding_supply(&self) -> Decimal {
+            self.outstanding_mints
+                .values()
+                .fold(Decimal::ZERO, |total, outstanding_mint| {
+                    total + *outstanding_mint
+                })
+        }
+
+        /// Internal method that checks a proof of a single minter badge and returns its id.
+        #[inline]
+        fn check_minter_proof(&self, minter_proof: Proof) -> NonFungibleLocalId {
+            let valid_proof = minter_proof
+                .validate_proof(ProofValidationMode::ValidateContainsAmount(
+                    self.minter_badge_address,
+                    Decimal::ONE,
+                ))
+                .expect("Please provide a valid proof of a single minter badge");
+
+            valid_proof.non_fungible::<MinterBadge>().local_id().clone()
+        }
+
+        /// Internal function that checks the validity of a minter cap.
+        #[inline]
+        fn check_cap(cap: Decimal) {
+            assert!(!cap.is_negative(), "The minter cap should be non negative");
+        }
+    }
+}
@@ -0,0 +1,357 @@
+//! # PriceFeed Blueprint
+//!
+//! Implements a price oracle fed by whitelisted reporters. Each reporter owns a [`ReporterBadge`]
+//! NFR and pushes timestamped prices that are checked for freshness and bounds before being
+//! stored. The blueprint exposes the same `get_twap_since` method as the DEX router so that it
+//! can be used as the oracle of a `Lender`.
+//!
+//! # Functions & Methods
+//!
+//! ### Function
+//! - [new](PriceFeedComponent::new) - Instantiates and globalizes a new [`PriceFeedComponent`] and returns its address and the address of the reporter badge.
+//!
+//! ### Methods
+//! - [new_reporter](PriceFeedComponent::new_reporter) - Mints a new whitelisted [`ReporterBadge`].
+//! - [revoke_reporter](PriceFeedComponent::revoke_reporter) - Removes a [`ReporterBadge`] from the whitelist.
+//! - [add_feed](PriceFeedComponent::add_feed) - Starts accepting reports for a new token.
+//! - [change_feed_bounds](PriceFeedComponent::change_feed_bounds) - Changes the accepted price range of a feed.
+//! - [change_parameters](PriceFeedComponent::change_parameters) - Changes the freshness and deviation parameters.
+//! - [report_price](PriceFeedComponent::report_price) - Pushes a new price for a given token.
+//! - [get_twap_since](PriceFeedComponent::get_twap_since) - Returns the time-weighted average price of a token since a given time.
+//! - [get_truncated_twap_since](PriceFeedComponent::get_truncated_twap_since) - Same as `get_twap_since`, reports being already bounded.
+//! - [get_last_report](PriceFeedComponent::get_last_report) - Returns the last price reported for a token and its timestamp.
+
+use scrypto::blueprint;
+
+#[blueprint]
+mod price_feed {
+    use crate::price_report::PriceReportArray;
+    use crate::reporter_badge::ReporterBadge;
+
+    pub struct PriceFeed {
+        /// Reports made for each token
+        feeds: HashMap<ResourceAddress, PriceReportArray>,
+
+        /// Minimum and maximum prices accepted for each token
+        price_bounds: HashMap<ResourceAddress, (Decimal, Decimal)>,
+
+        /// Ids of the [`ReporterBadge`]s allowed to report prices
+        whitelisted_reporters: HashSet<NonFungibleLocalId>,
+
+        /// Vault used to mint [`ReporterBadge`]s
+        reporter_badge_minter: Vault,
+
+        /// ResourceAddress of the [`ReporterBadge`] NFR
+        reporter_badge_address: ResourceAddress,
+
+        /// Id of the next reporter badge to be minted
+        reporter_id: u64,
+
+        /// Maximum age in seconds of a report when it is pushed and when it is read
+        max_report_age: i64,
+
+        /// Maximum relative change between two consecutive reports of a token
+        max_price_deviation: Decimal,
+    }
+
+    impl PriceFeed {
+        /// Instantiates and globalizes a new [`PriceFeedComponent`] and returns its address and
+        /// the address of the reporter badge.
+        ///
+        /// # Arguments
+        /// * `admin_badge` - ResourceAddress of the admin badge controlling the price feed
+        /// * `max_report_age` - Maximum age in seconds of a report
+        /// * `max_price_deviation` - Maximum relative change between two consecutive reports
+        pub fn new(
+            admin_badge: ResourceAddress,
+            max_report_age: i64,
+            max_price_deviation: Decimal,
+        ) -> (ComponentAddress, ResourceAddress) {
+            Self::check_parameters(max_report_age, max_price_deviation);
+
+            // Creates the reporter badge minter
+            let reporter_badge_minter = ResourceBuilder::new_fungible()
+                .divisibility(DIVISIBILITY_NONE)
+                .mint_initial_supply(Decimal::ONE);
+
+            // Creates the NFR ReporterBadge address
+            let reporter_badge_address = ResourceBuilder::new_integer_non_fungible()
+                .metadata("name", "Stoichiometric Price Reporter")
+                .mintable(
+                    rule!(require(reporter_badge_minter.resource_address())),
+                    AccessRule::DenyAll,
+                )
+                .burnable(
+                    rule!(require(reporter_badge_minter.resource_address())),
+                    AccessRule::DenyAll,
+                )
+                .create_with_no_initial_supply();
+
+            let price_feed_rules = AccessRules::new()
+                .method("report_price", AccessRule::AllowAll, AccessRule::DenyAll)
+                .method("get_twap_since", AccessRule::AllowAll, AccessRule::DenyAll)
+                .method(
+                    "get_truncated_twap_since",
+                    AccessRule::AllowAll,
+                    AccessRule::DenyAll,
+                )
+                .method("get_last_report", AccessRule::AllowAll, AccessRule::DenyAll)
+                .default(rule!(require(admin_badge)), AccessRule::DenyAll);
+
+            let mut component = Self {
+                feeds: HashMap::new(),
+                price_bounds: HashMap::new(),
+                whitelisted_reporters: HashSet::new(),
+                reporter_badge_minter: Vault::with_bucket(reporter_badge_minter),
+                reporter_badge_address,
+                reporter_id: 0,
+                max_report_age,
+                max_price_deviation,
+            }
+            .instantiate();
+
+            component.add_access_check(price_feed_rules);
+            (component.globalize(), reporter_badge_address)
+        }
+
+        /// Mints a new whitelisted [`ReporterBadge`].
+        ///
+        /// # Access Rule
+        /// Can only be called by the owner of the admin badge
+        ///
+        /// # Arguments
+        /// * `name` - Name of the new reporter
+        pub fn new_reporter(&mut self, name: String) -> Bucket {
+            let reporter_id = NonFungibleLocalId::Integer(self.reporter_id.into());
+            let reporter_badge = self.reporter_badge_minter.authorize(|| {
+                borrow_resource_manager!(self.reporter_badge_address)
+                    .mint_non_fungible(&reporter_id, ReporterBadge { name })
+            });
+
+            self.whitelisted_reporters.insert(reporter_id);
+            self.reporter_id += 1;
+
+            reporter_badge
+        }
+
+        /// Removes a [`ReporterBadge`] from the whitelist.
+        ///
+        /// # Access Rule
+        /// Can only be called by the owner of the admin badge
+        ///
+        /// # Arguments
+        /// * `reporter_id` - Id of the [`ReporterBadge`] to revoke
+        pub fn revoke_reporter(&mut self, reporter_id: NonFungibleLocalId) {
+            assert!(
+                self.whitelisted_reporters.remove(&reporter_id),
+                "This reporter is not whitelisted"
+            );
+        }
+
+        /// Starts accepting reports for a new token.
+        ///
+        /// # Access Rule
+        /// Can only be called by the owner of the admin badge
+        ///
+        /// # Arguments
+        /// * `token` - ResourceAddress of the token to report prices for
+        /// * `min_price` - Minimum price that can be reported
+        /// * `max_price` - Maximum price that can be reported
+        pub fn add_feed(&mut self, token: ResourceAddress, min_price: Decimal, max_price: Decimal) {
+            assert!(
+                self.feeds.get(&token).is_none(),
+                "There is already a feed for the given token"
+            );
+            Self::check_bounds(min_price, max_price);
+
+            self.feeds.insert(token, PriceReportArray::new());
+            self.price_bounds.insert(token, (min_price, max_price));
+        }
+
+        /// Changes the accepted price range of a feed.
+        ///
+        /// # Access Rule
+        /// Can only be called by the owner of the admin badge
+        ///
+        /// # Arguments
+        /// * `token` - ResourceAddress of the token of the feed
+        /// * `min_price` - New minimum price that can be reported
+        /// * `max_price` - New maximum price that can be reported
+        pub fn change_feed_bounds(
+            &mut self,
+            token: ResourceAddress,
+            min_price: Decimal,
+            max_price: Decimal,
+        ) {
+            Self::check_bounds(min_price, max_price);
+
+            match self.price_bounds.get_mut(&token) {
+                None => panic!("There is no feed for this token"),
+                Some(bounds) => *bounds = (min_price, max_price),
+            }
+        }
+
+        /// Changes the freshness and deviation parameters.
+        ///
+        /// # Access Rule
+        /// Can only be called by the owner of the admin badge
+        ///
+        /// # Arguments
+        /// * `max_report_age` - New maximum age in seconds of a report
+        /// * `max_price_deviation` - New maximum relative change between two consecutive reports
+        pub fn change_parameters(&mut self, max_report_age: i64, max_price_deviation: Decimal) {
+            Self::check_parameters(max_report_age, max_price_deviation);
+
+            self.max_report_age = max_report_age;
+            self.max_price_deviation = max_price_deviation;
+        }
+
+        /// Pushes a new price for a given token.
+        ///
+        /// # Arguments
+        /// * `reporter_proof` - Proof of a whitelisted [`ReporterBadge`]
+        /// * `token` - ResourceAddress of the token whose price is reported
+        /// * `price` - Price of the token in stablecoins
+        /// * `timestamp` - Time at which the price was observed by the reporter
+        pub fn report_price(
+            &mut self,
+            reporter_proof: Proof,
+            token: ResourceAddress,
+            price: Decimal,
+            timestamp: i64,
+        ) {
+            let valid_proof = reporter_proof
+                .validate_proof(ProofValidationMode::ValidateContainsAmount(
+                    self.reporter_badge_address,
+                    Decimal::ONE,
+                ))
+                .expect("Please provide a valid proof of a single reporter badge");
+            let reporter_id = valid_proof
+                .non_fungible::<ReporterBadge>()
+                .local_id()
+                .clone();
+            assert!(
+                self.whitelisted_reporters.contains(&reporter_id),
+                "This reporter is not allowed to report prices"
+            );
+
+            let (min_price, max_price) = match self.price_bounds.get(&token) {
+                None => panic!("There is no feed for this token"),
+                Some(bounds) => *bounds,
+            };
+            assert!(
+                price >= min_price && price <= max_price,
+                "The reported price should be in the range [{}, {}]",
+                min_price,
+                max_price
+            );
+
+            let current_time = Clock::current_time(TimePrecision::Minute).seconds_since_unix_epoch;
+            assert!(
+                timestamp <= current_time,
+                "Cannot report a price from the future"
+            );
+            assert!(
+                current_time - timestamp <= self.max_report_age,
+                "The report is too old"
+            );
+
+            let max_price_deviation = self.max_price_deviation;
+            let reports = self.feeds.get_mut(&token).unwrap();
+            match reports.last() {
+                None => {}
+                Some(last_report) => {
+                    assert!(
+                        timestamp > last_report.timestamp,
+                        "A more recent price has already been reported"
+                    );
+
+                    let deviation = (price - last_report.price).abs() / last_report.price;
+                    assert!(
+                        deviation <= max_price_deviation,
+                        "The reported price deviates by {} from the last one",
+                        deviation
+                    );
+                }
+            }
+
+            reports.push(timestamp, price);
+        }
+
+        /// Returns the time-weighted average price of a token since a given time.
+        ///
+        /// # Arguments
+        /// * `token` - ResourceAddress of the token to get the price of
+        /// * `timestamp` - Time from which to compute the average
+        pub fn get_twap_since(&self, token: ResourceAddress, timestamp: i64) -> Decimal {
+            let reports = self.get_feed(&token);
+            let current_time = Clock::current_time(TimePrecision::Minute).seconds_since_unix_epoch;
+
+            let last_report = reports.last().expect("No price has been reported yet");
+            assert!(
+                current_time - last_report.timestamp <= self.max_report_age,
+                "The price feed is stale"
+            );
+
+            reports.get_time_weighted_average_price_since(timestamp, current_time)
+        }
+
+        /// Returns the time-weighted average price of a token since a given time. Reports are
+        /// already bounded by the maximum price deviation, so this is the same as
+        /// [`get_twap_since`](PriceFeedComponent::get_twap_since).
+        ///
+        /// # Arguments
+        /// * `token` - ResourceAddress of the token to get the price of
+        /// * `timestamp` - Time from which to compute the average
+        pub fn get_truncated_twap_since(&self, token: ResourceAddress, timestamp: i64) -> Decimal {
+            self.get_twap_since(token, timestamp)
+        }
+
+        /// Returns the last price reported for a token and its timestamp.
+        ///
+        /// # Arguments
+        /// * `token` - ResourceAddress of the token to get the last report of
+        pub fn get_last_report(&self, token: ResourceAddress) -> (Decimal, i64) {
+            let last_report = self
+                .get_feed(&token)
+                .last()
+                .expect("No price has been reported yet");
+            (last_report.price, last_report.timestamp)
+        }
+
+        /// Internal method that returns the reports of a given token.
+        #[inline]
+        fn get_feed(&self, token: &ResourceAddress) -> &PriceReportArray {
+            match self.feeds.get(token) {
+                None => panic!("There is no feed for this token"),
+                Some(reports) => reports,
+            }
+        }
+
+        /// Internal function that checks the validity of price bounds.
+        #[inline]
+        fn check_bounds(min_price: Decimal, max_price: Decimal) {
+            assert!(
+                min_price.is_positive(),
+                "The minimum price should be positive"
+            );
+            assert!(
+                max_price > min_price,
+                "The maximum price should be greater than the minimum price"
+            );
+        }
+
+        /// Internal function that checks the validity of the freshness and deviation parameters.
+        #[inline]
+        fn check_parameters(max_report_age: i64, max_price_deviation: Decimal) {
+            assert!(
+                max_report_age > 0,
+                "The maximum report age should be positive"
+            );
+            assert!(
+                max_price_deviation.is_positive(),
+                "The maximum price deviation should be positive"
+            );
+        }
+    }
+}
@@ -0,0 +1,112 @@
+//! Circular Array used by the [`PriceFeed`](crate::price_feed) to store signed price reports
+
+use scrypto::prelude::*;
+use stoichiometric_dex::timestamped_array::{Timestamped, TimestampedArray};
+
+const ARRAY_LENGTH: u16 = 4096;
+
+#[derive(ScryptoCategorize, LegacyDescribe, ScryptoEncode, ScryptoDecode, Clone)]
+pub struct PriceReport {
+    /// Timestamp given by the reporter
+    pub timestamp: i64,
+
+    /// Reported price of the token in stablecoins
+    pub price: Decimal,
+}
+
+impl PriceReport {
+    pub fn from(timestamp: i64, price: Decimal) -> Self {
+        Self { timestamp, price }
+    }
+}
+
+impl Timestamped for PriceReport {
+    const NAME: &'static str = "Price reports";
+
+    fn timestamp(&self) -> i64 {
+        self.timestamp
+    }
+}
+
+#[derive(ScryptoCategorize, LegacyDescribe, ScryptoEncode, ScryptoDecode, Clone)]
+pub struct PriceReportArray {
+    /// Circular Array that stores the reports
+    reports: TimestampedArray<PriceReport>,
+}
+
+impl PriceReportArray {
+    pub fn new() -> Self {
+        Self {
+            reports: TimestampedArray::with_capacity(ARRAY_LENGTH),
+        }
+    }
+
+    /// Returns the number of reports stored in the array
+    pub fn len(&self) -> usize {
+        self.reports.len()
+    }
+
+    /// Returns whether the array does not contain any report
+    pub fn is_empty(&self) -> bool {
+        self.reports.is_empty()
+    }
+
+    /// Pushes a new report in the array, overwriting the oldest one if the array is full
+    ///
+    /// # Arguments
+    /// * `timestamp` - timestamp of the report
+    /// * `price` - reported price
+    pub fn push(&mut self, timestamp: i64, price: Decimal) {
+        self.reports.push(PriceReport::from(timestamp, price));
+    }
+
+    /// Gets a report at a given index, the index 0 being the oldest report
+    ///
+    /// # Arguments
+    /// * `index` - index of the report to get
+    pub fn get(&self, index: usize) -> Option<&PriceReport> {
+        self.reports.get(index)
+    }
+
+    /// Returns the most recent report
+    pub fn last(&self) -> Option<&PriceReport> {
+        self.reports.last()
+    }
+
+    /// Returns the time weighted average price since a given timestamp.
+    ///
+    /// If the first report was made after `timestamp`, the average is computed from this
+    /// first report.
+    ///
+    /// # Arguments
+    /// * `timestamp` - timestamp to compute the average since
+    /// * `current_timestamp` - timestamp at request
+    pub fn get_time_weighted_average_price_since(
+        &self,
+        timestamp: i64,
+        current_timestamp: i64,
+    ) -> Decimal {
+        assert!(!self.is_empty(), "No price has been reported yet");
+
+        let len = self.len();
+        let start_index = self.reports.index_at(timestamp);
+        let begin = timestamp.max(self.get(start_index).unwrap().timestamp);
+
+        if current_timestamp <= begin {
+            return self.last().unwrap().price;
+        }
+
+        let mut total = Decimal::ZERO;
+        for i in start_index..len {
+            let report = self.get(i).unwrap();
+            let from = report.timestamp.max(begin);
+            let to = match self.get(i + 1) {
+                Some(next_report) => next_report.timestamp,
+                None => current_timestamp,
+            };
+            total += report.price * (to - from);
+        }
+
+        total / (current_timestamp - begin)
+    }
+}
@@ -0,0 +1,275 @@
+//! # Peg Stability Module Blueprint
+//!
+//! Implements 1:1 swaps between stablecoins and whitelisted external stablecoins. Stablecoins
+//! are minted against deposited assets minus a fee in and redeemed for them minus a fee out. The
+//! amount of stablecoins minted against each asset is capped by the protocol. Redemptions are
+//! rounded down to the divisibility of the asset and only the matching stablecoins are charged.
+//!
+//! The module mints stablecoins with a stablecoin minter badge granted by the DAO through a
+//! `GrantIssuingRight` proposal.
+//!
+//! # Functions & Methods
+//!
+//! ### Function
+//! - [new](PsmComponent::new) - Instantiates and globalizes a new [`PsmComponent`] and returns its address.
+//!
+//! ### Methods
+//! - [mint_stablecoins](PsmComponent::mint_stablecoins) - Mints stablecoins one for one against whitelisted assets.
+//! - [redeem_stablecoins](PsmComponent::redeem_stablecoins) - Redeems stablecoins one for one for a whitelisted asset.
+//! - [set_asset_parameters](PsmComponent::set_asset_parameters) - Whitelists an asset or changes its fees and cap.
+//! - [claim_fees](PsmComponent::claim_fees) - Sends the stablecoins paid as fees.
+//! - [get_asset_state](PsmComponent::get_asset_state) - Returns the amount held, the fees and the cap of an asset.
+//! - [get_outstanding_supply](PsmComponent::get_outstanding_supply) - Returns the stablecoins minted against all assets and not yet redeemed.
+
+use scrypto::blueprint;
+
+#[blueprint]
+mod psm {
+    pub struct Psm {
+        /// Vault holding the stablecoin minter granted by the DAO
+        stablecoin_minter: Vault,
+
+        /// ResourceAddress of the stablecoin
+        stablecoin_address: ResourceAddress,
+
+        /// ResourceAddress of the admin badge controlling the module
+        admin_badge: ResourceAddress,
+
+        /// Assets backing the stablecoins minted by the module, one for one
+        assets: HashMap<ResourceAddress, Vault>,
+
+        /// Share of the deposited assets taken as fee when minting stablecoins
+        fees_in: HashMap<ResourceAddress, Decimal>,
+
+        /// Share of the stablecoins taken as fee when redeeming them for an asset
+        fees_out: HashMap<ResourceAddress, Decimal>,
+
+        /// Maximum amount of stablecoins that can be minted against each asset
+        caps: HashMap<ResourceAddress, Decimal>,
+
+        /// Stablecoins paid as fees and not yet claimed by the protocol
+        fees: Vault,
+
+        /// Divisibility of each whitelisted asset
+        divisibilities: HashMap<ResourceAddress, u8>,
+    }
+
+    impl Psm {
+        /// Instantiates and globalizes a new [`PsmComponent`] and returns its address.
+        ///
+        /// # Arguments
+        /// * `admin_badge` - ResourceAddress of the admin badge controlling the module
+        /// * `stablecoin_minter` - Stablecoin minter granted by the DAO
+        /// * `stablecoin_address` - ResourceAddress of the stablecoin
+        pub fn new(
+            admin_badge: ResourceAddress,
+            stablecoin_minter: Bucket,
+            stablecoin_address: ResourceAddress,
+        ) -> ComponentAddress {
+            let psm_rules = AccessRules::new()
+                .method(
+                    "mint_stablecoins",
+                    AccessRule::AllowAll,
+                    AccessRule::DenyAll,
+                )
+                .method(
+                    "redeem_stablecoins",
+                    AccessRule::AllowAll,
+                    AccessRule::DenyAll,
+                )
+                .method("get_asset_state", AccessRule::AllowAll, AccessRule::DenyAll)
+                .method(
+                    "get_outstanding_supply",
+                    AccessRule::AllowAll,
+                    AccessRule::DenyAll,
+                )
+                .default(rule!(require(admin_badge)), AccessRule::DenyAll);
+
+            let mut component = Self {
+                stablecoin_minter: Vault::with_bucket(stablecoin_minter),
+                stablecoin_address,
+                admin_badge,
+                assets: HashMap::new(),
+                fees_in: HashMap::new(),
+                fees_out: HashMap::new(),
+                caps: HashMap::new(),
+                fees: Vault::new(stablecoin_address),
+                divisibilities: HashMap::new(),
+            }
+            .instantiate();
+
+            component.add_access_check(psm_rules);
+
+            component.globalize()
+        }
+
+        /// Mints stablecoins one for one against the given assets and keeps the fee in.
+        ///
+        /// # Arguments
+        /// * `assets` - Bucket containing a whitelisted asset
+        pub fn mint_stablecoins(&mut self, assets: Bucket) -> Bucket {
+            let asset = assets.resource_address();
+            let amount = assets.amount();
+            assert!(
+                amount.is_positive(),
+                "The amount of assets to deposit should be positive"
+            );
+            let fee_in = self.get_fee_in(asset);
+            let cap = *self.caps.get(&asset).unwrap();
+
+            let vault = self.assets.get_mut(&asset).unwrap();
+            assert!(
+                vault.amount() + amount <= cap,
+                "Cannot mint more than {} stablecoins against this asset",
+                cap - vault.amount()
+            );
+            vault.put(assets);
+
+            let mut stablecoins = self
+                .stablecoin_minter
+                .authorize(|| borrow_resource_manager!(self.stablecoin_address).mint(amount));
+            self.fees.put(stablecoins.take(amount * fee_in));
+
+            stablecoins
+        }
+
+        /// Redeems stablecoins one for one for the given asset and keeps the fee out.
+        ///
+        /// The amount of assets sent back is rounded down to the divisibility of the asset and
+        /// only the stablecoins matching this amount are charged; the others are returned.
+        ///
+        /// # Arguments
+        /// * `stablecoins` - Bucket containing the stablecoins to redeem
+        /// * `asset` - ResourceAddress of the whitelisted asset to receive
+        pub fn redeem_stablecoins(
+            &mut self,
+            mut stablecoins: Bucket,
+            asset: ResourceAddress,
+        ) -> (Bucket, Bucket) {
+            assert!(
+                stablecoins.resource_address() == self.stablecoin_address,
+                "Please provide stablecoins to redeem"
+            );
+
+            let fee_out = match self.fees_out.get(&asset) {
+                Some(fee_out) => *fee_out,
+                None => panic!("The given asset is not accepted by the peg stability module"),
+            };
+            let divisibility = *self.divisibilities.get(&asset).unwrap();
+            let asset_amount = (stablecoins.amount() * (Decimal::ONE - fee_out))
+                .round(divisibility as u32, RoundingMode::TowardsZero);
+            assert!(
+                asset_amount.is_positive(),
+                "Please provide enough stablecoins to redeem at least one unit of the asset"
+            );
+
+            let vault = self.assets.get_mut(&asset).unwrap();
+            assert!(
+                asset_amount <= vault.amount(),
+                "Cannot redeem more than {} stablecoins for this asset",
+                vault.amount()
+            );
+            let assets = vault.take(asset_amount);
+
+            let stablecoins_used =
+                (asset_amount / (Decimal::ONE - fee_out)).min(stablecoins.amount());
+            self.fees
+                .put(stablecoins.take(stablecoins_used - asset_amount));
+            let stablecoins_to_burn = stablecoins.take(asset_amount);
+            self.stablecoin_minter.authorize(|| {
+                borrow_resource_manager!(self.stablecoin_address).burn(stablecoins_to_burn)
+            });
+
+            (assets, stablecoins)
+        }
+
+        /// Whitelists an asset or changes its fees and cap.
+        ///
+        /// # Access Rule
+        /// Can only be called by the owner of the admin badge
+        ///
+        /// # Arguments
+        /// * `asset` - ResourceAddress of the asset
+        /// * `fee_in` - Share of the deposited assets taken as fee when minting stablecoins
+        /// * `fee_out` - Share of the stablecoins taken as fee when redeeming them
+        /// * `cap` - Maximum amount of stablecoins that can be minted against the asset
+        pub fn set_asset_parameters(
+            &mut self,
+            asset: ResourceAddress,
+            fee_in: Decimal,
+            fee_out: Decimal,
+            cap: Decimal,
+        ) {
+            assert!(
+                asset != self.stablecoin_address,
+                "The stablecoin cannot be used as an asset of the peg stability module"
+            );
+            assert!(
+                !fee_in.is_negative() && fee_in < Decimal::ONE,
+                "The fee in should be such that 0<=FI<1"
+            );
+            assert!(
+                !fee_out.is_negative() && fee_out < Decimal::ONE,
+                "The fee out should be such that 0<=FO<1"
+            );
+            assert!(!cap.is_negative(), "The cap should be non negative");
+
+            let divisibility = match borrow_resource_manager!(asset).resource_type() {
+                ResourceType::Fungible { divisibility } => divisibility,
+                _ => panic!("Only fungible assets can be accepted by the peg stability module"),
+            };
+
+            if self.assets.get(&asset).is_none() {
+                self.assets.insert(asset, Vault::new(asset));
+            }
+            self.fees_in.insert(asset, fee_in);
+            self.fees_out.insert(asset, fee_out);
+            self.caps.insert(asset, cap);
+            self.divisibilities.insert(asset, divisibility);
+        }
+
+        /// Sends the stablecoins paid as fees.
+        ///
+        /// # Access Rule
+        /// Can only be called by the owner of the admin badge
+        pub fn claim_fees(&mut self) -> Bucket {
+            self.fees.take_all()
+        }
+
+        /// Returns the amount of a given asset held by the module, which is also the amount of
+        /// stablecoins minted against it, along with its fee in, fee out and cap.
+        ///
+        /// # Arguments
+        /// * `asset` - ResourceAddress of the whitelisted asset
+        pub fn get_asset_state(
+            &self,
+            asset: ResourceAddress,
+        ) -> (Decimal, Decimal, Decimal, Decimal) {
+            let fee_in = self.get_fee_in(asset);
+
+            (
+                self.assets.get(&asset).unwrap().amount(),
+                fee_in,
+                *self.fees_out.get(&asset).unwrap(),
+                *self.caps.get(&asset).unwrap(),
+            )
+        }
+
+        /// Returns the amount of stablecoins minted against all assets and not yet redeemed, which
+        /// is also the amount of assets held by the module.
+        pub fn get_outstanding_supply(&self) -> Decimal {
+            self.assets
+                .values()
+                .fold(Decimal::ZERO, |total, vault| total + vault.amount())
+        }
+
+        /// Internal method that returns the fee in of a whitelisted asset.
+        #[inline]
+        fn get_fee_in(&self, asset: ResourceAddress) -> Decimal {
+            match self.fees_in.get(&asset) {
+                Some(fee_in) => *fee_in,
+                None => panic!("The given asset is not accepted by the peg stability module"),
+            }
+        }
+    }
+}
@@ -0,0 +1,11 @@
+//! Definition of the ReporterBadge NFR which allows its owner to push prices to a PriceFeed
+
+use scrypto::prelude::*;
+
+#[derive(
+    NonFungibleData, ScryptoCategorize, LegacyDescribe, ScryptoEncode, ScryptoDecode, Clone,
+)]
+pub struct ReporterBadge {
+    /// Name of the reporter
+    pub name: String,
+}
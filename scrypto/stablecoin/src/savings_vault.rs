@@ -0,0 +1,106 @@
+//! # Savings Vault Blueprint
+//!
+//! Implements a vault of stablecoins earning the savings rate set by the protocol. Depositors
+//! receive shares whose value in stablecoins grows with a rate accumulator, compounded
+//! continuously like the interests of loans. The interests owed to depositors are funded by the
+//! issuer every time the accumulator is updated.
+
+use scrypto::blueprint;
+
+#[blueprint]
+mod savings_vault {
+    use crate::constants::SECONDS_PER_DAY;
+    use stoichiometric_dex::decimal_maths::exp;
+
+    pub struct SavingsVault {
+        /// Stablecoins deposited in the vault and interests funded by the issuer
+        stablecoins: Vault,
+
+        /// Daily savings rate paid to depositors
+        savings_rate: Decimal,
+
+        /// Value of a share in stablecoins at the last accumulator update
+        rate_accumulator: Decimal,
+
+        /// Time of the last accumulator update
+        last_accumulator_update: i64,
+
+        /// Number of shares owned by depositors
+        total_shares: Decimal,
+    }
+
+    impl SavingsVault {
+        pub fn new(stablecoin_address: ResourceAddress) -> SavingsVaultComponent {
+            Self {
+                stablecoins: Vault::new(stablecoin_address),
+                savings_rate: Decimal::ZERO,
+                rate_accumulator: Decimal::ONE,
+                last_accumulator_update: Clock::current_time(TimePrecision::Minute)
+                    .seconds_since_unix_epoch,
+                total_shares: Decimal::ZERO,
+            }
+            .instantiate()
+        }
+
+        /// Compounds the rate accumulator up to the current time and returns the amount of
+        /// stablecoins that the issuer should fund for the vault to cover every share
+        pub fn drip(&mut self) -> Decimal {
+            self.rate_accumulator = self.current_rate_accumulator();
+            self.last_accumulator_update =
+                Clock::current_time(TimePrecision::Minute).seconds_since_unix_epoch;
+
+            (self.total_shares * self.rate_accumulator - self.stablecoins.amount())
+                .max(Decimal::ZERO)
+        }
+
+        /// Puts stablecoins funded by the issuer in the vault
+        pub fn fund(&mut self, stablecoins: Bucket) {
+            self.stablecoins.put(stablecoins);
+        }
+
+        /// Deposits stablecoins in the vault and returns the amount of shares to mint
+        pub fn deposit(&mut self, stablecoins: Bucket) -> Decimal {
+            assert!(
+                stablecoins.amount().is_positive(),
+                "The amount to deposit should be positive"
+            );
+
+            let shares = stablecoins.amount() / self.rate_accumulator;
+            self.total_shares += shares;
+            self.stablecoins.put(stablecoins);
+
+            shares
+        }
+
+        /// Returns the stablecoins owed for the given amount of shares, to be burned
+        pub fn withdraw(&mut self, shares: Decimal) -> Bucket {
+            let amount = (shares * self.rate_accumulator).min(self.stablecoins.amount());
+            self.total_shares -= shares;
+
+            self.stablecoins.take(amount)
+        }
+
+        pub fn set_savings_rate(&mut self, savings_rate: Decimal) {
+            self.savings_rate = savings_rate;
+        }
+
+        /// Returns the amount of stablecoins in the vault, the savings rate and the current value
+        /// of a share
+        pub fn get_state(&self) -> (Decimal, Decimal, Decimal) {
+            (
+                self.stablecoins.amount(),
+                self.savings_rate,
+                self.current_rate_accumulator(),
+            )
+        }
+
+        /// Returns the rate accumulator compounded up to the current time
+        fn current_rate_accumulator(&self) -> Decimal {
+            let current_time = Clock::current_time(TimePrecision::Minute).seconds_since_unix_epoch;
+            let days_elapsed =
+                Decimal::from(current_time - self.last_accumulator_update) / SECONDS_PER_DAY;
+
+            self.rate_accumulator * exp(self.savings_rate * days_elapsed)
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use scrypto::prelude::*;
+
+#[derive(
+    NonFungibleData, ScryptoCategorize, LegacyDescribe, ScryptoEncode, ScryptoDecode, Clone,
+)]
+pub struct StabilityDeposit {
+    /// Amount of stablecoins deposited at the time of the last snapshot
+    #[mutable]
+    pub deposit: Decimal,
+
+    /// Running product of the stability pool at the time of the last snapshot
+    #[mutable]
+    pub product_snapshot: Decimal,
+
+    /// Running sums of collateral gains of the stability pool at the time of the last snapshot
+    #[mutable]
+    pub sums_snapshot: HashMap<ResourceAddress, Decimal>,
+
+    /// Epoch of the stability pool at the time of the last snapshot
+    #[mutable]
+    pub epoch: u64,
+
+    /// Scale of the stability pool at the time of the last snapshot
+    #[mutable]
+    pub scale: u64,
+}
+
+impl StabilityDeposit {
+    pub fn from(
+        deposit: Decimal,
+        product_snapshot: Decimal,
+        sums_snapshot: HashMap<ResourceAddress, Decimal>,
+        epoch: u64,
+        scale: u64,
+    ) -> Self {
+        Self {
+            deposit,
+            product_snapshot,
+            sums_snapshot,
+            epoch,
+            scale,
+        }
+    }
+}
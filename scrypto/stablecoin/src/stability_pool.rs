@@ -0,0 +1,236 @@
+//! # Stability Pool Blueprint
+//!
+//! Implements a pool of stablecoins used to liquidate loans. Depositors pre-fund liquidations:
+//! the debt of liquidated loans is paid with the stablecoins of the pool and the seized
+//! collateral is shared between depositors proportionally to their deposits.
+//!
+//! Deposits and collateral gains are tracked with a running product and running sums so that
+//! liquidations do not need to iterate over the deposits. Every time a liquidation empties the
+//! pool, a new epoch starts and the deposits made before it are worth nothing. Every time the
+//! running product gets too small to be precise, it is scaled up and a new scale starts.
+
+use scrypto::blueprint;
+
+#[blueprint]
+mod stability_pool {
+    use crate::constants::{STABILITY_POOL_MIN_PRODUCT, STABILITY_POOL_SCALE_FACTOR};
+    use crate::stability_deposit::StabilityDeposit;
+
+    pub struct StabilityPool {
+        /// Stablecoins deposited in the pool and not yet used to liquidate loans
+        stablecoins: Vault,
+
+        /// Collateral seized by liquidations and not yet claimed by depositors
+        collaterals: HashMap<ResourceAddress, Vault>,
+
+        /// Running product by which deposits are multiplied after each liquidation
+        product: Decimal,
+
+        /// Running sums of collateral gained per stablecoin deposited, for each epoch and scale
+        sums: Vec<Vec<HashMap<ResourceAddress, Decimal>>>,
+
+        /// Number of times the pool was emptied by a liquidation
+        epoch: u64,
+
+        /// Number of times the running product was scaled up during the current epoch
+        scale: u64,
+    }
+
+    impl StabilityPool {
+        pub fn new(stablecoin_address: ResourceAddress) -> StabilityPoolComponent {
+            Self {
+                stablecoins: Vault::new(stablecoin_address),
+                collaterals: HashMap::new(),
+                product: Decimal::ONE,
+                sums: vec![vec![HashMap::new()]],
+                epoch: 0,
+                scale: 0,
+            }
+            .instantiate()
+        }
+
+        /// Deposits stablecoins in the pool and returns the data of the associated deposit
+        pub fn deposit(&mut self, stablecoins: Bucket) -> StabilityDeposit {
+            assert!(
+                stablecoins.amount().is_positive(),
+                "The amount to deposit should be positive"
+            );
+
+            let deposit = self.snapshot(stablecoins.amount());
+            self.stablecoins.put(stablecoins);
+            deposit
+        }
+
+        /// Withdraws stablecoins from a deposit, sends the collateral gained by the deposit and
+        /// returns the updated deposit
+        pub fn withdraw(
+            &mut self,
+            amount: Decimal,
+            deposit: StabilityDeposit,
+        ) -> (Bucket, Vec<Bucket>, StabilityDeposit) {
+            let compounded_deposit = self.compounded_deposit(&deposit);
+            assert!(
+                amount <= compounded_deposit,
+                "Cannot withdraw more than {} stablecoins",
+                compounded_deposit
+            );
+
+            let gains = self.take_gains(&deposit);
+            let stablecoins = self.stablecoins.take(amount);
+
+            (
+                stablecoins,
+                gains,
+                self.snapshot(compounded_deposit - amount),
+            )
+        }
+
+        /// Sends the collateral gained by a deposit and returns the updated deposit
+        pub fn claim(&mut self, deposit: StabilityDeposit) -> (Vec<Bucket>, StabilityDeposit) {
+            let compounded_deposit = self.compounded_deposit(&deposit);
+            let gains = self.take_gains(&deposit);
+
+            (gains, self.snapshot(compounded_deposit))
+        }
+
+        /// Uses the stablecoins of the pool to pay the debt of a liquidated loan and shares its
+        /// collateral between depositors. Returns the stablecoins to burn.
+        pub fn absorb(&mut self, debt: Decimal, collateral: Bucket) -> Bucket {
+            let total_deposits = self.stablecoins.amount();
+            assert!(total_deposits.is_positive(), "The stability pool is empty");
+            assert!(
+                debt <= total_deposits,
+                "The stability pool does not hold enough stablecoins"
+            );
+
+            let token = collateral.resource_address();
+            let gain_per_unit = collateral.amount() * self.product / total_deposits;
+            let sums = self.sums.last_mut().unwrap().last_mut().unwrap();
+            let sum = *sums.get(&token).unwrap_or(&Decimal::ZERO);
+            sums.insert(token, sum + gain_per_unit);
+
+            // Scale the running product up while it is too small to be precise. If it still rounds
+            // to zero, the deposits left are dust and the pool is considered emptied.
+            let remaining_deposits = total_deposits - debt;
+            let mut scaled_product = self.product;
+            let mut new_product = scaled_product * remaining_deposits / total_deposits;
+            let mut scale_increase = 0;
+            while remaining_deposits.is_positive()
+                && new_product < STABILITY_POOL_MIN_PRODUCT
+                && scale_increase < 2
+            {
+                scaled_product = scaled_product * STABILITY_POOL_SCALE_FACTOR;
+                new_product = scaled_product * remaining_deposits / total_deposits;
+                scale_increase += 1;
+            }
+
+            if new_product.is_zero() {
+                // The pool is emptied: every deposit is now worth nothing
+                self.epoch += 1;
+                self.scale = 0;
+                self.product = Decimal::ONE;
+                self.sums.push(vec![HashMap::new()]);
+            } else {
+                for _ in 0..scale_increase {
+                    self.scale += 1;
+                    self.sums.last_mut().unwrap().push(HashMap::new());
+                }
+                self.product = new_product;
+            }
+
+            match self.collaterals.get_mut(&token) {
+                Some(vault) => vault.put(collateral),
+                None => {
+                    self.collaterals
+                        .insert(token, Vault::with_bucket(collateral));
+                }
+            }
+
+            self.stablecoins.take(debt)
+        }
+
+        /// Returns the amount of stablecoins of a deposit and the collateral it gained
+        pub fn get_deposit_value(
+            &self,
+            deposit: StabilityDeposit,
+        ) -> (Decimal, HashMap<ResourceAddress, Decimal>) {
+            (
+                self.compounded_deposit(&deposit),
+                self.collateral_gains(&deposit),
+            )
+        }
+
+        /// Returns the amount of stablecoins in the pool
+        pub fn get_total_deposits(&self) -> Decimal {
+            self.stablecoins.amount()
+        }
+
+        /// Returns the amount of stablecoins left in a deposit after liquidations. Deposits made
+        /// before the last epoch or more than one scale ago are worth nothing.
+        fn compounded_deposit(&self, deposit: &StabilityDeposit) -> Decimal {
+            if deposit.epoch < self.epoch {
+                return Decimal::ZERO;
+            }
+
+            match self.scale - deposit.scale {
+                0 => deposit.deposit * self.product / deposit.product_snapshot,
+                1 => {
+                    deposit.deposit * self.product
+                        / deposit.product_snapshot
+                        / STABILITY_POOL_SCALE_FACTOR
+                }
+                _ => Decimal::ZERO,
+            }
+        }
+
+        /// Returns the amount of each collateral token gained by a deposit
+        fn collateral_gains(
+            &self,
+            deposit: &StabilityDeposit,
+        ) -> HashMap<ResourceAddress, Decimal> {
+            // Gains made during the scale following the one of the deposit are scaled down, the
+            // later ones being negligible
+            let no_sums = HashMap::new();
+            let epoch_sums = &self.sums[deposit.epoch as usize];
+            let sums = &epoch_sums[deposit.scale as usize];
+            let next_sums = epoch_sums
+                .get(deposit.scale as usize + 1)
+                .unwrap_or(&no_sums);
+
+            let mut gains = HashMap::new();
+            for token in sums.keys().chain(next_sums.keys()) {
+                let sum = *sums.get(token).unwrap_or(&Decimal::ZERO);
+                let next_sum = *next_sums.get(token).unwrap_or(&Decimal::ZERO);
+                let sum_snapshot = *deposit.sums_snapshot.get(token).unwrap_or(&Decimal::ZERO);
+                let gain = deposit.deposit
+                    * (sum - sum_snapshot + next_sum / STABILITY_POOL_SCALE_FACTOR)
+                    / deposit.product_snapshot;
+                if gain.is_positive() {
+                    gains.insert(*token, gain);
+                }
+            }
+            gains
+        }
+
+        /// Takes the collateral gained by a deposit from the vaults of the pool
+        fn take_gains(&mut self, deposit: &StabilityDeposit) -> Vec<Bucket> {
+            let mut buckets = Vec::new();
+            for (token, gain) in self.collateral_gains(deposit) {
+                let vault = self.collaterals.get_mut(&token).unwrap();
+                buckets.push(vault.take(gain.min(vault.amount())));
+            }
+            buckets
+        }
+
+        /// Returns the data of a deposit of a given amount made now
+        fn snapshot(&self, amount: Decimal) -> StabilityDeposit {
+            StabilityDeposit::from(
+                amount,
+                self.product,
+                self.sums.last().unwrap().last().unwrap().clone(),
+                self.epoch,
+                self.scale,
+            )
+        }
+    }
+}
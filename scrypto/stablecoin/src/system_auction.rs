@@ -0,0 +1,40 @@
+use scrypto::prelude::*;
+
+#[derive(ScryptoCategorize, LegacyDescribe, ScryptoEncode, ScryptoDecode, Clone)]
+pub struct SystemAuction {
+    /// Amount of stablecoins left to raise for a debt auction or left to sell for a surplus
+    /// auction
+    pub amount: Decimal,
+
+    /// Price of the sold resource, in the paid resource, at the start of the auction
+    pub start_price: Decimal,
+
+    /// Time at the start of the auction
+    pub start_time: i64,
+}
+
+impl SystemAuction {
+    pub fn new(amount: Decimal, start_price: Decimal, start_time: i64) -> Self {
+        Self {
+            amount,
+            start_price,
+            start_time,
+        }
+    }
+
+    /// Returns the price of the sold resource at a given time. The price decreases linearly from
+    /// the start price to zero over the duration of the auction.
+    pub fn price_at(&self, current_time: i64, duration: i64) -> Decimal {
+        let elapsed = current_time - self.start_time;
+        if elapsed >= duration {
+            Decimal::ZERO
+        } else {
+            self.start_price * (duration - elapsed) / duration
+        }
+    }
+
+    /// Returns whether the auction has reached its end
+    pub fn has_expired(&self, current_time: i64, duration: i64) -> bool {
+        current_time - self.start_time >= duration
+    }
+}
@@ -2,8 +2,8 @@ use crate::utils::{PROPOSAL_RECEIPT, VOTER_CARD_NAME};
 use scrypto::prelude::Decimal;
 use sqrt::blueprint::{AdminBadge, Blueprint};
 use sqrt::method::Arg::{
-    ComponentAddressArg, DecimalArg, FungibleBucketArg, NonFungibleBucketArg, ResourceAddressArg,
-    StringArg, VecArg, I64,
+    Bool, ComponentAddressArg, DecimalArg, FungibleBucketArg, NonFungibleBucketArg,
+    NonFungibleLocalId, ResourceAddressArg, StringArg, VecArg, I64,
 };
 use sqrt::method::{Arg, Method};
 use sqrt::{enum_arg, method_args, tuple_arg};
@@ -30,6 +30,7 @@ pub enum DaoMethods {
     MakeChangeVotePeriodProposal(i64),
     MakeMinimumVoteThresholdProposal(Decimal),
     MakeGrantIssuingRightProposal,
+    MakeRemoveIssuingRightProposal(String, String),
     MakeAllowClaimProposal(Vec<(String, Decimal)>),
     MakeAddNewCollateralToken(
         String,
@@ -40,13 +41,31 @@ pub enum DaoMethods {
         Decimal,
         Decimal,
         Decimal,
+        Decimal,
+        Decimal,
         String,
     ),
-    MakeChangeLenderParameters(String, Decimal, Decimal, Decimal, Decimal),
-    MakeChangeLenderOracle(String, String),
+    MakeChangeLenderParameters(String, Decimal, Decimal, Decimal, Decimal, Decimal, Decimal),
+    MakeChangeLenderOracle(String, String, bool),
     MakeAddTokensToIssuerReserves(Vec<(String, Decimal)>),
+    MakeChangeLenderLiquidationMode(String, bool, Decimal, i64, Decimal),
+    MakeChangeLenderDebtCeiling(String, Decimal),
+    MakeChangeGlobalDebtCeiling(Decimal),
+    MakeChangeLenderMinimumDebt(String, Decimal),
+    MakeChangeSurplusBuffer(Decimal),
+    MakeChangeFlashMintParameters(Decimal, Decimal),
+    MakeChangeIssuerFlashLoanFee(String, Decimal),
+    MakeChangePoolFlashLoanFee(String, Decimal),
+    MakeChangePsmAssetParameters(String, String, Decimal, Decimal, Decimal),
+    MakeChangeRedemptionFee(Decimal),
+    MakeChangeSavingsRate(Decimal),
+    MakeRegisterMinter(String, String, Decimal),
+    MakeChangeMinterCap(String, String, Decimal),
+    MakeChangeSystemAuctionParameters(Decimal, i64),
+    MakeChangeExternalMinters(Vec<String>, Vec<String>),
     ExecuteProposal(String),
     ClaimDexProtocolFees,
+    ClaimPsmFees(String),
 }
 
 impl Method for DaoMethods {
@@ -56,14 +75,31 @@ impl Method for DaoMethods {
             DaoMethods::Gift(_, _) => "put_in_reserves",
             DaoMethods::MakeChangeVotePeriodProposal(_)
             | DaoMethods::MakeGrantIssuingRightProposal
+            | DaoMethods::MakeRemoveIssuingRightProposal(_, _)
             | DaoMethods::MakeMinimumVoteThresholdProposal(_)
             | DaoMethods::MakeAllowClaimProposal(_)
-            | DaoMethods::MakeAddNewCollateralToken(_, _, _, _, _, _, _, _, _)
-            | DaoMethods::MakeChangeLenderParameters(_, _, _, _, _)
-            | DaoMethods::MakeChangeLenderOracle(_, _)
-            | DaoMethods::MakeAddTokensToIssuerReserves(_) => "make_proposal",
+            | DaoMethods::MakeAddNewCollateralToken(_, _, _, _, _, _, _, _, _, _, _)
+            | DaoMethods::MakeChangeLenderParameters(_, _, _, _, _, _, _)
+            | DaoMethods::MakeChangeLenderOracle(_, _, _)
+            | DaoMethods::MakeAddTokensToIssuerReserves(_)
+            | DaoMethods::MakeChangeLenderLiquidationMode(_, _, _, _, _)
+            | DaoMethods::MakeChangeLenderDebtCeiling(_, _)
+            | DaoMethods::MakeChangeGlobalDebtCeiling(_)
+            | DaoMethods::MakeChangeLenderMinimumDebt(_, _)
+            | DaoMethods::MakeChangeSurplusBuffer(_)
+            | DaoMethods::MakeChangeFlashMintParameters(_, _)
+            | DaoMethods::MakeChangeIssuerFlashLoanFee(_, _)
+            | DaoMethods::MakeChangePoolFlashLoanFee(_, _)
+            | DaoMethods::MakeChangePsmAssetParameters(_, _, _, _, _)
+            | DaoMethods::MakeChangeRedemptionFee(_)
+            | DaoMethods::MakeChangeSavingsRate(_)
+            | DaoMethods::MakeRegisterMinter(_, _, _)
+            | DaoMethods::MakeChangeMinterCap(_, _, _)
+            | DaoMethods::MakeChangeSystemAuctionParameters(_, _)
+            | DaoMethods::MakeChangeExternalMinters(_, _) => "make_proposal",
             DaoMethods::ExecuteProposal(_) => "execute_proposal",
             DaoMethods::ClaimDexProtocolFees => "claim_dex_protocol_fees",
+            DaoMethods::ClaimPsmFees(_) => "claim_psm_fees",
         }
     }
 
@@ -88,6 +124,14 @@ impl Method for DaoMethods {
             DaoMethods::MakeGrantIssuingRightProposal => {
                 method_args!(enum_arg!(2))
             }
+            DaoMethods::MakeRemoveIssuingRightProposal(minter_registry, minter_id) => {
+                let boxed_arg = Box::new(StringArg(minter_id.clone()));
+                method_args!(enum_arg!(
+                    3,
+                    ComponentAddressArg(minter_registry.clone()),
+                    NonFungibleLocalId(boxed_arg)
+                ))
+            }
             DaoMethods::MakeAllowClaimProposal(resources) => {
                 let mut vec_arg = vec![];
                 for (resource, amount) in resources {
@@ -103,7 +147,9 @@ impl Method for DaoMethods {
                 loan_to_value,
                 interest_rate,
                 liquidation_threshold,
-                liquidation_penalty,
+                liquidator_bonus,
+                protocol_liquidation_share,
+                close_factor,
                 initial_rate,
                 minimum_rate,
                 maximum_rate,
@@ -115,7 +161,9 @@ impl Method for DaoMethods {
                     DecimalArg(loan_to_value.clone()),
                     DecimalArg(interest_rate.clone()),
                     DecimalArg(liquidation_threshold.clone()),
-                    DecimalArg(liquidation_penalty.clone()),
+                    DecimalArg(liquidator_bonus.clone()),
+                    DecimalArg(protocol_liquidation_share.clone()),
+                    DecimalArg(close_factor.clone()),
                     DecimalArg(initial_rate.clone()),
                     DecimalArg(minimum_rate.clone()),
                     DecimalArg(maximum_rate.clone()),
@@ -127,7 +175,9 @@ impl Method for DaoMethods {
                 loan_to_value,
                 interest_rate,
                 liquidation_threshold,
-                liquidation_penalty,
+                liquidator_bonus,
+                protocol_liquidation_share,
+                close_factor,
             ) => {
                 method_args!(enum_arg!(
                     6,
@@ -135,14 +185,17 @@ impl Method for DaoMethods {
                     DecimalArg(loan_to_value.clone()),
                     DecimalArg(interest_rate.clone()),
                     DecimalArg(liquidation_threshold.clone()),
-                    DecimalArg(liquidation_penalty.clone())
+                    DecimalArg(liquidator_bonus.clone()),
+                    DecimalArg(protocol_liquidation_share.clone()),
+                    DecimalArg(close_factor.clone())
                 ))
             }
-            DaoMethods::MakeChangeLenderOracle(lender, oracle) => {
+            DaoMethods::MakeChangeLenderOracle(lender, oracle, use_truncated_twap) => {
                 method_args!(enum_arg!(
                     7,
                     ResourceAddressArg(lender.clone()),
-                    ComponentAddressArg(oracle.clone())
+                    ComponentAddressArg(oracle.clone()),
+                    Bool(use_truncated_twap.clone())
                 ))
             }
             DaoMethods::MakeAddTokensToIssuerReserves(resources) => {
@@ -155,15 +208,133 @@ impl Method for DaoMethods {
                 }
                 method_args!(enum_arg!(8, VecArg(vec_arg)))
             }
+            DaoMethods::MakeChangeLenderLiquidationMode(
+                lender,
+                use_auction_liquidation,
+                auction_start_premium,
+                auction_duration,
+                kick_incentive,
+            ) => {
+                method_args!(enum_arg!(
+                    9,
+                    ResourceAddressArg(lender.clone()),
+                    Bool(use_auction_liquidation.clone()),
+                    DecimalArg(auction_start_premium.clone()),
+                    I64(auction_duration.clone()),
+                    DecimalArg(kick_incentive.clone())
+                ))
+            }
+            DaoMethods::MakeChangeLenderDebtCeiling(lender, debt_ceiling) => {
+                method_args!(enum_arg!(
+                    10,
+                    ResourceAddressArg(lender.clone()),
+                    DecimalArg(debt_ceiling.clone())
+                ))
+            }
+            DaoMethods::MakeChangeGlobalDebtCeiling(global_debt_ceiling) => {
+                method_args!(enum_arg!(11, DecimalArg(global_debt_ceiling.clone())))
+            }
+            DaoMethods::MakeChangeLenderMinimumDebt(lender, minimum_debt) => {
+                method_args!(enum_arg!(
+                    12,
+                    ResourceAddressArg(lender.clone()),
+                    DecimalArg(minimum_debt.clone())
+                ))
+            }
+            DaoMethods::MakeChangeSurplusBuffer(surplus_buffer) => {
+                method_args!(enum_arg!(13, DecimalArg(surplus_buffer.clone())))
+            }
+            DaoMethods::MakeChangeFlashMintParameters(flash_mint_fee, flash_mint_max) => {
+                method_args!(enum_arg!(
+                    14,
+                    DecimalArg(flash_mint_fee.clone()),
+                    DecimalArg(flash_mint_max.clone())
+                ))
+            }
+            DaoMethods::MakeChangeIssuerFlashLoanFee(token, flash_loan_fee) => {
+                method_args!(enum_arg!(
+                    15,
+                    ResourceAddressArg(token.clone()),
+                    DecimalArg(flash_loan_fee.clone())
+                ))
+            }
+            DaoMethods::MakeChangePoolFlashLoanFee(token, flash_loan_fee) => {
+                method_args!(enum_arg!(
+                    16,
+                    ResourceAddressArg(token.clone()),
+                    DecimalArg(flash_loan_fee.clone())
+                ))
+            }
             DaoMethods::ExecuteProposal(proposal_receipt_id) => {
                 method_args!(NonFungibleBucketArg(
                     PROPOSAL_RECEIPT.to_string(),
                     vec![proposal_receipt_id.clone()]
                 ))
             }
+            DaoMethods::MakeChangePsmAssetParameters(psm, asset, fee_in, fee_out, cap) => {
+                method_args!(enum_arg!(
+                    17,
+                    ComponentAddressArg(psm.clone()),
+                    ResourceAddressArg(asset.clone()),
+                    DecimalArg(fee_in.clone()),
+                    DecimalArg(fee_out.clone()),
+                    DecimalArg(cap.clone())
+                ))
+            }
+            DaoMethods::MakeChangeRedemptionFee(redemption_fee) => {
+                method_args!(enum_arg!(18, DecimalArg(redemption_fee.clone())))
+            }
+            DaoMethods::MakeChangeSavingsRate(savings_rate) => {
+                method_args!(enum_arg!(19, DecimalArg(savings_rate.clone())))
+            }
+            DaoMethods::MakeRegisterMinter(minter_registry, name, cap) => {
+                method_args!(enum_arg!(
+                    20,
+                    ComponentAddressArg(minter_registry.clone()),
+                    StringArg(name.clone()),
+                    DecimalArg(cap.clone())
+                ))
+            }
+            DaoMethods::MakeChangeMinterCap(minter_registry, minter_id, cap) => {
+                let boxed_arg = Box::new(StringArg(minter_id.clone()));
+                method_args!(enum_arg!(
+                    21,
+                    ComponentAddressArg(minter_registry.clone()),
+                    NonFungibleLocalId(boxed_arg),
+                    DecimalArg(cap.clone())
+                ))
+            }
+            DaoMethods::MakeChangeSystemAuctionParameters(
+                auction_start_premium,
+                auction_duration,
+            ) => {
+                method_args!(enum_arg!(
+                    22,
+                    DecimalArg(auction_start_premium.clone()),
+                    I64(auction_duration.clone())
+                ))
+            }
+            DaoMethods::MakeChangeExternalMinters(psms, minter_registries) => {
+                let mut psm_args = vec![];
+                for psm in psms {
+                    psm_args.push(ComponentAddressArg(psm.clone()))
+                }
+                let mut minter_registry_args = vec![];
+                for minter_registry in minter_registries {
+                    minter_registry_args.push(ComponentAddressArg(minter_registry.clone()))
+                }
+                method_args!(enum_arg!(
+                    23,
+                    VecArg(psm_args),
+                    VecArg(minter_registry_args)
+                ))
+            }
             DaoMethods::ClaimDexProtocolFees => {
                 method_args!()
             }
+            DaoMethods::ClaimPsmFees(psm) => {
+                method_args!(ComponentAddressArg(psm.clone()))
+            }
         }
     }
 
@@ -180,19 +351,60 @@ impl Method for DaoMethods {
                 Some("make_minimum_vote_threshold_proposal")
             }
             DaoMethods::MakeGrantIssuingRightProposal => Some("make_grant_issuing_right_proposal"),
+            DaoMethods::MakeRemoveIssuingRightProposal(_, _) => {
+                Some("make_remove_issuing_right_proposal")
+            }
             DaoMethods::MakeAllowClaimProposal(_) => Some("make_allow_claim_proposal"),
-            DaoMethods::MakeAddNewCollateralToken(_, _, _, _, _, _, _, _, _) => {
+            DaoMethods::MakeAddNewCollateralToken(_, _, _, _, _, _, _, _, _, _, _) => {
                 Some("make_add_new_collateral_proposal")
             }
-            DaoMethods::MakeChangeLenderParameters(_, _, _, _, _) => {
+            DaoMethods::MakeChangeLenderParameters(_, _, _, _, _, _, _) => {
                 Some("make_change_lender_parameters_proposal")
             }
-            DaoMethods::MakeChangeLenderOracle(_, _) => Some("make_change_lender_oracle_proposal"),
+            DaoMethods::MakeChangeLenderOracle(_, _, _) => {
+                Some("make_change_lender_oracle_proposal")
+            }
             DaoMethods::MakeAddTokensToIssuerReserves(_) => {
                 Some("make_add_tokens_to_issuer_reserves_proposal")
             }
+            DaoMethods::MakeChangeLenderLiquidationMode(_, _, _, _, _) => {
+                Some("make_change_lender_liquidation_mode_proposal")
+            }
+            DaoMethods::MakeChangeLenderDebtCeiling(_, _) => {
+                Some("make_change_lender_debt_ceiling_proposal")
+            }
+            DaoMethods::MakeChangeGlobalDebtCeiling(_) => {
+                Some("make_change_global_debt_ceiling_proposal")
+            }
+            DaoMethods::MakeChangeLenderMinimumDebt(_, _) => {
+                Some("make_change_lender_minimum_debt_proposal")
+            }
+            DaoMethods::MakeChangeSurplusBuffer(_) => Some("make_change_surplus_buffer_proposal"),
+            DaoMethods::MakeChangeFlashMintParameters(_, _) => {
+                Some("make_change_flash_mint_parameters_proposal")
+            }
+            DaoMethods::MakeChangeIssuerFlashLoanFee(_, _) => {
+                Some("make_change_issuer_flash_loan_fee_proposal")
+            }
+            DaoMethods::MakeChangePoolFlashLoanFee(_, _) => {
+                Some("make_change_pool_flash_loan_fee_proposal")
+            }
+            DaoMethods::MakeChangePsmAssetParameters(_, _, _, _, _) => {
+                Some("make_change_psm_asset_parameters_proposal")
+            }
+            DaoMethods::MakeChangeRedemptionFee(_) => Some("make_change_redemption_fee_proposal"),
+            DaoMethods::MakeChangeSavingsRate(_) => Some("make_change_savings_rate_proposal"),
+            DaoMethods::MakeRegisterMinter(_, _, _) => Some("make_register_minter_proposal"),
+            DaoMethods::MakeChangeMinterCap(_, _, _) => Some("make_change_minter_cap_proposal"),
+            DaoMethods::MakeChangeSystemAuctionParameters(_, _) => {
+                Some("make_change_system_auction_parameters_proposal")
+            }
+            DaoMethods::MakeChangeExternalMinters(_, _) => {
+                Some("make_change_external_minters_proposal")
+            }
             DaoMethods::ExecuteProposal(_) => None,
             DaoMethods::ClaimDexProtocolFees => None,
+            DaoMethods::ClaimPsmFees(_) => None,
         }
     }
 }
@@ -41,6 +41,8 @@ pub fn instantiate() -> (TestEnvironment, DaoState) {
             DecimalArg(dec!("0.0001")),
             DecimalArg(dec!("1.3")),
             DecimalArg(dec!("0.1")),
+            DecimalArg(dec!("0.1")),
+            DecimalArg(Decimal::ONE),
             ComponentAddressArg(oracle_component),
             DecimalArg(dec!(20000)),
             DecimalArg(dec!(100)),
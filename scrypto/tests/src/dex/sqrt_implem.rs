@@ -3,7 +3,7 @@ use scrypto::prelude::Decimal;
 use sqrt::blueprint::{AdminBadge, Blueprint};
 use sqrt::method::Arg::{
     DecimalArg, FungibleBucketArg, NonFungibleBucketArg, NonFungibleProofArg, ResourceAddressArg,
-    U16,
+    I64, U16,
 };
 use sqrt::method::{Arg, Method};
 use sqrt::method_args;
@@ -33,6 +33,11 @@ pub enum RouterMethods {
     ClaimFees(String, Vec<String>),
     Swap(String, Decimal, String),
     ClaimProtocolFees,
+    NewObservation(String),
+    ChangeOracleParameters(String, i64, u16),
+    ResizeOracle(String, u16),
+    FlashLoan(String, String, Decimal),
+    ChangeFlashLoanFee(String, Decimal),
 }
 
 impl Method for RouterMethods {
@@ -46,6 +51,11 @@ impl Method for RouterMethods {
             RouterMethods::ClaimFees(_, _) => "claim_fees",
             RouterMethods::Swap(_, _, _) => "swap",
             RouterMethods::ClaimProtocolFees => "claim_protocol_fees",
+            RouterMethods::NewObservation(_) => "new_observation",
+            RouterMethods::ChangeOracleParameters(_, _, _) => "change_oracle_parameters",
+            RouterMethods::ResizeOracle(_, _) => "resize_oracle",
+            RouterMethods::FlashLoan(_, _, _) => "flash_loan",
+            RouterMethods::ChangeFlashLoanFee(_, _) => "change_flash_loan_fee",
         }
     }
 
@@ -93,12 +103,46 @@ impl Method for RouterMethods {
             RouterMethods::ClaimProtocolFees => {
                 method_args!()
             }
+            RouterMethods::NewObservation(token) => {
+                method_args!(ResourceAddressArg(token.clone()))
+            }
+            RouterMethods::ChangeOracleParameters(
+                token,
+                min_observation_spacing,
+                max_step_change_per_minute,
+            ) => {
+                method_args!(
+                    ResourceAddressArg(token.clone()),
+                    I64(min_observation_spacing.clone()),
+                    U16(max_step_change_per_minute.clone())
+                )
+            }
+            RouterMethods::ResizeOracle(token, capacity) => {
+                method_args!(ResourceAddressArg(token.clone()), U16(capacity.clone()))
+            }
+            RouterMethods::FlashLoan(pool_token, token, amount) => {
+                method_args!(
+                    ResourceAddressArg(pool_token.clone()),
+                    ResourceAddressArg(token.clone()),
+                    DecimalArg(amount.clone())
+                )
+            }
+            RouterMethods::ChangeFlashLoanFee(token, flash_loan_fee) => {
+                method_args!(
+                    ResourceAddressArg(token.clone()),
+                    DecimalArg(flash_loan_fee.clone())
+                )
+            }
         }
     }
 
     fn needs_admin_badge(&self) -> bool {
         match self {
-            RouterMethods::CreatePool(_, _, _, _) | RouterMethods::ClaimProtocolFees => true,
+            RouterMethods::CreatePool(_, _, _, _)
+            | RouterMethods::ClaimProtocolFees
+            | RouterMethods::ChangeOracleParameters(_, _, _)
+            | RouterMethods::ResizeOracle(_, _)
+            | RouterMethods::ChangeFlashLoanFee(_, _) => true,
             _ => false,
         }
     }
@@ -1,11 +1,14 @@
 use crate::dex::pool_state::PoolState;
 use crate::dex::sqrt_implem::{RouterBlueprint, RouterMethods};
-use crate::utils::{run_command, ADMIN_BADGE_NAME, POSITION_NAME, STABLECOIN_NAME};
+use crate::utils::{
+    run_command, ADMIN_BADGE_NAME, POOL_FLASH_LOAN_NAME, POSITION_NAME, STABLECOIN_NAME,
+};
 use lazy_static::lazy_static;
 use regex::Regex;
 use scrypto::math::Decimal;
 use scrypto::prelude::dec;
 use sqrt::manifest_call::ManifestCall;
+use sqrt::method::Arg;
 use sqrt::method::Arg::{
     AccountAddressArg, ComponentAddressArg, DecimalArg, ResourceAddressArg, StringArg, U16,
 };
@@ -229,6 +232,76 @@ pub fn add_liquidity<'a>(
     test_env.call_custom_manifest(manifest_name, env_args)
 }
 
+pub fn flash_loan_and_repay<'a>(
+    test_env: &'a mut TestEnvironment,
+    pool_token: &'a str,
+    token: &'a str,
+    amount: Decimal,
+    fee: Decimal,
+) -> ManifestCall<'a> {
+    let env_args = flash_loan_args(test_env, pool_token, token, amount, fee);
+
+    test_env.call_custom_manifest("flash_loan_and_repay", env_args)
+}
+
+pub fn flash_loan_and_swap<'a>(
+    test_env: &'a mut TestEnvironment,
+    pool_token: &'a str,
+    token: &'a str,
+    amount: Decimal,
+    swap_input: &'a str,
+    swap_amount: Decimal,
+    swap_output: &'a str,
+) -> ManifestCall<'a> {
+    // The swap is expected to fail, so the flash loan is repaid without fee
+    let mut env_args = flash_loan_args(test_env, pool_token, token, amount, Decimal::ZERO);
+    env_args.push((
+        "swap_input_address".to_string(),
+        ResourceAddressArg(swap_input.to_string()),
+    ));
+    env_args.push(("swap_amount".to_string(), DecimalArg(swap_amount)));
+    env_args.push((
+        "swap_output_address".to_string(),
+        ResourceAddressArg(swap_output.to_string()),
+    ));
+
+    test_env.call_custom_manifest("flash_loan_and_swap", env_args)
+}
+
+fn flash_loan_args(
+    test_env: &TestEnvironment,
+    pool_token: &str,
+    token: &str,
+    amount: Decimal,
+    fee: Decimal,
+) -> Vec<(String, Arg)> {
+    let mut env_args = Vec::new();
+    env_args.push((
+        "caller_address".to_string(),
+        AccountAddressArg(test_env.get_current_account_name().to_string()),
+    ));
+    env_args.push((
+        "component_address".to_string(),
+        ComponentAddressArg(test_env.get_current_component_name().unwrap().to_string()),
+    ));
+    env_args.push((
+        "pool_token_address".to_string(),
+        ResourceAddressArg(pool_token.to_string()),
+    ));
+    env_args.push((
+        "token_address".to_string(),
+        ResourceAddressArg(token.to_string()),
+    ));
+    env_args.push(("amount".to_string(), DecimalArg(amount)));
+    env_args.push(("fee".to_string(), DecimalArg(fee)));
+    env_args.push((
+        "flash_loan_address".to_string(),
+        ResourceAddressArg(POOL_FLASH_LOAN_NAME.to_string()),
+    ));
+
+    env_args
+}
+
 pub fn assert_current_position(
     test_env: &TestEnvironment,
     token: &str,
@@ -312,3 +385,47 @@ pub fn assert_no_positions(test_env: &TestEnvironment, token: &str) {
 
     assert!(!position_found);
 }
+
+pub fn get_oracle_parameters(test_env: &TestEnvironment, token: &str) -> (i64, u16, u16) {
+    let router_address = test_env.get_component("router_comp").unwrap();
+    let output = run_command(
+        Command::new("resim")
+            .arg("call-method")
+            .arg(router_address)
+            .arg("get_oracle_parameters")
+            .arg(test_env.get_resource(token)),
+    );
+
+    lazy_static! {
+        static ref ORACLE_PARAMETERS_RE: Regex =
+            Regex::new(r#"├─ Tuple\((\d*)i64, (\d*)u16, (\d*)u16\)"#).unwrap();
+    }
+
+    let capture = &ORACLE_PARAMETERS_RE
+        .captures(&output)
+        .expect("Could not find oracle parameters");
+    (
+        String::from(&capture[1]).parse::<i64>().unwrap(),
+        String::from(&capture[2]).parse::<u16>().unwrap(),
+        String::from(&capture[3]).parse::<u16>().unwrap(),
+    )
+}
+
+pub fn get_twap_since(test_env: &TestEnvironment, token: &str, timestamp: i64) -> Decimal {
+    let router_address = test_env.get_component("router_comp").unwrap();
+    let output = run_command(
+        Command::new("resim")
+            .arg("call-method")
+            .arg(router_address)
+            .arg("get_twap_since")
+            .arg(test_env.get_resource(token))
+            .arg(timestamp.to_string()),
+    );
+
+    lazy_static! {
+        static ref TWAP_RE: Regex = Regex::new(r#"├─ Decimal\("([\d.]*)"\)"#).unwrap();
+    }
+
+    let capture = &TWAP_RE.captures(&output).expect("Could not find the TWAP");
+    Decimal::from(&capture[1])
+}
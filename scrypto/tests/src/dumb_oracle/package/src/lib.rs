@@ -25,6 +25,9 @@ mod dumb_oracle {
             self.price
         }
 
-        pub fn new_observation(&mut self, _token: ResourceAddress) {}
+        pub fn get_truncated_twap_since(&self, _token: ResourceAddress, _timestamp: i64) -> Decimal
+        {
+            self.price
+        }
     }
 }
\ No newline at end of file
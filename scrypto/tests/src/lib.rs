@@ -1,5 +1,7 @@
 pub mod dao;
 pub mod dex;
 pub mod dumb_oracle;
+pub mod minter_registry;
+pub mod price_feed;
 pub mod stablecoin;
 pub mod utils;
@@ -0,0 +1,2 @@
+pub mod sqrt_implem;
+pub mod utils;
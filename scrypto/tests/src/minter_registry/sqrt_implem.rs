@@ -0,0 +1,91 @@
+use crate::utils::{ADMIN_BADGE_NAME, MINTER_BADGE_NAME, STABLECOIN_NAME};
+use scrypto::prelude::Decimal;
+use sqrt::blueprint::{AdminBadge, Blueprint};
+use sqrt::method::Arg::{
+    DecimalArg, FungibleBucketArg, NonFungibleLocalId, NonFungibleProofArg, StringArg,
+};
+use sqrt::method::{Arg, Method};
+use sqrt::method_args;
+
+pub struct MinterRegistryBlueprint {}
+
+impl Blueprint for MinterRegistryBlueprint {
+    fn instantiation_name(&self) -> &str {
+        "new"
+    }
+
+    fn name(&self) -> &str {
+        "MinterRegistry"
+    }
+
+    fn has_admin_badge(&self) -> AdminBadge {
+        AdminBadge::External(ADMIN_BADGE_NAME.to_string())
+    }
+}
+
+pub enum MinterRegistryMethods {
+    NewMinter(String, Decimal),
+    ChangeMinterCap(String, Decimal),
+    RevokeMinter(String),
+    Mint(String, Decimal),
+    Burn(String, Decimal),
+    GetMinterUsage(String),
+}
+
+impl Method for MinterRegistryMethods {
+    fn name(&self) -> &str {
+        match self {
+            MinterRegistryMethods::NewMinter(_, _) => "new_minter",
+            MinterRegistryMethods::ChangeMinterCap(_, _) => "change_minter_cap",
+            MinterRegistryMethods::RevokeMinter(_) => "revoke_minter",
+            MinterRegistryMethods::Mint(_, _) => "mint",
+            MinterRegistryMethods::Burn(_, _) => "burn",
+            MinterRegistryMethods::GetMinterUsage(_) => "get_minter_usage",
+        }
+    }
+
+    fn args(&self) -> Option<Vec<Arg>> {
+        match self {
+            MinterRegistryMethods::NewMinter(name, cap) => {
+                method_args!(StringArg(name.clone()), DecimalArg(cap.clone()))
+            }
+            MinterRegistryMethods::ChangeMinterCap(minter_id, cap) => {
+                let boxed_arg = Box::new(StringArg(minter_id.clone()));
+                method_args!(NonFungibleLocalId(boxed_arg), DecimalArg(cap.clone()))
+            }
+            MinterRegistryMethods::RevokeMinter(minter_id) => {
+                let boxed_arg = Box::new(StringArg(minter_id.clone()));
+                method_args!(NonFungibleLocalId(boxed_arg))
+            }
+            MinterRegistryMethods::Mint(minter_id, amount) => {
+                method_args!(
+                    NonFungibleProofArg(MINTER_BADGE_NAME.to_string(), vec![minter_id.clone()]),
+                    DecimalArg(amount.clone())
+                )
+            }
+            MinterRegistryMethods::Burn(minter_id, amount) => {
+                method_args!(
+                    NonFungibleProofArg(MINTER_BADGE_NAME.to_string(), vec![minter_id.clone()]),
+                    FungibleBucketArg(STABLECOIN_NAME.to_string(), amount.clone())
+                )
+            }
+            MinterRegistryMethods::GetMinterUsage(minter_id) => {
+                let boxed_arg = Box::new(StringArg(minter_id.clone()));
+                method_args!(NonFungibleLocalId(boxed_arg))
+            }
+        }
+    }
+
+    fn needs_admin_badge(&self) -> bool {
+        match self {
+            MinterRegistryMethods::NewMinter(_, _)
+            | MinterRegistryMethods::ChangeMinterCap(_, _)
+            | MinterRegistryMethods::RevokeMinter(_) => true,
+            _ => false,
+        }
+    }
+
+    fn custom_manifest_name(&self) -> Option<&str> {
+        None
+    }
+}
@@ -0,0 +1,37 @@
+use crate::minter_registry::sqrt_implem::{MinterRegistryBlueprint, MinterRegistryMethods};
+use crate::utils::{ADMIN_BADGE_NAME, STABLECOIN_NAME};
+use scrypto::prelude::{dec, Decimal};
+use sqrt::method::Arg::{FungibleBucketArg, ResourceAddressArg};
+use sqrt::package::Package;
+use sqrt::test_environment::TestEnvironment;
+
+pub fn instantiate() -> TestEnvironment {
+    let mut test_env = TestEnvironment::new();
+    test_env.create_fixed_supply_token(ADMIN_BADGE_NAME, dec!(2));
+    test_env.create_mintable_token(STABLECOIN_NAME, ADMIN_BADGE_NAME);
+
+    let minter_registry_blueprint = Box::new(MinterRegistryBlueprint {});
+    let mut stablecoin_package = Package::new("../stablecoin");
+    stablecoin_package.add_blueprint("minter_registry_bp", minter_registry_blueprint);
+    test_env.publish_package("stablecoin", stablecoin_package);
+
+    test_env.new_component(
+        "minter_registry_comp",
+        "minter_registry_bp",
+        vec![
+            ResourceAddressArg(ADMIN_BADGE_NAME.to_string()),
+            FungibleBucketArg(ADMIN_BADGE_NAME.to_string(), Decimal::ONE),
+            ResourceAddressArg(STABLECOIN_NAME.to_string()),
+        ],
+    );
+
+    // The first minter can have up to 1000 stablecoins outstanding
+    test_env
+        .call_method(MinterRegistryMethods::NewMinter(
+            "protocol".to_string(),
+            dec!(1000),
+        ))
+        .run();
+
+    test_env
+}
@@ -0,0 +1,88 @@
+use crate::utils::{ADMIN_BADGE_NAME, REPORTER_BADGE_NAME};
+use scrypto::prelude::Decimal;
+use sqrt::blueprint::{AdminBadge, Blueprint};
+use sqrt::method::Arg::{
+    DecimalArg, NonFungibleLocalId, NonFungibleProofArg, ResourceAddressArg, StringArg, I64,
+};
+use sqrt::method::{Arg, Method};
+use sqrt::method_args;
+
+pub struct PriceFeedBlueprint {}
+
+impl Blueprint for PriceFeedBlueprint {
+    fn instantiation_name(&self) -> &str {
+        "new"
+    }
+
+    fn name(&self) -> &str {
+        "PriceFeed"
+    }
+
+    fn has_admin_badge(&self) -> AdminBadge {
+        AdminBadge::External(ADMIN_BADGE_NAME.to_string())
+    }
+}
+
+pub enum PriceFeedMethods {
+    NewReporter(String),
+    RevokeReporter(String),
+    AddFeed(String, Decimal, Decimal),
+    ChangeParameters(i64, Decimal),
+    ReportPrice(String, String, Decimal, i64),
+}
+
+impl Method for PriceFeedMethods {
+    fn name(&self) -> &str {
+        match self {
+            PriceFeedMethods::NewReporter(_) => "new_reporter",
+            PriceFeedMethods::RevokeReporter(_) => "revoke_reporter",
+            PriceFeedMethods::AddFeed(_, _, _) => "add_feed",
+            PriceFeedMethods::ChangeParameters(_, _) => "change_parameters",
+            PriceFeedMethods::ReportPrice(_, _, _, _) => "report_price",
+        }
+    }
+
+    fn args(&self) -> Option<Vec<Arg>> {
+        match self {
+            PriceFeedMethods::NewReporter(name) => {
+                method_args!(StringArg(name.clone()))
+            }
+            PriceFeedMethods::RevokeReporter(reporter_id) => {
+                let boxed_arg = Box::new(StringArg(reporter_id.clone()));
+                method_args!(NonFungibleLocalId(boxed_arg))
+            }
+            PriceFeedMethods::AddFeed(token, min_price, max_price) => {
+                method_args!(
+                    ResourceAddressArg(token.clone()),
+                    DecimalArg(min_price.clone()),
+                    DecimalArg(max_price.clone())
+                )
+            }
+            PriceFeedMethods::ChangeParameters(max_report_age, max_price_deviation) => {
+                method_args!(
+                    I64(max_report_age.clone()),
+                    DecimalArg(max_price_deviation.clone())
+                )
+            }
+            PriceFeedMethods::ReportPrice(reporter_id, token, price, timestamp) => {
+                method_args!(
+                    NonFungibleProofArg(REPORTER_BADGE_NAME.to_string(), vec![reporter_id.clone()]),
+                    ResourceAddressArg(token.clone()),
+                    DecimalArg(price.clone()),
+                    I64(timestamp.clone())
+                )
+            }
+        }
+    }
+
+    fn needs_admin_badge(&self) -> bool {
+        match self {
+            PriceFeedMethods::ReportPrice(_, _, _, _) => false,
+            _ => true,
+        }
+    }
+
+    fn custom_manifest_name(&self) -> Option<&str> {
+        None
+    }
+}
@@ -0,0 +1,78 @@
+use crate::price_feed::sqrt_implem::{PriceFeedBlueprint, PriceFeedMethods};
+use crate::stablecoin::sqrt_implem::IssuerBlueprint;
+use crate::utils::{ADMIN_BADGE_NAME, STABLECOIN_NAME};
+use scrypto::prelude::{dec, Decimal};
+use sqrt::method::Arg::{DecimalArg, FungibleBucketArg, ResourceAddressArg, I64};
+use sqrt::package::Package;
+use sqrt::test_environment::TestEnvironment;
+
+pub fn instantiate() -> TestEnvironment {
+    let mut test_env = TestEnvironment::new();
+    test_env.create_fixed_supply_token(ADMIN_BADGE_NAME, dec!(2));
+    test_env.create_fixed_supply_token("btc", dec!(10000000));
+    test_env.create_mintable_token(STABLECOIN_NAME, ADMIN_BADGE_NAME);
+
+    let price_feed_blueprint = Box::new(PriceFeedBlueprint {});
+    let issuer_blueprint = Box::new(IssuerBlueprint {});
+    let mut stablecoin_package = Package::new("../stablecoin");
+    stablecoin_package.add_blueprint("price_feed_bp", price_feed_blueprint);
+    stablecoin_package.add_blueprint("issuer_bp", issuer_blueprint);
+    test_env.publish_package("stablecoin", stablecoin_package);
+
+    // Reports older than an hour are rejected and prices cannot move by more than 10% at once
+    test_env.new_component(
+        "price_feed_comp",
+        "price_feed_bp",
+        vec![
+            ResourceAddressArg(ADMIN_BADGE_NAME.to_string()),
+            I64(3600),
+            DecimalArg(dec!("0.1")),
+        ],
+    );
+
+    test_env
+        .call_method(PriceFeedMethods::AddFeed(
+            "btc".to_string(),
+            dec!(1000),
+            dec!(100000),
+        ))
+        .run();
+    test_env
+        .call_method(PriceFeedMethods::NewReporter("reporter".to_string()))
+        .run();
+
+    test_env
+}
+
+pub fn instantiate_issuer(test_env: &mut TestEnvironment) {
+    test_env.new_component(
+        "issuer_comp",
+        "issuer_bp",
+        vec![
+            ResourceAddressArg(ADMIN_BADGE_NAME.to_string()),
+            FungibleBucketArg(ADMIN_BADGE_NAME.to_string(), Decimal::ONE),
+            ResourceAddressArg(STABLECOIN_NAME.to_string()),
+        ],
+    );
+}
+
+pub fn report_price(test_env: &mut TestEnvironment, token: &str, price: Decimal, timestamp: i64) {
+    let component_name = test_env
+        .get_current_component_name()
+        .map(|name| String::from(name));
+
+    test_env.set_current_component("price_feed_comp");
+    test_env
+        .call_method(PriceFeedMethods::ReportPrice(
+            "#0#".to_string(),
+            token.to_string(),
+            price,
+            timestamp,
+        ))
+        .run();
+
+    match component_name {
+        None => {}
+        Some(name) => test_env.set_current_component(&name),
+    }
+}
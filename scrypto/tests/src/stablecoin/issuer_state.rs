@@ -10,7 +10,9 @@ pub struct LenderState {
     loan_to_value: Decimal,
     interest_rate: Decimal,
     liquidation_threshold: Decimal,
-    protocol_liquidation_share: Decimal,
+    liquidator_bonus: Decimal,
+    liquidation_parameters: Option<(Decimal, Decimal)>,
+    debt: Option<(Decimal, Decimal)>,
 }
 
 impl LenderState {
@@ -19,20 +21,38 @@ impl LenderState {
         loan_to_value: Decimal,
         interest_rate: Decimal,
         liquidation_threshold: Decimal,
-        protocol_liquidation_share: Decimal,
+        liquidator_bonus: Decimal,
     ) -> Self {
         Self {
             collateral_amount,
             loan_to_value,
             interest_rate,
             liquidation_threshold,
-            protocol_liquidation_share,
+            liquidator_bonus,
+            liquidation_parameters: None,
+            debt: None,
         }
     }
 
+    /// Also checks the protocol liquidation share and the close factor of the lender
+    pub fn with_liquidation_parameters(
+        mut self,
+        protocol_liquidation_share: Decimal,
+        close_factor: Decimal,
+    ) -> Self {
+        self.liquidation_parameters = Some((protocol_liquidation_share, close_factor));
+        self
+    }
+
+    /// Also checks the principal lent against the collateral of the lender and its debt ceiling
+    pub fn with_debt(mut self, debt: Decimal, debt_ceiling: Decimal) -> Self {
+        self.debt = Some((debt, debt_ceiling));
+        self
+    }
+
     pub fn from_output(str_output: &str) -> LenderState {
         lazy_static! {
-            static ref LENDER_RE: Regex = Regex::new(r#"Array<Decimal>\(Decimal\("([\d.]*)"\), Decimal\("([\d.]*)"\), Decimal\("([\d.]*)"\), Decimal\("([\d.]*)"\), Decimal\("([\d.]*)"\)\)"#).unwrap();
+            static ref LENDER_RE: Regex = Regex::new(r#"Array<Decimal>\(Decimal\("([\d.]*)"\), Decimal\("([\d.]*)"\), Decimal\("([\d.]*)"\), Decimal\("([\d.]*)"\), Decimal\("([\d.]*)"\), Decimal\("([\d.]*)"\), Decimal\("([\d.]*)"\), Decimal\("([\d.]*)"\), Decimal\("([\d.]*)"\)\)"#).unwrap();
         }
 
         let lender_cap = LENDER_RE.captures(str_output).unwrap();
@@ -42,7 +62,12 @@ impl LenderState {
             loan_to_value: Decimal::from(&lender_cap[2]),
             interest_rate: Decimal::from(&lender_cap[3]),
             liquidation_threshold: Decimal::from(&lender_cap[4]),
-            protocol_liquidation_share: Decimal::from(&lender_cap[5]),
+            liquidator_bonus: Decimal::from(&lender_cap[5]),
+            liquidation_parameters: Some((
+                Decimal::from(&lender_cap[6]),
+                Decimal::from(&lender_cap[7]),
+            )),
+            debt: Some((Decimal::from(&lender_cap[8]), Decimal::from(&lender_cap[9]))),
         }
     }
 
@@ -62,10 +87,13 @@ impl LenderState {
             assert_eq!(value.loan_to_value, state.loan_to_value);
             assert_eq!(value.interest_rate, state.interest_rate);
             assert_eq!(value.liquidation_threshold, state.liquidation_threshold);
-            assert_eq!(
-                value.protocol_liquidation_share,
-                state.protocol_liquidation_share
-            );
+            assert_eq!(value.liquidator_bonus, state.liquidator_bonus);
+            if state.liquidation_parameters.is_some() {
+                assert_eq!(value.liquidation_parameters, state.liquidation_parameters);
+            }
+            if state.debt.is_some() {
+                assert_eq!(value.debt, state.debt);
+            }
         }
     }
 }
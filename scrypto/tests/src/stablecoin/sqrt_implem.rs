@@ -1,9 +1,12 @@
-use crate::utils::{ADMIN_BADGE_NAME, LOAN_NAME, STABLECOIN_NAME};
+use crate::utils::{
+    ADMIN_BADGE_NAME, BASKET_LOAN_NAME, LOAN_NAME, RESERVE_CLAIM_NAME, SAVINGS_SHARE_NAME,
+    STABILITY_DEPOSIT_NAME, STABLECOIN_NAME,
+};
 use scrypto::prelude::Decimal;
 use sqrt::blueprint::{AdminBadge, Blueprint};
 use sqrt::method::Arg::{
-    ComponentAddressArg, DecimalArg, FungibleBucketArg, NonFungibleBucketArg, NonFungibleLocalId,
-    NonFungibleProofArg, ResourceAddressArg, StringArg,
+    Bool, ComponentAddressArg, DecimalArg, FungibleBucketArg, NonFungibleBucketArg,
+    NonFungibleLocalId, NonFungibleProofArg, ResourceAddressArg, StringArg, VecArg, I64,
 };
 use sqrt::method::{Arg, Method};
 use sqrt::method_args;
@@ -25,27 +28,138 @@ impl Blueprint for IssuerBlueprint {
 }
 
 pub enum IssuerMethods {
-    NewLender(String, Decimal, Decimal, Decimal, Decimal, String),
+    NewLender(
+        String,
+        Decimal,
+        Decimal,
+        Decimal,
+        Decimal,
+        Decimal,
+        Decimal,
+        String,
+    ),
     TakeLoan(String, Decimal, Decimal),
     RepayLoans(Decimal, Vec<String>),
+    RepayPartial(String, Decimal),
+    BorrowMore(String, Decimal),
     AddCollateral(String, Decimal, String),
     RemoveCollateral(Decimal, String),
     Liquidate(Decimal, String),
-    ChangeLenderParameters(String, Decimal, Decimal, Decimal, Decimal),
-    ChangeLenderOracle(String),
+    StartAuction(String),
+    TakeAuction(Decimal, String),
+    ResetAuction(String),
+    DepositInStabilityPool(Decimal),
+    WithdrawFromStabilityPool(String, Decimal),
+    ClaimStabilityPoolGains(String),
+    LiquidateWithStabilityPool(String),
+    ClearBadDebt(String),
+    SettleSystemDebt,
+    StartDebtAuction,
+    TakeDebtAuction(Decimal),
+    ResetDebtAuction,
+    StartSurplusAuction,
+    TakeSurplusAuction(Decimal),
+    ResetSurplusAuction,
+    GiveTokens(Vec<(String, Decimal)>),
+    DepositSavings(Decimal),
+    WithdrawSavings(Decimal),
+    DripSavings,
+    GetSavingsState,
+    FlashMint(Decimal),
+    FlashLoan(String, Decimal),
+    Redeem(Decimal, String),
+    TakeBasketLoan(Vec<(String, Decimal)>, Decimal),
+    RepayBasketLoan(Decimal, String),
+    LiquidateBasketLoan(Decimal, String),
+    ClearBasketBadDebt(String),
+    SetCollateralPriority(Vec<String>),
+    SetExternalMinters(Vec<String>, Vec<String>),
+    GetLoanHealth(String),
+    GetBasketLoanHealth(String),
+    GetLoanHistory(String),
+    GetBasketLoanHistory(String),
+    GetLiquidatableLoans(String),
+    GetLiquidatableBasketLoans,
+    GetLenderTotals(String),
+    GetProtocolState,
+    ChangeLenderParameters(String, Decimal, Decimal, Decimal, Decimal, Decimal, Decimal),
+    ChangeLenderOracle(String, String, bool),
+    ChangeLenderLiquidationMode(String, bool, Decimal, i64, Decimal),
+    SetMinimumDebt(String, Decimal),
+    SetDebtCeiling(String, Decimal),
+    SetGlobalDebtCeiling(Decimal),
+    SetSurplusBuffer(Decimal),
+    SetSystemAuctionParameters(Decimal, i64),
+    SetFlashMintParameters(Decimal, Decimal),
+    SetFlashLoanFee(String, Decimal),
+    SetRedemptionFee(Decimal),
+    SetSavingsRate(Decimal),
 }
 
 impl Method for IssuerMethods {
     fn name(&self) -> &str {
         match self {
-            IssuerMethods::NewLender(_, _, _, _, _, _) => "new_lender",
+            IssuerMethods::NewLender(_, _, _, _, _, _, _, _) => "new_lender",
             IssuerMethods::TakeLoan(_, _, _) => "take_loan",
             IssuerMethods::RepayLoans(_, _) => "repay_loans",
+            IssuerMethods::RepayPartial(_, _) => "repay_partial",
+            IssuerMethods::BorrowMore(_, _) => "borrow_more",
             IssuerMethods::AddCollateral(_, _, _) => "add_collateral",
             IssuerMethods::RemoveCollateral(_, _) => "remove_collateral",
             IssuerMethods::Liquidate(_, _) => "liquidate",
-            IssuerMethods::ChangeLenderParameters(_, _, _, _, _) => "change_lender_parameters",
-            IssuerMethods::ChangeLenderOracle(_) => "changer_lender_oracle",
+            IssuerMethods::StartAuction(_) => "start_auction",
+            IssuerMethods::TakeAuction(_, _) => "take_auction",
+            IssuerMethods::ResetAuction(_) => "reset_auction",
+            IssuerMethods::DepositInStabilityPool(_) => "deposit_in_stability_pool",
+            IssuerMethods::WithdrawFromStabilityPool(_, _) => "withdraw_from_stability_pool",
+            IssuerMethods::ClaimStabilityPoolGains(_) => "claim_stability_pool_gains",
+            IssuerMethods::LiquidateWithStabilityPool(_) => "liquidate_with_stability_pool",
+            IssuerMethods::ClearBadDebt(_) => "clear_bad_debt",
+            IssuerMethods::SettleSystemDebt => "settle_system_debt",
+            IssuerMethods::StartDebtAuction => "start_debt_auction",
+            IssuerMethods::TakeDebtAuction(_) => "take_debt_auction",
+            IssuerMethods::ResetDebtAuction => "reset_debt_auction",
+            IssuerMethods::StartSurplusAuction => "start_surplus_auction",
+            IssuerMethods::TakeSurplusAuction(_) => "take_surplus_auction",
+            IssuerMethods::ResetSurplusAuction => "reset_surplus_auction",
+            IssuerMethods::GiveTokens(_) => "give_tokens",
+            IssuerMethods::DepositSavings(_) => "deposit_savings",
+            IssuerMethods::WithdrawSavings(_) => "withdraw_savings",
+            IssuerMethods::DripSavings => "drip_savings",
+            IssuerMethods::GetSavingsState => "get_savings_state",
+            IssuerMethods::FlashMint(_) => "flash_mint",
+            IssuerMethods::FlashLoan(_, _) => "flash_loan",
+            IssuerMethods::Redeem(_, _) => "redeem",
+            IssuerMethods::TakeBasketLoan(_, _) => "take_basket_loan",
+            IssuerMethods::RepayBasketLoan(_, _) => "repay_basket_loan",
+            IssuerMethods::LiquidateBasketLoan(_, _) => "liquidate_basket_loan",
+            IssuerMethods::ClearBasketBadDebt(_) => "clear_basket_bad_debt",
+            IssuerMethods::SetCollateralPriority(_) => "set_collateral_priority",
+            IssuerMethods::SetExternalMinters(_, _) => "set_external_minters",
+            IssuerMethods::GetLoanHealth(_) => "get_loan_health",
+            IssuerMethods::GetBasketLoanHealth(_) => "get_basket_loan_health",
+            IssuerMethods::GetLoanHistory(_) => "get_loan_history",
+            IssuerMethods::GetBasketLoanHistory(_) => "get_basket_loan_history",
+            IssuerMethods::GetLiquidatableLoans(_) => "get_liquidatable_loans",
+            IssuerMethods::GetLiquidatableBasketLoans => "get_liquidatable_basket_loans",
+            IssuerMethods::GetLenderTotals(_) => "get_lender_totals",
+            IssuerMethods::GetProtocolState => "get_protocol_state",
+            IssuerMethods::ChangeLenderParameters(_, _, _, _, _, _, _) => {
+                "change_lender_parameters"
+            }
+            IssuerMethods::ChangeLenderOracle(_, _, _) => "change_lender_oracle",
+            IssuerMethods::ChangeLenderLiquidationMode(_, _, _, _, _) => {
+                "change_lender_liquidation_mode"
+            }
+            IssuerMethods::SetMinimumDebt(_, _) => "set_minimum_debt",
+            IssuerMethods::SetDebtCeiling(_, _) => "set_debt_ceiling",
+            IssuerMethods::SetGlobalDebtCeiling(_) => "set_global_debt_ceiling",
+            IssuerMethods::SetSurplusBuffer(_) => "set_surplus_buffer",
+            IssuerMethods::SetSystemAuctionParameters(_, _) => "set_system_auction_parameters",
+            IssuerMethods::SetFlashMintParameters(_, _) => "set_flash_mint_parameters",
+            IssuerMethods::SetFlashLoanFee(_, _) => "set_flash_loan_fee",
+            IssuerMethods::SetRedemptionFee(_) => "set_redemption_fee",
+            IssuerMethods::SetSavingsRate(_) => "set_savings_rate",
         }
     }
 
@@ -56,7 +170,9 @@ impl Method for IssuerMethods {
                 loan_to_value,
                 interest_rate,
                 liquidation_threshold,
-                liquidation_penalty,
+                liquidator_bonus,
+                protocol_liquidation_share,
+                close_factor,
                 oracle,
             ) => {
                 method_args!(
@@ -64,7 +180,9 @@ impl Method for IssuerMethods {
                     DecimalArg(loan_to_value.clone()),
                     DecimalArg(interest_rate.clone()),
                     DecimalArg(liquidation_threshold.clone()),
-                    DecimalArg(liquidation_penalty.clone()),
+                    DecimalArg(liquidator_bonus.clone()),
+                    DecimalArg(protocol_liquidation_share.clone()),
+                    DecimalArg(close_factor.clone()),
                     ComponentAddressArg(oracle.clone())
                 )
             }
@@ -80,6 +198,18 @@ impl Method for IssuerMethods {
                     NonFungibleBucketArg(LOAN_NAME.to_string(), loan_ids.clone())
                 )
             }
+            IssuerMethods::RepayPartial(loan_id, repayment_amount) => {
+                method_args!(
+                    NonFungibleProofArg(LOAN_NAME.to_string(), vec![loan_id.clone()]),
+                    FungibleBucketArg(STABLECOIN_NAME.to_string(), repayment_amount.clone())
+                )
+            }
+            IssuerMethods::BorrowMore(loan_id, amount) => {
+                method_args!(
+                    NonFungibleProofArg(LOAN_NAME.to_string(), vec![loan_id.clone()]),
+                    DecimalArg(amount.clone())
+                )
+            }
             IssuerMethods::AddCollateral(collateral_token, collateral_amount, loan_id) => {
                 method_args!(
                     FungibleBucketArg(collateral_token.clone(), collateral_amount.clone()),
@@ -99,32 +229,354 @@ impl Method for IssuerMethods {
                     NonFungibleLocalId(boxed_arg)
                 )
             }
+            IssuerMethods::StartAuction(loan_id) => {
+                let boxed_arg = Box::new(StringArg(loan_id.clone()));
+                method_args!(NonFungibleLocalId(boxed_arg))
+            }
+            IssuerMethods::TakeAuction(repayment_amount, loan_id) => {
+                let boxed_arg = Box::new(StringArg(loan_id.clone()));
+                method_args!(
+                    FungibleBucketArg(STABLECOIN_NAME.to_string(), repayment_amount.clone()),
+                    NonFungibleLocalId(boxed_arg)
+                )
+            }
+            IssuerMethods::ResetAuction(loan_id) => {
+                let boxed_arg = Box::new(StringArg(loan_id.clone()));
+                method_args!(NonFungibleLocalId(boxed_arg))
+            }
+            IssuerMethods::DepositInStabilityPool(amount) => {
+                method_args!(FungibleBucketArg(
+                    STABLECOIN_NAME.to_string(),
+                    amount.clone()
+                ))
+            }
+            IssuerMethods::WithdrawFromStabilityPool(deposit_id, amount) => {
+                method_args!(
+                    NonFungibleProofArg(
+                        STABILITY_DEPOSIT_NAME.to_string(),
+                        vec![deposit_id.clone()]
+                    ),
+                    DecimalArg(amount.clone())
+                )
+            }
+            IssuerMethods::ClaimStabilityPoolGains(deposit_id) => {
+                method_args!(NonFungibleProofArg(
+                    STABILITY_DEPOSIT_NAME.to_string(),
+                    vec![deposit_id.clone()]
+                ))
+            }
+            IssuerMethods::LiquidateWithStabilityPool(loan_id) => {
+                let boxed_arg = Box::new(StringArg(loan_id.clone()));
+                method_args!(NonFungibleLocalId(boxed_arg))
+            }
+            IssuerMethods::ClearBadDebt(loan_id) => {
+                let boxed_arg = Box::new(StringArg(loan_id.clone()));
+                method_args!(NonFungibleLocalId(boxed_arg))
+            }
+            IssuerMethods::SettleSystemDebt
+            | IssuerMethods::StartDebtAuction
+            | IssuerMethods::ResetDebtAuction
+            | IssuerMethods::StartSurplusAuction
+            | IssuerMethods::ResetSurplusAuction => {
+                method_args!()
+            }
+            IssuerMethods::TakeDebtAuction(amount) => {
+                method_args!(FungibleBucketArg(
+                    STABLECOIN_NAME.to_string(),
+                    amount.clone()
+                ))
+            }
+            IssuerMethods::TakeSurplusAuction(amount) => {
+                method_args!(FungibleBucketArg(
+                    RESERVE_CLAIM_NAME.to_string(),
+                    amount.clone()
+                ))
+            }
+            IssuerMethods::GiveTokens(tokens) => {
+                let token_args = tokens
+                    .iter()
+                    .map(|(token, amount)| FungibleBucketArg(token.clone(), amount.clone()))
+                    .collect();
+                method_args!(VecArg(token_args))
+            }
+            IssuerMethods::TakeBasketLoan(collaterals, amount_to_loan) => {
+                let collateral_args = collaterals
+                    .iter()
+                    .map(|(token, amount)| FungibleBucketArg(token.clone(), amount.clone()))
+                    .collect();
+                method_args!(VecArg(collateral_args), DecimalArg(amount_to_loan.clone()))
+            }
+            IssuerMethods::RepayBasketLoan(repayment_amount, loan_id) => {
+                method_args!(
+                    FungibleBucketArg(STABLECOIN_NAME.to_string(), repayment_amount.clone()),
+                    NonFungibleBucketArg(BASKET_LOAN_NAME.to_string(), vec![loan_id.clone()])
+                )
+            }
+            IssuerMethods::LiquidateBasketLoan(repayment_amount, loan_id) => {
+                let boxed_arg = Box::new(StringArg(loan_id.clone()));
+                method_args!(
+                    FungibleBucketArg(STABLECOIN_NAME.to_string(), repayment_amount.clone()),
+                    NonFungibleLocalId(boxed_arg)
+                )
+            }
+            IssuerMethods::ClearBasketBadDebt(loan_id) => {
+                let boxed_arg = Box::new(StringArg(loan_id.clone()));
+                method_args!(NonFungibleLocalId(boxed_arg))
+            }
+            IssuerMethods::SetCollateralPriority(collateral_priority) => {
+                let token_args = collateral_priority
+                    .iter()
+                    .map(|token| ResourceAddressArg(token.clone()))
+                    .collect();
+                method_args!(VecArg(token_args))
+            }
+            IssuerMethods::SetExternalMinters(psms, minter_registries) => {
+                let psm_args = psms
+                    .iter()
+                    .map(|psm| ComponentAddressArg(psm.clone()))
+                    .collect();
+                let minter_registry_args = minter_registries
+                    .iter()
+                    .map(|minter_registry| ComponentAddressArg(minter_registry.clone()))
+                    .collect();
+                method_args!(VecArg(psm_args), VecArg(minter_registry_args))
+            }
+            IssuerMethods::GetLoanHealth(loan_id) => {
+                let boxed_arg = Box::new(StringArg(loan_id.clone()));
+                method_args!(NonFungibleLocalId(boxed_arg))
+            }
+            IssuerMethods::GetBasketLoanHealth(loan_id) => {
+                let boxed_arg = Box::new(StringArg(loan_id.clone()));
+                method_args!(NonFungibleLocalId(boxed_arg))
+            }
+            IssuerMethods::GetLoanHistory(loan_id) => {
+                let boxed_arg = Box::new(StringArg(loan_id.clone()));
+                method_args!(NonFungibleLocalId(boxed_arg))
+            }
+            IssuerMethods::GetBasketLoanHistory(loan_id) => {
+                let boxed_arg = Box::new(StringArg(loan_id.clone()));
+                method_args!(NonFungibleLocalId(boxed_arg))
+            }
+            IssuerMethods::GetLiquidatableLoans(collateral_token) => {
+                method_args!(ResourceAddressArg(collateral_token.clone()))
+            }
+            IssuerMethods::GetLiquidatableBasketLoans => {
+                method_args!()
+            }
+            IssuerMethods::GetLenderTotals(collateral_token) => {
+                method_args!(ResourceAddressArg(collateral_token.clone()))
+            }
+            IssuerMethods::GetProtocolState => {
+                method_args!()
+            }
             IssuerMethods::ChangeLenderParameters(
                 collateral_token,
                 loan_to_value,
                 interest_rate,
                 liquidation_threshold,
-                liquidation_incentive,
+                liquidator_bonus,
+                protocol_liquidation_share,
+                close_factor,
             ) => {
                 method_args!(
                     ResourceAddressArg(collateral_token.clone()),
                     DecimalArg(loan_to_value.clone()),
                     DecimalArg(interest_rate.clone()),
                     DecimalArg(liquidation_threshold.clone()),
-                    DecimalArg(liquidation_incentive.clone())
+                    DecimalArg(liquidator_bonus.clone()),
+                    DecimalArg(protocol_liquidation_share.clone()),
+                    DecimalArg(close_factor.clone())
+                )
+            }
+            IssuerMethods::ChangeLenderOracle(collateral_token, oracle, use_truncated_twap) => {
+                method_args!(
+                    ResourceAddressArg(collateral_token.clone()),
+                    ComponentAddressArg(oracle.clone()),
+                    Bool(use_truncated_twap.clone())
+                )
+            }
+            IssuerMethods::ChangeLenderLiquidationMode(
+                collateral_token,
+                use_auction_liquidation,
+                auction_start_premium,
+                auction_duration,
+                kick_incentive,
+            ) => {
+                method_args!(
+                    ResourceAddressArg(collateral_token.clone()),
+                    Bool(use_auction_liquidation.clone()),
+                    DecimalArg(auction_start_premium.clone()),
+                    I64(auction_duration.clone()),
+                    DecimalArg(kick_incentive.clone())
+                )
+            }
+            IssuerMethods::SetMinimumDebt(collateral_token, minimum_debt) => {
+                method_args!(
+                    ResourceAddressArg(collateral_token.clone()),
+                    DecimalArg(minimum_debt.clone())
+                )
+            }
+            IssuerMethods::SetDebtCeiling(collateral_token, debt_ceiling) => {
+                method_args!(
+                    ResourceAddressArg(collateral_token.clone()),
+                    DecimalArg(debt_ceiling.clone())
+                )
+            }
+            IssuerMethods::SetGlobalDebtCeiling(global_debt_ceiling) => {
+                method_args!(DecimalArg(global_debt_ceiling.clone()))
+            }
+            IssuerMethods::SetSurplusBuffer(surplus_buffer) => {
+                method_args!(DecimalArg(surplus_buffer.clone()))
+            }
+            IssuerMethods::SetSystemAuctionParameters(auction_start_premium, auction_duration) => {
+                method_args!(
+                    DecimalArg(auction_start_premium.clone()),
+                    I64(auction_duration.clone())
+                )
+            }
+            IssuerMethods::FlashMint(amount_to_mint) => {
+                method_args!(DecimalArg(amount_to_mint.clone()))
+            }
+            IssuerMethods::FlashLoan(token, amount) => {
+                method_args!(
+                    ResourceAddressArg(token.clone()),
+                    DecimalArg(amount.clone())
+                )
+            }
+            IssuerMethods::Redeem(amount, collateral_token) => {
+                method_args!(
+                    FungibleBucketArg(STABLECOIN_NAME.to_string(), amount.clone()),
+                    ResourceAddressArg(collateral_token.clone())
+                )
+            }
+            IssuerMethods::SetRedemptionFee(redemption_fee) => {
+                method_args!(DecimalArg(redemption_fee.clone()))
+            }
+            IssuerMethods::DepositSavings(amount) => {
+                method_args!(FungibleBucketArg(
+                    STABLECOIN_NAME.to_string(),
+                    amount.clone()
+                ))
+            }
+            IssuerMethods::WithdrawSavings(shares) => {
+                method_args!(FungibleBucketArg(
+                    SAVINGS_SHARE_NAME.to_string(),
+                    shares.clone()
+                ))
+            }
+            IssuerMethods::DripSavings | IssuerMethods::GetSavingsState => {
+                method_args!()
+            }
+            IssuerMethods::SetSavingsRate(savings_rate) => {
+                method_args!(DecimalArg(savings_rate.clone()))
+            }
+            IssuerMethods::SetFlashLoanFee(token, flash_loan_fee) => {
+                method_args!(
+                    ResourceAddressArg(token.clone()),
+                    DecimalArg(flash_loan_fee.clone())
+                )
+            }
+            IssuerMethods::SetFlashMintParameters(flash_mint_fee, flash_mint_max) => {
+                method_args!(
+                    DecimalArg(flash_mint_fee.clone()),
+                    DecimalArg(flash_mint_max.clone())
+                )
+            }
+        }
+    }
+
+    fn needs_admin_badge(&self) -> bool {
+        match self {
+            IssuerMethods::NewLender(_, _, _, _, _, _, _, _)
+            | IssuerMethods::ChangeLenderParameters(_, _, _, _, _, _, _)
+            | IssuerMethods::ChangeLenderOracle(_, _, _)
+            | IssuerMethods::ChangeLenderLiquidationMode(_, _, _, _, _)
+            | IssuerMethods::SetMinimumDebt(_, _)
+            | IssuerMethods::SetDebtCeiling(_, _)
+            | IssuerMethods::SetGlobalDebtCeiling(_)
+            | IssuerMethods::SetSurplusBuffer(_)
+            | IssuerMethods::SetSystemAuctionParameters(_, _)
+            | IssuerMethods::SetFlashMintParameters(_, _)
+            | IssuerMethods::SetFlashLoanFee(_, _)
+            | IssuerMethods::SetRedemptionFee(_)
+            | IssuerMethods::SetSavingsRate(_)
+            | IssuerMethods::SetCollateralPriority(_)
+            | IssuerMethods::SetExternalMinters(_, _) => true,
+            _ => false,
+        }
+    }
+
+    fn custom_manifest_name(&self) -> Option<&str> {
+        None
+    }
+}
+
+pub struct PsmBlueprint {}
+
+impl Blueprint for PsmBlueprint {
+    fn instantiation_name(&self) -> &str {
+        "new"
+    }
+
+    fn name(&self) -> &str {
+        "Psm"
+    }
+
+    fn has_admin_badge(&self) -> AdminBadge {
+        AdminBadge::External(ADMIN_BADGE_NAME.to_string())
+    }
+}
+
+pub enum PsmMethods {
+    MintStablecoins(String, Decimal),
+    RedeemStablecoins(Decimal, String),
+    SetAssetParameters(String, Decimal, Decimal, Decimal),
+    ClaimFees,
+    GetAssetState(String),
+}
+
+impl Method for PsmMethods {
+    fn name(&self) -> &str {
+        match self {
+            PsmMethods::MintStablecoins(_, _) => "mint_stablecoins",
+            PsmMethods::RedeemStablecoins(_, _) => "redeem_stablecoins",
+            PsmMethods::SetAssetParameters(_, _, _, _) => "set_asset_parameters",
+            PsmMethods::ClaimFees => "claim_fees",
+            PsmMethods::GetAssetState(_) => "get_asset_state",
+        }
+    }
+
+    fn args(&self) -> Option<Vec<Arg>> {
+        match self {
+            PsmMethods::MintStablecoins(asset, amount) => {
+                method_args!(FungibleBucketArg(asset.clone(), amount.clone()))
+            }
+            PsmMethods::RedeemStablecoins(amount, asset) => {
+                method_args!(
+                    FungibleBucketArg(STABLECOIN_NAME.to_string(), amount.clone()),
+                    ResourceAddressArg(asset.clone())
                 )
             }
-            IssuerMethods::ChangeLenderOracle(oracle) => {
-                method_args!(ComponentAddressArg(oracle.to_string()))
+            PsmMethods::SetAssetParameters(asset, fee_in, fee_out, cap) => {
+                method_args!(
+                    ResourceAddressArg(asset.clone()),
+                    DecimalArg(fee_in.clone()),
+                    DecimalArg(fee_out.clone()),
+                    DecimalArg(cap.clone())
+                )
+            }
+            PsmMethods::ClaimFees => {
+                method_args!()
+            }
+            PsmMethods::GetAssetState(asset) => {
+                method_args!(ResourceAddressArg(asset.clone()))
             }
         }
     }
 
     fn needs_admin_badge(&self) -> bool {
         match self {
-            IssuerMethods::NewLender(_, _, _, _, _, _)
-            | IssuerMethods::ChangeLenderParameters(_, _, _, _, _)
-            | IssuerMethods::ChangeLenderOracle(_) => true,
+            PsmMethods::SetAssetParameters(_, _, _, _) | PsmMethods::ClaimFees => true,
             _ => false,
         }
     }
@@ -1,11 +1,16 @@
 use crate::dumb_oracle::utils::{instantiate_oracle, new_oracle};
 use crate::stablecoin::issuer_state::IssuerState;
-use crate::stablecoin::sqrt_implem::{IssuerBlueprint, IssuerMethods};
-use crate::utils::{run_command, ADMIN_BADGE_NAME, STABLECOIN_NAME};
+use crate::stablecoin::sqrt_implem::{IssuerBlueprint, IssuerMethods, PsmBlueprint, PsmMethods};
+use crate::utils::{run_command, ADMIN_BADGE_NAME, FLASH_LOAN_NAME, STABLECOIN_NAME};
 use lazy_static::lazy_static;
 use regex::Regex;
 use scrypto::prelude::{dec, Decimal};
-use sqrt::method::Arg::{FungibleBucketArg, ResourceAddressArg};
+use sqrt::manifest_call::ManifestCall;
+use sqrt::method::Arg;
+use sqrt::method::Arg::{
+    AccountAddressArg, ComponentAddressArg, DecimalArg, FungibleBucketArg, ResourceAddressArg,
+    StringArg,
+};
 use sqrt::package::Package;
 use sqrt::test_environment::TestEnvironment;
 use std::process::Command;
@@ -21,15 +26,59 @@ pub fn instantiate() -> (TestEnvironment, IssuerState) {
     let mut issuer_package = Package::new("../stablecoin");
     issuer_package.add_blueprint("issuer_bp", issuer_blueprint);
     test_env.publish_package("issuer", issuer_package);
+    new_issuer(&mut test_env);
+
+    instantiate_oracle(&mut test_env);
+
+    let issuer_address = test_env.get_component("issuer_comp").unwrap();
+    let mut issuer_state = IssuerState::from(issuer_address.to_string());
+    issuer_state.update();
+
+    (test_env, issuer_state)
+}
+
+/// Instantiates an issuer along with a peg stability module accepting usdc without fees, whose
+/// supply is reported by the issuer
+pub fn instantiate_with_psm() -> (TestEnvironment, IssuerState) {
+    let mut test_env = TestEnvironment::new();
+    test_env.create_fixed_supply_token(ADMIN_BADGE_NAME, dec!(3));
+
+    test_env.create_fixed_supply_token("btc", dec!(10000000));
+    test_env.create_fixed_supply_token("usdc", dec!(10000000));
+    test_env.create_mintable_token(STABLECOIN_NAME, ADMIN_BADGE_NAME);
+
+    let issuer_blueprint = Box::new(IssuerBlueprint {});
+    let psm_blueprint = Box::new(PsmBlueprint {});
+    let mut issuer_package = Package::new("../stablecoin");
+    issuer_package.add_blueprint("issuer_bp", issuer_blueprint);
+    issuer_package.add_blueprint("psm_bp", psm_blueprint);
+    test_env.publish_package("issuer", issuer_package);
+
     test_env.new_component(
-        "issuer_comp",
-        "issuer_bp",
+        "psm_comp",
+        "psm_bp",
         vec![
             ResourceAddressArg(ADMIN_BADGE_NAME.to_string()),
             FungibleBucketArg(ADMIN_BADGE_NAME.to_string(), Decimal::ONE),
             ResourceAddressArg(STABLECOIN_NAME.to_string()),
         ],
     );
+    test_env
+        .call_method(PsmMethods::SetAssetParameters(
+            "usdc".to_string(),
+            Decimal::ZERO,
+            Decimal::ZERO,
+            dec!(10000),
+        ))
+        .run();
+
+    new_issuer(&mut test_env);
+    test_env
+        .call_method(IssuerMethods::SetExternalMinters(
+            vec!["psm_comp".to_string()],
+            vec![],
+        ))
+        .run();
 
     instantiate_oracle(&mut test_env);
 
@@ -40,6 +89,178 @@ pub fn instantiate() -> (TestEnvironment, IssuerState) {
     (test_env, issuer_state)
 }
 
+fn new_issuer(test_env: &mut TestEnvironment) {
+    test_env.new_component(
+        "issuer_comp",
+        "issuer_bp",
+        vec![
+            ResourceAddressArg(ADMIN_BADGE_NAME.to_string()),
+            FungibleBucketArg(ADMIN_BADGE_NAME.to_string(), Decimal::ONE),
+            ResourceAddressArg(STABLECOIN_NAME.to_string()),
+        ],
+    );
+}
+
+pub fn instantiate_psm() -> TestEnvironment {
+    let mut test_env = TestEnvironment::new();
+    test_env.create_fixed_supply_token(ADMIN_BADGE_NAME, dec!(2));
+
+    test_env.create_fixed_supply_token("usdc", dec!(10000000));
+    test_env.create_fixed_supply_token("btc", dec!(10000000));
+    test_env.create_mintable_token(STABLECOIN_NAME, ADMIN_BADGE_NAME);
+
+    let psm_blueprint = Box::new(PsmBlueprint {});
+    let mut psm_package = Package::new("../stablecoin");
+    psm_package.add_blueprint("psm_bp", psm_blueprint);
+    test_env.publish_package("psm", psm_package);
+    test_env.new_component(
+        "psm_comp",
+        "psm_bp",
+        vec![
+            ResourceAddressArg(ADMIN_BADGE_NAME.to_string()),
+            FungibleBucketArg(ADMIN_BADGE_NAME.to_string(), Decimal::ONE),
+            ResourceAddressArg(STABLECOIN_NAME.to_string()),
+        ],
+    );
+
+    test_env
+        .call_method(PsmMethods::SetAssetParameters(
+            "usdc".to_string(),
+            dec!("0.001"),
+            dec!("0.002"),
+            dec!(10000),
+        ))
+        .run();
+
+    test_env
+}
+
+pub fn flash_loan_and_repay<'a>(
+    test_env: &'a mut TestEnvironment,
+    token: &'a str,
+    amount: Decimal,
+    fee: Decimal,
+) -> ManifestCall<'a> {
+    let env_args = flash_loan_args(test_env, token, amount, fee);
+
+    test_env.call_custom_manifest("flash_loan_and_repay", env_args)
+}
+
+pub fn flash_loan_and_settle_system_debt<'a>(
+    test_env: &'a mut TestEnvironment,
+    token: &'a str,
+    amount: Decimal,
+    fee: Decimal,
+) -> ManifestCall<'a> {
+    let env_args = flash_loan_args(test_env, token, amount, fee);
+
+    test_env.call_custom_manifest("flash_loan_and_settle_system_debt", env_args)
+}
+
+fn flash_loan_args(
+    test_env: &TestEnvironment,
+    token: &str,
+    amount: Decimal,
+    fee: Decimal,
+) -> Vec<(String, Arg)> {
+    let mut env_args = Vec::new();
+    env_args.push((
+        "caller_address".to_string(),
+        AccountAddressArg(test_env.get_current_account_name().to_string()),
+    ));
+    env_args.push((
+        "component_address".to_string(),
+        ComponentAddressArg(test_env.get_current_component_name().unwrap().to_string()),
+    ));
+    env_args.push((
+        "token_address".to_string(),
+        ResourceAddressArg(token.to_string()),
+    ));
+    env_args.push(("amount".to_string(), DecimalArg(amount)));
+    env_args.push(("fee".to_string(), DecimalArg(fee)));
+    env_args.push((
+        "flash_loan_address".to_string(),
+        ResourceAddressArg(FLASH_LOAN_NAME.to_string()),
+    ));
+
+    env_args
+}
+
+pub fn create_token_with_divisibility(
+    test_env: &mut TestEnvironment,
+    token_name: &str,
+    divisibility: u8,
+    initial_supply: Decimal,
+) {
+    let mut env_args = Vec::new();
+    env_args.push((
+        "caller_address".to_string(),
+        AccountAddressArg(test_env.get_current_account_name().to_string()),
+    ));
+    env_args.push(("token_name".to_string(), StringArg(token_name.to_string())));
+    env_args.push((
+        "divisibility".to_string(),
+        StringArg(divisibility.to_string()),
+    ));
+    env_args.push(("initial_supply".to_string(), DecimalArg(initial_supply)));
+
+    test_env
+        .call_custom_manifest("create_token_with_divisibility", env_args)
+        .run();
+}
+
+pub fn get_psm_asset_state(
+    test_env: &TestEnvironment,
+    asset: &str,
+) -> (Decimal, Decimal, Decimal, Decimal) {
+    let psm_address = test_env.get_component("psm_comp").unwrap();
+    let output = run_command(
+        Command::new("resim")
+            .arg("call-method")
+            .arg(psm_address)
+            .arg("get_asset_state")
+            .arg(test_env.get_resource(asset)),
+    );
+
+    lazy_static! {
+        static ref ASSET_STATE_RE: Regex = Regex::new(r#"├─ Tuple\(Decimal\("([\d.]*)"\), Decimal\("([\d.]*)"\), Decimal\("([\d.]*)"\), Decimal\("([\d.]*)"\)\)"#).unwrap();
+    }
+
+    let capture = &ASSET_STATE_RE
+        .captures(&output)
+        .expect("Could not find the asset state");
+    (
+        Decimal::from(&capture[1]),
+        Decimal::from(&capture[2]),
+        Decimal::from(&capture[3]),
+        Decimal::from(&capture[4]),
+    )
+}
+
+pub fn get_protocol_state(test_env: &TestEnvironment) -> (Decimal, Decimal, Decimal, Decimal) {
+    let issuer_address = test_env.get_component("issuer_comp").unwrap();
+    let output = run_command(
+        Command::new("resim")
+            .arg("call-method")
+            .arg(issuer_address)
+            .arg("get_protocol_state"),
+    );
+
+    lazy_static! {
+        static ref PROTOCOL_STATE_RE: Regex = Regex::new(r#"├─ Tuple\(Decimal\("([\d.]*)"\), Decimal\("([\d.]*)"\), Decimal\("([\d.]*)"\), Decimal\("([\d.]*)"\)\)"#).unwrap();
+    }
+
+    let capture = &PROTOCOL_STATE_RE
+        .captures(&output)
+        .expect("Could not find the protocol state");
+    (
+        Decimal::from(&capture[1]),
+        Decimal::from(&capture[2]),
+        Decimal::from(&capture[3]),
+        Decimal::from(&capture[4]),
+    )
+}
+
 pub fn new_default_lender(test_env: &mut TestEnvironment, token: &str) {
     let component_name = new_oracle(test_env, token);
 
@@ -50,6 +271,8 @@ pub fn new_default_lender(test_env: &mut TestEnvironment, token: &str) {
             dec!("0.0001"),
             dec!("1.3"),
             dec!("0.1"),
+            dec!("0.1"),
+            Decimal::ONE,
             component_name,
         ))
         .run();
@@ -61,23 +284,23 @@ pub fn assert_current_has_loan(
     collateral_token: &str,
     collateral_amount: Decimal,
     amount_lent: Decimal,
+    normalised_debt: Decimal,
     loan_date: i64,
-    interest_rate: Decimal,
 ) {
     let current_account = test_env.get_current_account_address();
     let output = run_command(Command::new("resim").arg("show").arg(current_account));
 
     lazy_static! {
-        static ref LOAN_RE: Regex = Regex::new(r#"NonFungible \{ id: NonFungibleLocalId\("(.*)"\), immutable_data: Tuple\(ResourceAddress\("(\w*)"\), (\w*)i64, Decimal\("([\d.]*)"\)\), mutable_data: Tuple\(Decimal\("([\d.]*)"\), Decimal\("([\d.]*)"\)\) \}"#).unwrap();
+        static ref LOAN_RE: Regex = Regex::new(r#"NonFungible \{ id: NonFungibleLocalId\("(.*)"\), immutable_data: Tuple\(ResourceAddress\("(\w*)"\), (\w*)i64\), mutable_data: Tuple\(Decimal\("([\d.]*)"\), Decimal\("([\d.]*)"\), Decimal\("([\d.]*)"\)\) \}"#).unwrap();
     }
 
     for loan_capture in LOAN_RE.captures_iter(&output) {
         if loan_id.to_string() == String::from(&loan_capture[1]) {
             let collateral_token_found = String::from(&loan_capture[2]);
             let loan_date_found = String::from(&loan_capture[3]).parse::<i64>().unwrap();
-            let interest_rate_found = Decimal::from(&loan_capture[4]);
-            let collateral_amount_found = Decimal::from(&loan_capture[5]);
-            let amount_lent_found = Decimal::from(&loan_capture[6]);
+            let collateral_amount_found = Decimal::from(&loan_capture[4]);
+            let amount_lent_found = Decimal::from(&loan_capture[5]);
+            let normalised_debt_found = Decimal::from(&loan_capture[6]);
 
             assert_eq!(
                 test_env.get_resource(collateral_token).clone(),
@@ -85,8 +308,8 @@ pub fn assert_current_has_loan(
             );
             assert_eq!(collateral_amount, collateral_amount_found);
             assert_eq!(amount_lent, amount_lent_found);
+            assert_eq!(normalised_debt, normalised_debt_found);
             assert_eq!(loan_date, loan_date_found);
-            assert_eq!(interest_rate, interest_rate_found);
 
             return;
         }
@@ -98,7 +321,7 @@ pub fn assert_current_has_no_loan_id(test_env: &TestEnvironment, loan_id: &str)
     let output = run_command(Command::new("resim").arg("show").arg(current_account));
 
     lazy_static! {
-        static ref LOAN_RE: Regex = Regex::new(r#"NonFungible \{ id: NonFungibleLocalId\("(.)*"\), immutable_data: Tuple\(ResourceAddress\("(\w*)"\), (\w*)i64, Decimal\("([\d.]*)"\), Decimal\("([\d.]*)"\)\), mutable_data: Tuple\(Decimal\("([\d.]*)"\), Decimal\("([\d.]*)"\)\) \}"#).unwrap();
+        static ref LOAN_RE: Regex = Regex::new(r#"NonFungible \{ id: NonFungibleLocalId\("(.)*"\), immutable_data: Tuple\(ResourceAddress\("(\w*)"\), (\w*)i64\), mutable_data: Tuple\(Decimal\("([\d.]*)"\), Decimal\("([\d.]*)"\), Decimal\("([\d.]*)"\)\) \}"#).unwrap();
     }
 
     for loan_capture in LOAN_RE.captures_iter(&output) {
@@ -1,10 +1,18 @@
 use std::process::Command;
 
 pub const ADMIN_BADGE_NAME: &str = "Stoichiometric protocol admin badge";
+pub const BASKET_LOAN_NAME: &str = "Stoichiometric Basket Loan";
+pub const FLASH_LOAN_NAME: &str = "Stoichiometric Flash Loan";
 pub const FLASH_MINT_NAME: &str = "Stoichiometric Flash Mint";
 pub const LOAN_NAME: &str = "Stoichiometric Loan";
+pub const MINTER_BADGE_NAME: &str = "Stoichiometric Stablecoin Minter Badge";
+pub const POOL_FLASH_LOAN_NAME: &str = "Stoichiometric Pool Flash Loan";
 pub const POSITION_NAME: &str = "Stoichiometric Position";
 pub const PROPOSAL_RECEIPT: &str = "Stoichiometric proposal receipt";
+pub const REPORTER_BADGE_NAME: &str = "Stoichiometric Price Reporter";
+pub const RESERVE_CLAIM_NAME: &str = "Stoichiometric Reserve Claim";
+pub const SAVINGS_SHARE_NAME: &str = "Stoichiometric Savings Share";
+pub const STABILITY_DEPOSIT_NAME: &str = "Stoichiometric Stability Deposit";
 pub const STABLECOIN_NAME: &str = "Stoichiometric USD";
 pub const STABLECOIN_MINTER: &str = "Stoichiometric stablecoin minter";
 pub const VOTER_CARD_NAME: &str = "Stoichiometric voter card";
@@ -1 +1,5 @@
+pub mod observation_array_tests;
+pub mod oracle_tests;
+pub mod pool_statistics_tests;
+pub mod timestamped_array_tests;
 pub mod unit_tests;
@@ -0,0 +1,258 @@
+use proptest::prelude::*;
+use stoichiometric_dex::observation_array::{Observation, ObservationArray, ARRAY_LENGTH};
+
+/// Deterministic pseudo-random generator used to produce long sequences of observations
+struct XorShift(u64);
+
+impl XorShift {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+}
+
+/// Pushes `nb_pushes` observations with increasing timestamps to a new [`ObservationArray`] and
+/// returns it along with every pushed observation.
+fn fill_array(nb_pushes: usize, seed: u64) -> (ObservationArray, Vec<Observation>) {
+    let mut rng = XorShift(seed | 1);
+    let mut array = ObservationArray::new();
+    let mut pushed = Vec::with_capacity(nb_pushes);
+    let mut timestamp = (rng.next() % 1_000_000) as i64;
+
+    for _ in 0..nb_pushes {
+        let step = (rng.next() % 65536) as u16;
+        array.push(timestamp, step);
+        pushed.push(Observation::from(timestamp, step));
+        timestamp += 60 * (1 + (rng.next() % 10) as i64);
+    }
+
+    (array, pushed)
+}
+
+/// Straightforward implementation of the time weighted average step over the given observations
+fn expected_twas(observations: &[Observation], timestamp: i64, current_timestamp: i64) -> u16 {
+    let mut start_index = 0;
+    for (i, obs) in observations.iter().enumerate() {
+        if obs.timestamp <= timestamp {
+            start_index = i;
+        }
+    }
+    let begin = timestamp.max(observations[start_index].timestamp);
+    if current_timestamp <= begin {
+        return observations.last().unwrap().step;
+    }
+
+    let mut total: i64 = 0;
+    for i in start_index..observations.len() {
+        let from = observations[i].timestamp.max(begin);
+        let to = if i + 1 < observations.len() {
+            observations[i + 1].timestamp.min(current_timestamp)
+        } else {
+            current_timestamp
+        };
+        if to > from {
+            total += (to - from) * observations[i].step as i64;
+        }
+    }
+
+    (total / (current_timestamp - begin)) as u16
+}
+
+#[test]
+fn test_empty_array() {
+    let array = ObservationArray::new();
+
+    assert!(array.is_empty());
+    assert!(array.get(0).is_none());
+    assert!(array.last().is_none());
+    assert_eq!(array.get_time_weighted_average_step_since(0, 100), None);
+}
+
+#[test]
+fn test_single_observation() {
+    let mut array = ObservationArray::new();
+    array.push(60, 1000);
+
+    assert_eq!(
+        array.get_time_weighted_average_step_since(0, 600),
+        Some(1000)
+    );
+    assert_eq!(
+        array.get_time_weighted_average_step_since(120, 600),
+        Some(1000)
+    );
+    assert_eq!(
+        array.get_time_weighted_average_step_since(600, 600),
+        Some(1000)
+    );
+}
+
+#[test]
+fn test_observation_at_same_timestamp_is_replaced() {
+    let mut array = ObservationArray::new();
+    array.push(60, 1000);
+    array.push(60, 2000);
+
+    assert_eq!(array.len(), 1);
+    assert_eq!(array.get(0), Some(&Observation::from(60, 2000)));
+}
+
+#[test]
+#[should_panic(expected = "Observations should be pushed in chronological order")]
+fn test_push_older_observation_fails() {
+    let mut array = ObservationArray::new();
+    array.push(120, 1000);
+    array.push(60, 2000);
+}
+
+#[test]
+fn test_average_starting_between_observations() {
+    let mut array = ObservationArray::new();
+    array.push(0, 100);
+    array.push(100, 200);
+    array.push(200, 400);
+
+    // From 50 to 100 the step is 100, from 100 to 200 it is 200 and from 200 to 300 it is 400
+    assert_eq!(
+        array.get_time_weighted_average_step_since(50, 300),
+        Some(260)
+    );
+}
+
+#[test]
+fn test_average_starting_before_first_observation() {
+    let mut array = ObservationArray::new();
+    array.push(1000, 100);
+    array.push(2000, 300);
+
+    // Nothing is known before the first observation so the average starts at 1000
+    assert_eq!(
+        array.get_time_weighted_average_step_since(0, 3000),
+        Some(200)
+    );
+}
+
+#[test]
+fn test_wrap_around_keeps_most_recent_observations() {
+    let nb_pushes = ARRAY_LENGTH as usize + 12345;
+    let (array, pushed) = fill_array(nb_pushes, 42);
+
+    assert_eq!(array.len(), ARRAY_LENGTH as usize);
+    assert_eq!(array.get(0), Some(&pushed[12345]));
+    assert_eq!(array.last(), pushed.last());
+    assert!(array.get(ARRAY_LENGTH as usize).is_none());
+}
+
+#[test]
+fn test_growing_wrapped_array_keeps_history() {
+    let mut array = ObservationArray::with_capacity(3);
+    for i in 0..5 {
+        array.push(60 * i, i as u16);
+    }
+    array.resize(5);
+    array.push(300, 5);
+    array.push(360, 6);
+
+    assert_eq!(array.capacity(), 5);
+    assert_eq!(array.len(), 5);
+    assert_eq!(array.get(0), Some(&Observation::from(120, 2)));
+    assert_eq!(array.last(), Some(&Observation::from(360, 6)));
+}
+
+#[test]
+fn test_shrinking_array_keeps_most_recent_observations() {
+    let mut array = ObservationArray::with_capacity(4);
+    for i in 0..6 {
+        array.push(60 * i, i as u16);
+    }
+    array.resize(2);
+
+    assert_eq!(array.len(), 2);
+    assert_eq!(array.get(0), Some(&Observation::from(240, 4)));
+    assert_eq!(array.get(1), Some(&Observation::from(300, 5)));
+
+    array.push(360, 6);
+    assert_eq!(array.get(0), Some(&Observation::from(300, 5)));
+    assert_eq!(array.last(), Some(&Observation::from(360, 6)));
+}
+
+#[test]
+#[should_panic(expected = "The capacity of the array should be positive")]
+fn test_resize_to_zero_fails() {
+    let mut array = ObservationArray::new();
+    array.resize(0);
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(24))]
+
+    #[test]
+    fn prop_array_stores_last_observations(nb_pushes in 1usize..150_000, seed in any::<u64>()) {
+        let (array, pushed) = fill_array(nb_pushes, seed);
+        let kept = &pushed[nb_pushes.saturating_sub(ARRAY_LENGTH as usize)..];
+
+        prop_assert_eq!(array.len(), kept.len());
+        for (i, obs) in kept.iter().enumerate().step_by(97) {
+            prop_assert_eq!(array.get(i), Some(obs));
+        }
+        prop_assert_eq!(array.last(), kept.last());
+        prop_assert!(array.get(kept.len()).is_none());
+    }
+
+    #[test]
+    fn prop_twas_matches_reference(
+        nb_pushes in 1usize..150_000,
+        seed in any::<u64>(),
+        since_fraction in 0u64..=1000,
+        delay in 0i64..100_000,
+    ) {
+        let (array, pushed) = fill_array(nb_pushes, seed);
+        let kept = &pushed[nb_pushes.saturating_sub(ARRAY_LENGTH as usize)..];
+
+        let first = kept.first().unwrap().timestamp;
+        let current_timestamp = kept.last().unwrap().timestamp + delay;
+        let since = first - 600 + ((current_timestamp - first + 600) as u64 * since_fraction / 1000) as i64;
+
+        let twas = array.get_time_weighted_average_step_since(since, current_timestamp).unwrap();
+        prop_assert_eq!(twas, expected_twas(kept, since, current_timestamp));
+
+        // The average should be bounded by the observed steps
+        let min_step = kept.iter().map(|obs| obs.step).min().unwrap();
+        let max_step = kept.iter().map(|obs| obs.step).max().unwrap();
+        prop_assert!(twas >= min_step && twas <= max_step);
+    }
+
+    #[test]
+    fn prop_resize_keeps_last_observations(
+        capacity in 1u16..500,
+        new_capacity in 1u16..500,
+        nb_pushes in 1usize..1500,
+        nb_extra_pushes in 0usize..1500,
+        seed in any::<u64>(),
+    ) {
+        let mut rng = XorShift(seed | 1);
+        let mut array = ObservationArray::with_capacity(capacity);
+        let mut pushed = Vec::new();
+        for i in 0..nb_pushes + nb_extra_pushes {
+            if i == nb_pushes {
+                array.resize(new_capacity);
+                let kept = pushed.len().saturating_sub(new_capacity as usize);
+                pushed.drain(..kept);
+            }
+            let obs = Observation::from(60 * i as i64, (rng.next() % 65536) as u16);
+            array.push(obs.timestamp, obs.step);
+            pushed.push(obs);
+            let current_capacity = if i < nb_pushes { capacity } else { new_capacity };
+            if pushed.len() > current_capacity as usize {
+                pushed.remove(0);
+            }
+        }
+
+        prop_assert_eq!(array.len(), pushed.len());
+        for (i, obs) in pushed.iter().enumerate() {
+            prop_assert_eq!(array.get(i), Some(obs));
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use stoichiometric_dex::constants::NB_STEP;
+use stoichiometric_dex::observation_array::{Observation, ObservationArray};
+use stoichiometric_dex::oracle::truncate_step;
+
+/// Records an observation in both series the way the oracle does
+fn observe(
+    raw: &mut ObservationArray,
+    truncated: &mut ObservationArray,
+    timestamp: i64,
+    step: u16,
+    max_step_change_per_minute: u16,
+) {
+    let truncated_step = truncate_step(
+        truncated.last(),
+        timestamp,
+        step,
+        max_step_change_per_minute,
+    );
+    raw.push(timestamp, step);
+    truncated.push(timestamp, truncated_step);
+}
+
+#[test]
+fn test_first_observation_is_not_truncated() {
+    assert_eq!(truncate_step(None, 60, 60000, 100), 60000);
+}
+
+#[test]
+fn test_small_change_is_not_truncated() {
+    let last_obs = Observation::from(60, 50000);
+
+    assert_eq!(truncate_step(Some(&last_obs), 120, 50050, 100), 50050);
+    assert_eq!(truncate_step(Some(&last_obs), 120, 49900, 100), 49900);
+}
+
+#[test]
+fn test_jump_up_is_clamped() {
+    let last_obs = Observation::from(60, 50000);
+
+    assert_eq!(truncate_step(Some(&last_obs), 120, 60000, 100), 50100);
+}
+
+#[test]
+fn test_jump_down_is_clamped() {
+    let last_obs = Observation::from(60, 50000);
+
+    assert_eq!(truncate_step(Some(&last_obs), 120, 40000, 100), 49900);
+}
+
+#[test]
+fn test_max_change_grows_with_elapsed_minutes() {
+    let last_obs = Observation::from(60, 50000);
+
+    assert_eq!(truncate_step(Some(&last_obs), 360, 60000, 100), 50500);
+    assert_eq!(truncate_step(Some(&last_obs), 360, 40000, 100), 49500);
+}
+
+#[test]
+fn test_less_than_a_minute_counts_as_one_minute() {
+    let last_obs = Observation::from(60, 50000);
+
+    assert_eq!(truncate_step(Some(&last_obs), 90, 60000, 100), 50100);
+}
+
+#[test]
+fn test_clamp_stays_within_step_range() {
+    let low_obs = Observation::from(60, 30);
+    let high_obs = Observation::from(60, NB_STEP - 30);
+
+    assert_eq!(truncate_step(Some(&low_obs), 120, 0, 100), 0);
+    assert_eq!(truncate_step(Some(&high_obs), 120, NB_STEP, 100), NB_STEP);
+    assert_eq!(
+        truncate_step(Some(&low_obs), 60 * 100_000, NB_STEP, u16::MAX),
+        NB_STEP
+    );
+}
+
+#[test]
+fn test_truncated_twap_differs_from_raw_twap_after_jump() {
+    let mut raw = ObservationArray::new();
+    let mut truncated = ObservationArray::new();
+
+    observe(&mut raw, &mut truncated, 0, 50000, 100);
+    observe(&mut raw, &mut truncated, 60, 50000, 100);
+    observe(&mut raw, &mut truncated, 120, 60000, 100);
+    observe(&mut raw, &mut truncated, 180, 60000, 100);
+
+    assert_eq!(truncated.last(), Some(&Observation::from(180, 50200)));
+
+    // From 0 to 120 the step is 50000 and from 120 to 300 it is 60000
+    assert_eq!(
+        raw.get_time_weighted_average_step_since(0, 300),
+        Some(56000)
+    );
+
+    // The truncated series only moves to 50100 at 120 and to 50200 at 180
+    assert_eq!(
+        truncated.get_time_weighted_average_step_since(0, 300),
+        Some(50100)
+    );
+}
+
+#[test]
+fn test_truncated_series_catches_up_with_persistent_jump() {
+    let mut raw = ObservationArray::new();
+    let mut truncated = ObservationArray::new();
+
+    observe(&mut raw, &mut truncated, 0, 50000, 100);
+    observe(&mut raw, &mut truncated, 60, 50250, 100);
+    observe(&mut raw, &mut truncated, 180, 50250, 100);
+
+    assert_eq!(truncated.get(1), Some(&Observation::from(60, 50100)));
+    assert_eq!(truncated.last(), Some(&Observation::from(180, 50250)));
+}
@@ -0,0 +1,123 @@
+use scrypto::prelude::{dec, Decimal};
+use stoichiometric_dex::pool_statistics::{PoolStatistics, StatisticsArray};
+
+/// Returns a snapshot obtained by recording a swap on top of the given snapshot
+fn record(
+    last: &PoolStatistics,
+    timestamp: i64,
+    stable_volume: Decimal,
+    other_volume: Decimal,
+    liquidity: Decimal,
+) -> PoolStatistics {
+    let mut statistics = last.accumulated_to(timestamp);
+    statistics.stable_volume += stable_volume;
+    statistics.other_volume += other_volume;
+    statistics.stable_fees += stable_volume * dec!("0.003");
+    statistics.liquidity = liquidity;
+    statistics
+}
+
+#[test]
+fn test_empty_array() {
+    let array = StatisticsArray::new();
+
+    assert!(array.is_empty());
+    assert_eq!(
+        array.get_statistics_since(0, 1000),
+        (
+            Decimal::ZERO,
+            Decimal::ZERO,
+            Decimal::ZERO,
+            Decimal::ZERO,
+            Decimal::ZERO
+        )
+    );
+}
+
+#[test]
+fn test_volumes_since_creation() {
+    let mut array = StatisticsArray::new();
+    array.push(PoolStatistics::new(0));
+    let first = record(array.last().unwrap(), 60, dec!(100), dec!(50), dec!(1000));
+    array.push(first);
+    let second = record(array.last().unwrap(), 120, dec!(20), dec!(10), dec!(1000));
+    array.push(second);
+
+    let (stable_volume, other_volume, stable_fees, other_fees, _) =
+        array.get_statistics_since(0, 180);
+
+    assert_eq!(stable_volume, dec!(120));
+    assert_eq!(other_volume, dec!(60));
+    assert_eq!(stable_fees, dec!("0.36"));
+    assert_eq!(other_fees, Decimal::ZERO);
+}
+
+#[test]
+fn test_volumes_over_window() {
+    let mut array = StatisticsArray::new();
+    array.push(PoolStatistics::new(0));
+    let first = record(array.last().unwrap(), 60, dec!(100), dec!(50), dec!(1000));
+    array.push(first);
+    let second = record(array.last().unwrap(), 120, dec!(20), dec!(10), dec!(1000));
+    array.push(second);
+
+    let (stable_volume, other_volume, _, _, _) = array.get_statistics_since(90, 180);
+
+    assert_eq!(stable_volume, dec!(20));
+    assert_eq!(other_volume, dec!(10));
+}
+
+#[test]
+fn test_time_weighted_liquidity() {
+    let mut array = StatisticsArray::new();
+    array.push(PoolStatistics::new(0));
+    let first = record(
+        array.last().unwrap(),
+        60,
+        Decimal::ZERO,
+        Decimal::ZERO,
+        dec!(100),
+    );
+    array.push(first);
+    let second = record(
+        array.last().unwrap(),
+        120,
+        Decimal::ZERO,
+        Decimal::ZERO,
+        dec!(300),
+    );
+    array.push(second);
+
+    // No liquidity between 0 and 60, 100 between 60 and 120 and 300 between 120 and 180
+    let (_, _, _, _, liquidity) = array.get_statistics_since(0, 180);
+    assert_eq!(liquidity, dec!(400) / 3);
+
+    // Window starting between two snapshots
+    let (_, _, _, _, liquidity) = array.get_statistics_since(90, 180);
+    assert_eq!(liquidity, dec!(21000) / 90);
+
+    // Empty window returns the current liquidity
+    let (_, _, _, _, liquidity) = array.get_statistics_since(180, 180);
+    assert_eq!(liquidity, dec!(300));
+}
+
+#[test]
+fn test_same_timestamp_replaces_snapshot() {
+    let mut array = StatisticsArray::new();
+    array.push(PoolStatistics::new(0));
+    let first = record(array.last().unwrap(), 60, dec!(100), dec!(50), dec!(1000));
+    array.push(first);
+    let second = record(array.last().unwrap(), 60, dec!(20), dec!(10), dec!(1000));
+    array.push(second);
+
+    assert_eq!(array.len(), 2);
+    assert_eq!(array.get_statistics_since(0, 60).0, dec!(120));
+}
+
+#[test]
+#[should_panic(expected = "Statistics should be pushed in chronological order")]
+fn test_push_in_the_past() {
+    let mut array = StatisticsArray::new();
+    array.push(PoolStatistics::new(60));
+    array.push(PoolStatistics::new(0));
+}
@@ -0,0 +1,35 @@
+use stoichiometric_dex::observation_array::Observation;
+use stoichiometric_dex::timestamped_array::TimestampedArray;
+
+#[test]
+fn test_index_at() {
+    let mut array = TimestampedArray::with_capacity(3);
+    for i in 1..5 {
+        array.push(Observation::from(60 * i, i as u16));
+    }
+
+    // The observation made at 60 was overwritten, so the oldest one is made at 120
+    assert_eq!(array.index_at(0), 0);
+    assert_eq!(array.index_at(120), 0);
+    assert_eq!(array.index_at(179), 0);
+    assert_eq!(array.index_at(180), 1);
+    assert_eq!(array.index_at(1000), 2);
+}
+
+#[test]
+fn test_entry_at_same_timestamp_is_replaced() {
+    let mut array = TimestampedArray::with_capacity(3);
+    array.push(Observation::from(60, 1));
+    array.push(Observation::from(60, 2));
+
+    assert_eq!(array.len(), 1);
+    assert_eq!(array.last(), Some(&Observation::from(60, 2)));
+}
+
+#[test]
+#[should_panic(expected = "Observations should be pushed in chronological order")]
+fn test_push_in_the_past_fails() {
+    let mut array = TimestampedArray::with_capacity(3);
+    array.push(Observation::from(120, 1));
+    array.push(Observation::from(60, 2));
+}
@@ -1,11 +1,12 @@
-use scrypto::prelude::{dec, Decimal};
+use scrypto::prelude::{dec, Decimal, Instant};
 use sqrt::error::Error;
 use std::collections::HashMap;
 use stoichiometric_tests::dex::pool_state::StepState;
 use stoichiometric_tests::dex::sqrt_implem::RouterMethods;
 use stoichiometric_tests::dex::utils::{
     add_liquidity, add_liquidity_at_step, add_liquidity_at_steps, assert_current_position,
-    assert_no_positions, create_pool, instantiate,
+    assert_no_positions, create_pool, flash_loan_and_repay, flash_loan_and_swap,
+    get_oracle_parameters, get_twap_since, instantiate,
 };
 use stoichiometric_tests::utils::POSITION_NAME;
 
@@ -191,3 +192,257 @@ fn create_pool_initial_rate_greater_than_max_fail() {
         ))
         .run();
 }
+
+#[test]
+fn test_new_observation() {
+    let mut test_env = instantiate();
+    create_pool(&mut test_env, "btc", dec!(20000), dec!(100), dec!(100000));
+
+    test_env
+        .call_method(RouterMethods::NewObservation("btc".to_string()))
+        .run();
+}
+
+#[test]
+fn test_change_oracle_parameters() {
+    let mut test_env = instantiate();
+    create_pool(&mut test_env, "btc", dec!(20000), dec!(100), dec!(100000));
+
+    test_env
+        .call_method(RouterMethods::ChangeOracleParameters(
+            "btc".to_string(),
+            300,
+            50,
+        ))
+        .run();
+
+    assert_eq!(get_oracle_parameters(&test_env, "btc"), (300, 50, 65535));
+}
+
+#[test]
+fn test_change_oracle_parameters_zero_step_change_fail() {
+    let mut test_env = instantiate();
+    create_pool(&mut test_env, "btc", dec!(20000), dec!(100), dec!(100000));
+
+    test_env
+        .call_method(RouterMethods::ChangeOracleParameters(
+            "btc".to_string(),
+            300,
+            0,
+        ))
+        .should_panic(Error::AssertFailed(
+            "The maximum step change per minute should be positive".to_string(),
+        ))
+        .run();
+}
+
+#[test]
+fn test_flash_loan_more_than_liquidity_fail() {
+    let mut test_env = instantiate();
+    create_pool(&mut test_env, "btc", dec!(20000), dec!(100), dec!(100000));
+
+    test_env
+        .call_method(RouterMethods::FlashLoan(
+            "btc".to_string(),
+            "btc".to_string(),
+            dec!(1),
+        ))
+        .should_panic(Error::AssertFailed(
+            "Not enough liquidity to lend 1 tokens".to_string(),
+        ))
+        .run();
+}
+
+#[test]
+fn test_flash_loan_zero_fail() {
+    let mut test_env = instantiate();
+    create_pool(&mut test_env, "btc", dec!(20000), dec!(100), dec!(100000));
+
+    test_env
+        .call_method(RouterMethods::FlashLoan(
+            "btc".to_string(),
+            "btc".to_string(),
+            dec!(0),
+        ))
+        .should_panic(Error::AssertFailed(
+            "The amount to lend should be positive".to_string(),
+        ))
+        .run();
+}
+
+#[test]
+fn test_flash_loan_and_repay() {
+    let mut test_env = instantiate();
+    let mut pool_usd_btc = create_pool(&mut test_env, "btc", dec!(20000), dec!(100), dec!(100000));
+    add_liquidity(
+        &mut test_env,
+        dec!(10000),
+        "btc",
+        Decimal::ZERO,
+        dec!(20000),
+        None,
+    )
+    .run();
+
+    flash_loan_and_repay(&mut test_env, "btc", "usd", dec!(1000), dec!("0.5")).run();
+
+    // The fee of 0.05% goes to the liquidity providers of the current step
+    pool_usd_btc.update();
+    let mut pool_states = HashMap::new();
+    pool_states.insert(
+        50266,
+        StepState::from(
+            dec!(10000),
+            Decimal::ZERO,
+            dec!(20000),
+            dec!("0.00005"),
+            Decimal::ZERO,
+            dec!("0.5"),
+            Decimal::ZERO,
+        ),
+    );
+    pool_usd_btc.assert_state_is(
+        dec!("1.000105411144423293"),
+        50266,
+        dec!(100),
+        pool_states,
+        Decimal::ZERO,
+        Decimal::ZERO,
+    );
+
+    assert_eq!(test_env.amount_owned_by_current("usd"), dec!("9989999.5"));
+}
+
+#[test]
+fn test_flash_loan_not_repaid_fail() {
+    let mut test_env = instantiate();
+    create_pool(&mut test_env, "btc", dec!(20000), dec!(100), dec!(100000));
+    add_liquidity(
+        &mut test_env,
+        dec!(10000),
+        "btc",
+        Decimal::ZERO,
+        dec!(20000),
+        None,
+    )
+    .run();
+
+    flash_loan_and_repay(&mut test_env, "btc", "usd", dec!(1000), Decimal::ZERO)
+        .should_panic(Error::AssertFailed(
+            "You did not provide enough tokens to repay the flash loan".to_string(),
+        ))
+        .run();
+}
+
+#[test]
+fn test_swap_during_flash_loan_fail() {
+    let mut test_env = instantiate();
+    create_pool(&mut test_env, "btc", dec!(20000), dec!(100), dec!(100000));
+    add_liquidity(
+        &mut test_env,
+        dec!(10000),
+        "btc",
+        Decimal::ZERO,
+        dec!(20000),
+        None,
+    )
+    .run();
+
+    flash_loan_and_swap(
+        &mut test_env,
+        "btc",
+        "usd",
+        dec!(1000),
+        "btc",
+        dec!("0.01"),
+        "usd",
+    )
+    .should_panic(Error::AssertFailed(
+        "The pool is locked until its flash loan is repaid".to_string(),
+    ))
+    .run();
+}
+
+#[test]
+fn test_change_flash_loan_fee() {
+    let mut test_env = instantiate();
+    create_pool(&mut test_env, "btc", dec!(20000), dec!(100), dec!(100000));
+
+    test_env
+        .call_method(RouterMethods::ChangeFlashLoanFee(
+            "btc".to_string(),
+            dec!("0.001"),
+        ))
+        .run();
+}
+
+#[test]
+fn test_change_flash_loan_fee_not_smaller_than_one_fail() {
+    let mut test_env = instantiate();
+    create_pool(&mut test_env, "btc", dec!(20000), dec!(100), dec!(100000));
+
+    test_env
+        .call_method(RouterMethods::ChangeFlashLoanFee(
+            "btc".to_string(),
+            dec!(1),
+        ))
+        .should_panic(Error::AssertFailed(
+            "The flash loan fee should be such that 0<=FLF<1".to_string(),
+        ))
+        .run();
+}
+
+#[test]
+fn test_resize_oracle() {
+    let mut test_env = instantiate();
+    create_pool(&mut test_env, "btc", dec!(20000), dec!(100), dec!(100000));
+
+    test_env
+        .call_method(RouterMethods::ResizeOracle("btc".to_string(), 1440))
+        .run();
+
+    assert_eq!(get_oracle_parameters(&test_env, "btc"), (60, 100, 1440));
+}
+
+#[test]
+fn test_resize_oracle_keeps_observations() {
+    let mut test_env = instantiate();
+    create_pool(&mut test_env, "btc", dec!(20000), dec!(100), dec!(100000));
+
+    test_env.set_current_time(Instant::new(600));
+    test_env
+        .call_method(RouterMethods::NewObservation("btc".to_string()))
+        .run();
+    test_env.set_current_time(Instant::new(1200));
+    test_env
+        .call_method(RouterMethods::NewObservation("btc".to_string()))
+        .run();
+    let twap = get_twap_since(&test_env, "btc", 0);
+
+    // Growing the oracle keeps every observation
+    test_env
+        .call_method(RouterMethods::ResizeOracle("btc".to_string(), 1440))
+        .run();
+    assert_eq!(get_oracle_parameters(&test_env, "btc"), (60, 100, 1440));
+    assert_eq!(get_twap_since(&test_env, "btc", 0), twap);
+
+    // Shrinking the oracle keeps the most recent observation
+    test_env
+        .call_method(RouterMethods::ResizeOracle("btc".to_string(), 1))
+        .run();
+    assert_eq!(get_oracle_parameters(&test_env, "btc"), (60, 100, 1));
+    assert_eq!(get_twap_since(&test_env, "btc", 0), twap);
+}
+
+#[test]
+fn test_resize_oracle_zero_capacity_fail() {
+    let mut test_env = instantiate();
+    create_pool(&mut test_env, "btc", dec!(20000), dec!(100), dec!(100000));
+
+    test_env
+        .call_method(RouterMethods::ResizeOracle("btc".to_string(), 0))
+        .should_panic(Error::AssertFailed(
+            "The capacity of the array should be positive".to_string(),
+        ))
+        .run();
+}
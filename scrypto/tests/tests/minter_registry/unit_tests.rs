@@ -0,0 +1,122 @@
+use scrypto::prelude::{dec, Decimal};
+use sqrt::error::Error;
+use stoichiometric_tests::minter_registry::sqrt_implem::MinterRegistryMethods;
+use stoichiometric_tests::minter_registry::utils::instantiate;
+use stoichiometric_tests::utils::{MINTER_BADGE_NAME, STABLECOIN_NAME};
+
+#[test]
+fn test_instantiate() {
+    let test_env = instantiate();
+    assert_eq!(
+        test_env.amount_owned_by_current(MINTER_BADGE_NAME),
+        Decimal::ONE
+    );
+}
+
+#[test]
+fn test_mint() {
+    let mut test_env = instantiate();
+
+    test_env
+        .call_method(MinterRegistryMethods::Mint("#0#".to_string(), dec!(600)))
+        .run();
+
+    assert_eq!(test_env.amount_owned_by_current(STABLECOIN_NAME), dec!(600));
+}
+
+#[test]
+fn test_mint_above_cap_fails() {
+    let mut test_env = instantiate();
+
+    test_env
+        .call_method(MinterRegistryMethods::Mint("#0#".to_string(), dec!(600)))
+        .run();
+    test_env
+        .call_method(MinterRegistryMethods::Mint("#0#".to_string(), dec!(500)))
+        .should_panic(Error::AssertFailed(
+            "Cannot mint more than 400 stablecoins".to_string(),
+        ))
+        .run();
+}
+
+#[test]
+fn test_burn_frees_cap() {
+    let mut test_env = instantiate();
+
+    test_env
+        .call_method(MinterRegistryMethods::Mint("#0#".to_string(), dec!(1000)))
+        .run();
+    test_env
+        .call_method(MinterRegistryMethods::Burn("#0#".to_string(), dec!(300)))
+        .run();
+    test_env
+        .call_method(MinterRegistryMethods::Mint("#0#".to_string(), dec!(300)))
+        .run();
+    test_env
+        .call_method(MinterRegistryMethods::GetMinterUsage("#0#".to_string()))
+        .run();
+
+    assert_eq!(
+        test_env.amount_owned_by_current(STABLECOIN_NAME),
+        dec!(1000)
+    );
+}
+
+#[test]
+fn test_lowered_cap_prevents_minting() {
+    let mut test_env = instantiate();
+
+    test_env
+        .call_method(MinterRegistryMethods::Mint("#0#".to_string(), dec!(600)))
+        .run();
+    test_env
+        .call_method(MinterRegistryMethods::ChangeMinterCap(
+            "#0#".to_string(),
+            dec!(500),
+        ))
+        .run();
+    test_env
+        .call_method(MinterRegistryMethods::Mint("#0#".to_string(), dec!(1)))
+        .should_panic(Error::AssertFailed(
+            "Cannot mint more than 0 stablecoins".to_string(),
+        ))
+        .run();
+}
+
+#[test]
+fn test_revoked_minter_cannot_mint() {
+    let mut test_env = instantiate();
+
+    test_env
+        .call_method(MinterRegistryMethods::Mint("#0#".to_string(), dec!(600)))
+        .run();
+    test_env
+        .call_method(MinterRegistryMethods::RevokeMinter("#0#".to_string()))
+        .run();
+    test_env
+        .call_method(MinterRegistryMethods::Mint("#0#".to_string(), dec!(1)))
+        .should_panic(Error::AssertFailed(
+            "This minter is not allowed to mint stablecoins".to_string(),
+        ))
+        .run();
+
+    // A revoked minter can still burn the stablecoins it minted
+    test_env
+        .call_method(MinterRegistryMethods::Burn("#0#".to_string(), dec!(600)))
+        .run();
+}
+
+#[test]
+fn test_burn_more_than_minted_fails() {
+    let mut test_env = instantiate();
+
+    test_env
+        .call_method(MinterRegistryMethods::Mint("#0#".to_string(), dec!(600)))
+        .run();
+    test_env
+        .call_method(MinterRegistryMethods::Burn("#0#".to_string(), dec!(601)))
+        .should_panic(Error::AssertFailed(
+            "Cannot burn more than 600 stablecoins".to_string(),
+        ))
+        .run();
+}
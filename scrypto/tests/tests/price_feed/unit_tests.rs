@@ -0,0 +1,195 @@
+use scrypto::prelude::{dec, Decimal, Instant};
+use sqrt::error::Error;
+use stoichiometric_tests::price_feed::sqrt_implem::PriceFeedMethods;
+use stoichiometric_tests::price_feed::utils::{instantiate, instantiate_issuer, report_price};
+use stoichiometric_tests::stablecoin::sqrt_implem::IssuerMethods;
+use stoichiometric_tests::utils::{REPORTER_BADGE_NAME, STABLECOIN_NAME};
+
+#[test]
+fn test_instantiate() {
+    let test_env = instantiate();
+    assert_eq!(
+        test_env.amount_owned_by_current(REPORTER_BADGE_NAME),
+        Decimal::ONE
+    );
+}
+
+#[test]
+fn test_report_price() {
+    let mut test_env = instantiate();
+
+    test_env
+        .call_method(PriceFeedMethods::ReportPrice(
+            "#0#".to_string(),
+            "btc".to_string(),
+            dec!(20000),
+            0,
+        ))
+        .run();
+}
+
+#[test]
+fn test_report_price_out_of_bounds_fails() {
+    let mut test_env = instantiate();
+
+    test_env
+        .call_method(PriceFeedMethods::ReportPrice(
+            "#0#".to_string(),
+            "btc".to_string(),
+            dec!(100001),
+            0,
+        ))
+        .should_panic(Error::AssertFailed(
+            "The reported price should be in the range [1000, 100000]".to_string(),
+        ))
+        .run();
+}
+
+#[test]
+fn test_report_stale_price_fails() {
+    let mut test_env = instantiate();
+
+    let new_time = Instant::new(0).add_days(1).unwrap();
+    test_env.set_current_time(new_time);
+
+    // The maximum report age is one hour
+    test_env
+        .call_method(PriceFeedMethods::ReportPrice(
+            "#0#".to_string(),
+            "btc".to_string(),
+            dec!(20000),
+            86400 - 3601,
+        ))
+        .should_panic(Error::AssertFailed("The report is too old".to_string()))
+        .run();
+}
+
+#[test]
+fn test_report_price_older_than_last_fails() {
+    let mut test_env = instantiate();
+
+    let new_time = Instant::new(0).add_days(1).unwrap();
+    test_env.set_current_time(new_time);
+
+    report_price(&mut test_env, "btc", dec!(20000), 86400);
+    test_env
+        .call_method(PriceFeedMethods::ReportPrice(
+            "#0#".to_string(),
+            "btc".to_string(),
+            dec!(20000),
+            86400 - 60,
+        ))
+        .should_panic(Error::AssertFailed(
+            "A more recent price has already been reported".to_string(),
+        ))
+        .run();
+}
+
+#[test]
+fn test_report_price_deviation_too_big_fails() {
+    let mut test_env = instantiate();
+
+    report_price(&mut test_env, "btc", dec!(20000), 0);
+
+    let new_time = Instant::new(0).add_days(1).unwrap();
+    test_env.set_current_time(new_time);
+
+    // The maximum deviation between two reports is 10%
+    test_env
+        .call_method(PriceFeedMethods::ReportPrice(
+            "#0#".to_string(),
+            "btc".to_string(),
+            dec!(23000),
+            86400,
+        ))
+        .should_panic(Error::AssertFailed(
+            "The reported price deviates by 0.15 from the last one".to_string(),
+        ))
+        .run();
+}
+
+#[test]
+fn test_revoked_reporter_fails() {
+    let mut test_env = instantiate();
+
+    test_env
+        .call_method(PriceFeedMethods::RevokeReporter("#0#".to_string()))
+        .run();
+    test_env
+        .call_method(PriceFeedMethods::ReportPrice(
+            "#0#".to_string(),
+            "btc".to_string(),
+            dec!(20000),
+            0,
+        ))
+        .should_panic(Error::AssertFailed(
+            "This reporter is not allowed to report prices".to_string(),
+        ))
+        .run();
+}
+
+#[test]
+fn test_take_loan_with_price_feed() {
+    let mut test_env = instantiate();
+    report_price(&mut test_env, "btc", dec!(20000), 0);
+
+    instantiate_issuer(&mut test_env);
+    test_env
+        .call_method(IssuerMethods::NewLender(
+            "btc".to_string(),
+            dec!("0.7"),
+            dec!("0.0001"),
+            dec!("1.3"),
+            dec!("0.1"),
+            dec!("0.1"),
+            Decimal::ONE,
+            "price_feed_comp".to_string(),
+        ))
+        .run();
+
+    // The default btc lender has an LTV of 0.7 so for a bitcoin at 20000$, we should be able to loan 42k$ for 3 bitcoins
+    test_env
+        .call_method(IssuerMethods::TakeLoan(
+            "btc".to_string(),
+            dec!(3),
+            dec!(42000),
+        ))
+        .run();
+
+    assert_eq!(
+        test_env.amount_owned_by_current(STABLECOIN_NAME),
+        dec!(42000)
+    );
+}
+
+#[test]
+fn test_take_loan_with_stale_price_feed_fails() {
+    let mut test_env = instantiate();
+    report_price(&mut test_env, "btc", dec!(20000), 0);
+
+    instantiate_issuer(&mut test_env);
+    test_env
+        .call_method(IssuerMethods::NewLender(
+            "btc".to_string(),
+            dec!("0.7"),
+            dec!("0.0001"),
+            dec!("1.3"),
+            dec!("0.1"),
+            dec!("0.1"),
+            Decimal::ONE,
+            "price_feed_comp".to_string(),
+        ))
+        .run();
+
+    let new_time = Instant::new(0).add_days(1).unwrap();
+    test_env.set_current_time(new_time);
+
+    test_env
+        .call_method(IssuerMethods::TakeLoan(
+            "btc".to_string(),
+            dec!(3),
+            dec!(42000),
+        ))
+        .should_panic(Error::AssertFailed("The price feed is stale".to_string()))
+        .run();
+}
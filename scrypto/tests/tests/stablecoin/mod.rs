@@ -1 +1,2 @@
+pub mod psm_tests;
 pub mod unit_tests;
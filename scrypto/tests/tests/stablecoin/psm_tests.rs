@@ -0,0 +1,178 @@
+use scrypto::prelude::dec;
+use sqrt::error::Error;
+use stoichiometric_tests::stablecoin::sqrt_implem::PsmMethods;
+use stoichiometric_tests::stablecoin::utils::{
+    create_token_with_divisibility, get_psm_asset_state, instantiate_psm,
+};
+use stoichiometric_tests::utils::STABLECOIN_NAME;
+
+#[test]
+fn test_mint_stablecoins() {
+    let mut test_env = instantiate_psm();
+
+    test_env
+        .call_method(PsmMethods::MintStablecoins("usdc".to_string(), dec!(1000)))
+        .run();
+
+    assert_eq!(test_env.amount_owned_by_current(STABLECOIN_NAME), dec!(999));
+    assert_eq!(test_env.amount_owned_by_current("usdc"), dec!(9999000));
+}
+
+#[test]
+fn test_mint_zero_stablecoins_fails() {
+    let mut test_env = instantiate_psm();
+
+    test_env
+        .call_method(PsmMethods::MintStablecoins("usdc".to_string(), dec!(0)))
+        .should_panic(Error::AssertFailed(
+            "The amount of assets to deposit should be positive".to_string(),
+        ))
+        .run();
+}
+
+#[test]
+fn test_mint_stablecoins_above_cap_fails() {
+    let mut test_env = instantiate_psm();
+
+    test_env
+        .call_method(PsmMethods::MintStablecoins("usdc".to_string(), dec!(10001)))
+        .should_panic(Error::AssertFailed(
+            "Cannot mint more than 10000 stablecoins against this asset".to_string(),
+        ))
+        .run();
+}
+
+#[test]
+fn test_mint_stablecoins_with_unknown_asset_fails() {
+    let mut test_env = instantiate_psm();
+
+    test_env
+        .call_method(PsmMethods::MintStablecoins("btc".to_string(), dec!(1)))
+        .should_panic(Error::AssertFailed(
+            "The given asset is not accepted by the peg stability module".to_string(),
+        ))
+        .run();
+}
+
+#[test]
+fn test_redeem_stablecoins() {
+    let mut test_env = instantiate_psm();
+
+    test_env
+        .call_method(PsmMethods::MintStablecoins("usdc".to_string(), dec!(1000)))
+        .run();
+    test_env
+        .call_method(PsmMethods::RedeemStablecoins(dec!(500), "usdc".to_string()))
+        .run();
+
+    assert_eq!(test_env.amount_owned_by_current(STABLECOIN_NAME), dec!(499));
+    assert_eq!(test_env.amount_owned_by_current("usdc"), dec!(9999499));
+}
+
+#[test]
+fn test_redeem_stablecoins_rounds_down_to_asset_divisibility() {
+    let mut test_env = instantiate_psm();
+    create_token_with_divisibility(&mut test_env, "usdt", 6, dec!(10000000));
+    test_env
+        .call_method(PsmMethods::SetAssetParameters(
+            "usdt".to_string(),
+            dec!("0.001"),
+            dec!("0.002"),
+            dec!(10000),
+        ))
+        .run();
+
+    test_env
+        .call_method(PsmMethods::MintStablecoins("usdt".to_string(), dec!(1000)))
+        .run();
+    test_env
+        .call_method(PsmMethods::RedeemStablecoins(
+            dec!("100.0000005"),
+            "usdt".to_string(),
+        ))
+        .run();
+
+    assert_eq!(test_env.amount_owned_by_current(STABLECOIN_NAME), dec!(899));
+    assert_eq!(test_env.amount_owned_by_current("usdt"), dec!("9999099.8"));
+    assert_eq!(
+        get_psm_asset_state(&test_env, "usdt"),
+        (dec!("900.2"), dec!("0.001"), dec!("0.002"), dec!(10000))
+    );
+}
+
+#[test]
+fn test_redeem_less_than_one_unit_of_asset_fails() {
+    let mut test_env = instantiate_psm();
+    create_token_with_divisibility(&mut test_env, "usdt", 6, dec!(10000000));
+    test_env
+        .call_method(PsmMethods::SetAssetParameters(
+            "usdt".to_string(),
+            dec!("0.001"),
+            dec!("0.002"),
+            dec!(10000),
+        ))
+        .run();
+
+    test_env
+        .call_method(PsmMethods::MintStablecoins("usdt".to_string(), dec!(1000)))
+        .run();
+    test_env
+        .call_method(PsmMethods::RedeemStablecoins(
+            dec!("0.0000005"),
+            "usdt".to_string(),
+        ))
+        .should_panic(Error::AssertFailed(
+            "Please provide enough stablecoins to redeem at least one unit of the asset"
+                .to_string(),
+        ))
+        .run();
+}
+
+#[test]
+fn test_claim_fees() {
+    let mut test_env = instantiate_psm();
+
+    test_env
+        .call_method(PsmMethods::MintStablecoins("usdc".to_string(), dec!(1000)))
+        .run();
+    test_env.call_method(PsmMethods::ClaimFees).run();
+
+    assert_eq!(
+        test_env.amount_owned_by_current(STABLECOIN_NAME),
+        dec!(1000)
+    );
+}
+
+#[test]
+fn test_set_asset_parameters_fee_not_smaller_than_one_fails() {
+    let mut test_env = instantiate_psm();
+
+    test_env
+        .call_method(PsmMethods::SetAssetParameters(
+            "usdc".to_string(),
+            dec!(1),
+            dec!("0.002"),
+            dec!(10000),
+        ))
+        .should_panic(Error::AssertFailed(
+            "The fee in should be such that 0<=FI<1".to_string(),
+        ))
+        .run();
+}
+
+#[test]
+fn test_get_asset_state() {
+    let mut test_env = instantiate_psm();
+
+    test_env
+        .call_method(PsmMethods::MintStablecoins("usdc".to_string(), dec!(1000)))
+        .run();
+    test_env
+        .call_method(PsmMethods::GetAssetState("usdc".to_string()))
+        .run();
+
+    assert_eq!(
+        get_psm_asset_state(&test_env, "usdc"),
+        (dec!(1000), dec!("0.001"), dec!("0.002"), dec!(10000))
+    );
+}
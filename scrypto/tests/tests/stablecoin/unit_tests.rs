@@ -1,13 +1,19 @@
 use scrypto::prelude::{dec, Decimal, Instant};
 use sqrt::error::Error;
+use sqrt::test_environment::TestEnvironment;
 use std::collections::HashMap;
-use stoichiometric_tests::dumb_oracle::utils::set_oracle_price;
+use stoichiometric_tests::dumb_oracle::utils::{new_oracle, set_oracle_price};
 use stoichiometric_tests::stablecoin::issuer_state::LenderState;
-use stoichiometric_tests::stablecoin::sqrt_implem::IssuerMethods;
+use stoichiometric_tests::stablecoin::sqrt_implem::{IssuerMethods, PsmMethods};
 use stoichiometric_tests::stablecoin::utils::{
-    assert_current_has_loan, assert_current_has_no_loan_id, instantiate, new_default_lender,
+    assert_current_has_loan, assert_current_has_no_loan_id, flash_loan_and_repay,
+    flash_loan_and_settle_system_debt, get_protocol_state, instantiate, instantiate_with_psm,
+    new_default_lender,
+};
+use stoichiometric_tests::utils::{
+    ADMIN_BADGE_NAME, RESERVE_CLAIM_NAME, SAVINGS_SHARE_NAME, STABILITY_DEPOSIT_NAME,
+    STABLECOIN_NAME,
 };
-use stoichiometric_tests::utils::{ADMIN_BADGE_NAME, STABLECOIN_NAME};
 
 #[test]
 fn test_instantiate() {
@@ -80,6 +86,8 @@ fn test_new_lender_already_exists_fails() {
             Decimal::ONE,
             Decimal::ONE,
             Decimal::ONE,
+            dec!("0.1"),
+            Decimal::ONE,
             "issuer_comp".to_string(),
         ))
         .should_panic(Error::AssertFailed(
@@ -100,6 +108,8 @@ fn test_new_lender_ltv_negative_fails() {
             Decimal::ONE,
             Decimal::ONE,
             Decimal::ONE,
+            dec!("0.1"),
+            Decimal::ONE,
             "issuer_comp".to_string(),
         ))
         .should_panic(Error::AssertFailed(
@@ -120,6 +130,8 @@ fn test_new_lender_ltv_bigger_than_one_fails() {
             Decimal::ONE,
             Decimal::ONE,
             Decimal::ONE,
+            dec!("0.1"),
+            Decimal::ONE,
             "issuer_comp".to_string(),
         ))
         .should_panic(Error::AssertFailed(
@@ -140,6 +152,8 @@ fn test_new_lender_dir_negative_fails() {
             dec!("-1"),
             Decimal::ONE,
             Decimal::ONE,
+            dec!("0.1"),
+            Decimal::ONE,
             "issuer_comp".to_string(),
         ))
         .should_panic(Error::AssertFailed(
@@ -160,6 +174,8 @@ fn test_new_lender_dir_bigger_than_one_fails() {
             dec!("2"),
             Decimal::ONE,
             Decimal::ONE,
+            dec!("0.1"),
+            Decimal::ONE,
             "issuer_comp".to_string(),
         ))
         .should_panic(Error::AssertFailed(
@@ -180,6 +196,8 @@ fn test_new_lender_liquidation_threshold_smaller_than_one_fails() {
             dec!("0.7"),
             dec!("0.9"),
             Decimal::ONE,
+            dec!("0.1"),
+            Decimal::ONE,
             "issuer_comp".to_string(),
         ))
         .should_panic(Error::AssertFailed(
@@ -200,6 +218,8 @@ fn test_new_lender_liquidation_threshold_ltv_product_greater_than_one_fails() {
             dec!("0.7"),
             dec!("2"),
             Decimal::ONE,
+            dec!("0.1"),
+            Decimal::ONE,
             "issuer_comp".to_string(),
         ))
         .should_panic(Error::AssertFailed(
@@ -209,7 +229,7 @@ fn test_new_lender_liquidation_threshold_ltv_product_greater_than_one_fails() {
 }
 
 #[test]
-fn test_new_lender_liquidation_incentive_non_positive_fails() {
+fn test_new_lender_liquidator_bonus_non_positive_fails() {
     let (mut test_env, _) = instantiate();
 
     new_default_lender(&mut test_env, "btc");
@@ -220,12 +240,90 @@ fn test_new_lender_liquidation_incentive_non_positive_fails() {
             dec!("0.7"),
             dec!("1.3"),
             dec!("-0.5"),
+            dec!("0.1"),
+            Decimal::ONE,
+            "issuer_comp".to_string(),
+        ))
+        .should_panic(Error::AssertFailed(
+            "The liquidator bonus should be positive".to_string(),
+        ))
+        .run();
+}
+
+#[test]
+fn test_new_lender_protocol_liquidation_share_bigger_than_one_fails() {
+    let (mut test_env, _) = instantiate();
+
+    new_default_lender(&mut test_env, "btc");
+    test_env
+        .call_method(IssuerMethods::NewLender(
+            "radix".to_string(),
+            dec!("0.7"),
+            dec!("0.0001"),
+            dec!("1.3"),
+            dec!("0.1"),
+            dec!(2),
+            Decimal::ONE,
+            "issuer_comp".to_string(),
+        ))
+        .should_panic(Error::AssertFailed(
+            "The protocol liquidation share should be such that 0<=PLS<=1".to_string(),
+        ))
+        .run();
+}
+
+#[test]
+fn test_new_lender_close_factor_zero_fails() {
+    let (mut test_env, _) = instantiate();
+
+    new_default_lender(&mut test_env, "btc");
+    test_env
+        .call_method(IssuerMethods::NewLender(
+            "radix".to_string(),
+            dec!("0.7"),
+            dec!("0.0001"),
+            dec!("1.3"),
+            dec!("0.1"),
+            dec!("0.1"),
+            Decimal::ZERO,
             "issuer_comp".to_string(),
         ))
         .should_panic(Error::AssertFailed(
-            "The liquidation incentive should be positive".to_string(),
+            "The close factor should be such that 0<CF<=1".to_string(),
+        ))
+        .run();
+}
+
+#[test]
+fn test_new_lender_state_has_liquidation_parameters() {
+    let (mut test_env, mut issuer_state) = instantiate();
+
+    let oracle = new_oracle(&mut test_env, "btc");
+    test_env
+        .call_method(IssuerMethods::NewLender(
+            "btc".to_string(),
+            dec!("0.7"),
+            dec!("0.0001"),
+            dec!("1.3"),
+            dec!("0.1"),
+            dec!("0.2"),
+            dec!("0.5"),
+            oracle,
         ))
         .run();
+
+    issuer_state.update();
+    let mut lenders = HashMap::new();
+    let btc_lender = LenderState::from(
+        Decimal::ZERO,
+        dec!("0.7"),
+        dec!("0.0001"),
+        dec!("1.3"),
+        dec!("0.1"),
+    )
+    .with_liquidation_parameters(dec!("0.2"), dec!("0.5"));
+    lenders.insert(test_env.get_resource("btc").clone(), btc_lender);
+    issuer_state.assert_state_is(&HashMap::new(), &lenders, 0, 0);
 }
 
 #[test]
@@ -269,8 +367,8 @@ fn test_take_loan() {
         "btc",
         dec!(3),
         dec!(42000),
+        dec!(42000),
         0,
-        dec!("0.0001"),
     );
 }
 
@@ -326,8 +424,8 @@ fn test_repay_single_loan() {
         ))
         .run();
 
-    // Repay loan with interests
-    let interest_paid = dec!(93);
+    // Repay loan with interests compounded over 31 days: 30000 * (exp(31 * 0.0001) - 1)
+    let interest_paid = dec!("93.14429907051168");
     test_env
         .call_method(IssuerMethods::RepayLoans(
             dec!(30000) + interest_paid.clone(),
@@ -361,8 +459,8 @@ fn test_repay_single_loan() {
         "btc",
         dec!(1),
         dec!(10000),
+        dec!("9969.048000386789547873"),
         2678400,
-        dec!("0.0001"),
     );
 }
 
@@ -398,11 +496,11 @@ fn test_repay_single_loan_not_enough_fails() {
     // Repay loan with not enough interests
     test_env
         .call_method(IssuerMethods::RepayLoans(
-            dec!(30000) + dec!(92),
+            dec!(30000) + dec!(93),
             vec!["#0#".to_string()],
         ))
         .should_panic(Error::AssertFailed(
-            "You need to provide 30093 stablecoins to repay your loan".to_string(),
+            "You need to provide 30093.14429907051168 stablecoins to repay your loan".to_string(),
         ))
         .run();
 }
@@ -446,7 +544,7 @@ fn test_repay_multiple_loans() {
     // Repay loan with interests
     test_env
         .call_method(IssuerMethods::RepayLoans(
-            dec!(60186),
+            dec!(60187),
             vec!["#0#".to_string(), "#1#".to_string()],
         ))
         .run();
@@ -464,7 +562,10 @@ fn test_repay_multiple_loans() {
     );
     lenders.insert(test_env.get_resource("btc").clone(), btc_lender);
     let mut reserves = HashMap::new();
-    reserves.insert(test_env.get_resource(STABLECOIN_NAME).clone(), dec!(186));
+    reserves.insert(
+        test_env.get_resource(STABLECOIN_NAME).clone(),
+        dec!("186.28859814102336"),
+    );
     issuer_state.assert_state_is(&reserves, &lenders, 3, 0);
 
     assert_current_has_no_loan_id(&test_env, "#0#");
@@ -475,41 +576,80 @@ fn test_repay_multiple_loans() {
         "btc",
         dec!(1),
         dec!(10000),
+        dec!("9969.048000386789547873"),
         2678400,
-        dec!("0.0001"),
     );
 }
 
 #[test]
-fn test_add_collateral() {
-    let (mut test_env, mut issuer_state) = instantiate();
+fn test_interest_rate_change_applies_to_existing_loans() {
+    let (mut test_env, _) = instantiate();
 
     new_default_lender(&mut test_env, "btc");
     set_oracle_price(&mut test_env, "btc", dec!(20000));
 
-    // The default btc lender has an LTV of 0.7 so for a bitcoin at 20000$, we should be able to loan 42k$ for 3 bitcoins
     test_env
         .call_method(IssuerMethods::TakeLoan(
             "btc".to_string(),
             dec!(3),
-            dec!(42000),
+            dec!(30000),
         ))
         .run();
 
-    // We increase collateral
+    // Double the interest rate after 31 days
+    let new_time = Instant::new(0).add_days(31).unwrap();
+    test_env.set_current_time(new_time);
     test_env
-        .call_method(IssuerMethods::AddCollateral(
+        .call_method(IssuerMethods::ChangeLenderParameters(
             "btc".to_string(),
-            dec!(4),
-            "#0#".to_string(),
+            dec!("0.7"),
+            dec!("0.0002"),
+            dec!("1.3"),
+            dec!("0.1"),
+            dec!("0.1"),
+            Decimal::ONE,
         ))
         .run();
 
-    // Check that the issuer has the right state
+    // The debt is compounded at 0.0001 for 31 days and then at 0.0002 for 31 days
+    let new_time = Instant::new(0).add_days(62).unwrap();
+    test_env.set_current_time(new_time);
+    test_env
+        .call_method(IssuerMethods::RepayLoans(
+            dec!(30000),
+            vec!["#0#".to_string()],
+        ))
+        .should_panic(Error::AssertFailed(
+            "You need to provide 30280.30138115306919 stablecoins to repay your loan".to_string(),
+        ))
+        .run();
+}
+
+#[test]
+fn test_repay_partial() {
+    let (mut test_env, mut issuer_state) = instantiate();
+
+    new_default_lender(&mut test_env, "btc");
+    set_oracle_price(&mut test_env, "btc", dec!(20000));
+
+    test_env
+        .call_method(IssuerMethods::TakeLoan(
+            "btc".to_string(),
+            dec!(3),
+            dec!(30000),
+        ))
+        .run();
+
+    // Without accrued interests, the whole repayment goes to the principal
+    test_env
+        .call_method(IssuerMethods::RepayPartial("#0#".to_string(), dec!(10000)))
+        .run();
+
+    // The collateral stays in the lender
     issuer_state.update();
     let mut lenders = HashMap::new();
     let btc_lender = LenderState::from(
-        dec!(7),
+        dec!(3),
         dec!("0.7"),
         dec!("0.0001"),
         dec!("1.3"),
@@ -518,81 +658,97 @@ fn test_add_collateral() {
     lenders.insert(test_env.get_resource("btc").clone(), btc_lender);
     issuer_state.assert_state_is(&HashMap::new(), &lenders, 1, 0);
 
-    // Check that the account has the right amount of stablecoins and the right NFR
     assert_eq!(
         test_env.amount_owned_by_current(STABLECOIN_NAME),
-        dec!(42000)
+        dec!(20000)
     );
     assert_current_has_loan(
         &test_env,
         "#0#",
         "btc",
-        dec!(7),
-        dec!(42000),
+        dec!(3),
+        dec!(20000),
+        dec!(20000),
         0,
-        dec!("0.0001"),
     );
 }
 
 #[test]
-fn test_add_collateral_wrong_token_fails() {
-    let (mut test_env, _) = instantiate();
+fn test_repay_partial_pays_interests_first() {
+    let (mut test_env, mut issuer_state) = instantiate();
 
     new_default_lender(&mut test_env, "btc");
     set_oracle_price(&mut test_env, "btc", dec!(20000));
 
-    // The default btc lender has an LTV of 0.7 so for a bitcoin at 20000$, we should be able to loan 42k$ for 3 bitcoins
     test_env
         .call_method(IssuerMethods::TakeLoan(
             "btc".to_string(),
             dec!(3),
-            dec!(42000),
+            dec!(30000),
         ))
         .run();
 
-    // We try increasing collateral with the wrong token
-    test_env.create_fixed_supply_token("eth", dec!(10));
+    // Change time
+    let new_time = Instant::new(0).add_days(31).unwrap();
+    test_env.set_current_time(new_time);
+
+    // 93.14429907051168 of interests are paid and the rest goes to the principal
     test_env
-        .call_method(IssuerMethods::AddCollateral(
-            "eth".to_string(),
-            dec!(4),
-            "#0#".to_string(),
-        ))
-        .should_panic(Error::AssertFailed(
-            "Please provide the right tokens to add as collateral".to_string(),
-        ))
+        .call_method(IssuerMethods::RepayPartial("#0#".to_string(), dec!(100)))
         .run();
+
+    issuer_state.update();
+    let mut lenders = HashMap::new();
+    let btc_lender = LenderState::from(
+        dec!(3),
+        dec!("0.7"),
+        dec!("0.0001"),
+        dec!("1.3"),
+        dec!("0.1"),
+    );
+    lenders.insert(test_env.get_resource("btc").clone(), btc_lender);
+    let mut reserves = HashMap::new();
+    reserves.insert(
+        test_env.get_resource(STABLECOIN_NAME).clone(),
+        dec!("93.14429907051168"),
+    );
+    issuer_state.assert_state_is(&reserves, &lenders, 1, 0);
+
+    assert_current_has_loan(
+        &test_env,
+        "#0#",
+        "btc",
+        dec!(3),
+        dec!("29993.14429907051168"),
+        dec!("29900.309519996132104522"),
+        0,
+    );
 }
 
 #[test]
-fn test_remove_collateral() {
+fn test_borrow_more() {
     let (mut test_env, mut issuer_state) = instantiate();
 
     new_default_lender(&mut test_env, "btc");
     set_oracle_price(&mut test_env, "btc", dec!(20000));
 
-    // The default btc lender has an LTV of 0.7 so for a bitcoin at 20000$, we should be able to loan 42k$ for 3 bitcoins
     test_env
         .call_method(IssuerMethods::TakeLoan(
             "btc".to_string(),
             dec!(3),
-            dec!(28000),
+            dec!(30000),
         ))
         .run();
 
-    // We should be able to remove 1.18 *2000btc of collateral
+    // With an LTV of 0.7, 3 bitcoins at 20000$ allow to borrow up to 42k$
     test_env
-        .call_method(IssuerMethods::RemoveCollateral(
-            dec!("1.18"),
-            "#0#".to_string(),
-        ))
+        .call_method(IssuerMethods::BorrowMore("#0#".to_string(), dec!(12000)))
         .run();
 
-    // Check that the issuer has the right state
     issuer_state.update();
     let mut lenders = HashMap::new();
     let btc_lender = LenderState::from(
-        dec!("1.82"),
+        dec!(3),
         dec!("0.7"),
         dec!("0.0001"),
         dec!("1.3"),
@@ -601,129 +757,311 @@ fn test_remove_collateral() {
     lenders.insert(test_env.get_resource("btc").clone(), btc_lender);
     issuer_state.assert_state_is(&HashMap::new(), &lenders, 1, 0);
 
+    assert_eq!(
+        test_env.amount_owned_by_current(STABLECOIN_NAME),
+        dec!(42000)
+    );
     assert_current_has_loan(
         &test_env,
         "#0#",
         "btc",
-        dec!("1.82"),
-        dec!(28000),
+        dec!(3),
+        dec!(42000),
+        dec!(42000),
         0,
-        dec!("0.0001"),
     );
 }
 
 #[test]
-fn test_remove_collateral_too_much_fails() {
+fn test_borrow_more_above_ltv_fails() {
     let (mut test_env, _) = instantiate();
 
     new_default_lender(&mut test_env, "btc");
     set_oracle_price(&mut test_env, "btc", dec!(20000));
 
-    // The default btc lender has an LTV of 0.7 so for a bitcoin at 20000$, we should be able to loan 42k$ for 3 bitcoins
     test_env
         .call_method(IssuerMethods::TakeLoan(
             "btc".to_string(),
             dec!(3),
-            dec!(28000),
+            dec!(30000),
         ))
         .run();
 
-    // We can remove a maximum of 1.18 btc of collateral
     test_env
-        .call_method(IssuerMethods::RemoveCollateral(
-            dec!("1.180000000000000001"),
-            "#0#".to_string(),
-        ))
+        .call_method(IssuerMethods::BorrowMore("#0#".to_string(), dec!(12001)))
         .should_panic(Error::AssertFailed(
-            "Cannot remove 1.180000000000000001 because it would make the loan liquidatable"
-                .to_string(),
+            "Cannot borrow 12001 because the loan to value would exceed 0.7".to_string(),
         ))
         .run();
 }
 
 #[test]
-fn test_remove_collateral_after_time_fails() {
+fn test_borrow_more_zero_fails() {
     let (mut test_env, _) = instantiate();
 
     new_default_lender(&mut test_env, "btc");
     set_oracle_price(&mut test_env, "btc", dec!(20000));
 
-    // The default btc lender has an LTV of 0.7 so for a bitcoin at 20000$, we should be able to loan 42k$ for 3 bitcoins
     test_env
         .call_method(IssuerMethods::TakeLoan(
             "btc".to_string(),
             dec!(3),
-            dec!(28000),
+            dec!(30000),
         ))
         .run();
 
-    // Change time
-    let new_time = Instant::new(0).add_days(31).unwrap();
-    test_env.set_current_time(new_time);
-
-    // Now removing 1.18 btc collateral does not work because of accrued interests
     test_env
-        .call_method(IssuerMethods::RemoveCollateral(dec!(1), "#0#".to_string()))
+        .call_method(IssuerMethods::BorrowMore("#0#".to_string(), dec!(0)))
         .should_panic(Error::AssertFailed(
-            "Cannot remove 1.18 because it would make the loan liquidatable".to_string(),
-        ));
+            "The amount to borrow should be positive".to_string(),
+        ))
+        .run();
 }
 
 #[test]
-fn test_partial_liquidation() {
+fn test_add_collateral() {
     let (mut test_env, mut issuer_state) = instantiate();
 
     new_default_lender(&mut test_env, "btc");
-    set_oracle_price(&mut test_env, "btc", dec!(10000));
+    set_oracle_price(&mut test_env, "btc", dec!(20000));
 
+    // The default btc lender has an LTV of 0.7 so for a bitcoin at 20000$, we should be able to loan 42k$ for 3 bitcoins
     test_env
         .call_method(IssuerMethods::TakeLoan(
             "btc".to_string(),
-            dec!(1),
-            dec!(6000),
+            dec!(3),
+            dec!(42000),
         ))
         .run();
 
-    // Liquidation threshold is at 1.3 hence the loan can be partially liquidated at 1*price/6000 = 1.3
-    // => price = 7800
-    set_oracle_price(&mut test_env, "btc", dec!(7000));
-
-    // Take loan to liquidate first one
+    // We increase collateral
     test_env
-        .call_method(IssuerMethods::TakeLoan(
+        .call_method(IssuerMethods::AddCollateral(
             "btc".to_string(),
-            dec!(10),
-            dec!(10000),
+            dec!(4),
+            "#0#".to_string(),
         ))
         .run();
 
-    test_env
-        .call_method(IssuerMethods::Liquidate(dec!(616), "#0#".to_string()))
-        .run();
-
     // Check that the issuer has the right state
     issuer_state.update();
     let mut lenders = HashMap::new();
     let btc_lender = LenderState::from(
-        dec!("10.906892382103990327"),
+        dec!(7),
         dec!("0.7"),
         dec!("0.0001"),
         dec!("1.3"),
         dec!("0.1"),
     );
     lenders.insert(test_env.get_resource("btc").clone(), btc_lender);
+    issuer_state.assert_state_is(&HashMap::new(), &lenders, 1, 0);
 
-    let reserves = HashMap::new();
-    issuer_state.assert_state_is(&reserves, &lenders, 2, 0);
-
+    // Check that the account has the right amount of stablecoins and the right NFR
+    assert_eq!(
+        test_env.amount_owned_by_current(STABLECOIN_NAME),
+        dec!(42000)
+    );
     assert_current_has_loan(
         &test_env,
         "#0#",
         "btc",
-        dec!("0.906892382103990327"),
-        dec!("5384"),
+        dec!(7),
+        dec!(42000),
+        dec!(42000),
+        0,
+    );
+}
+
+#[test]
+fn test_add_collateral_wrong_token_fails() {
+    let (mut test_env, _) = instantiate();
+
+    new_default_lender(&mut test_env, "btc");
+    set_oracle_price(&mut test_env, "btc", dec!(20000));
+
+    // The default btc lender has an LTV of 0.7 so for a bitcoin at 20000$, we should be able to loan 42k$ for 3 bitcoins
+    test_env
+        .call_method(IssuerMethods::TakeLoan(
+            "btc".to_string(),
+            dec!(3),
+            dec!(42000),
+        ))
+        .run();
+
+    // We try increasing collateral with the wrong token
+    test_env.create_fixed_supply_token("eth", dec!(10));
+    test_env
+        .call_method(IssuerMethods::AddCollateral(
+            "eth".to_string(),
+            dec!(4),
+            "#0#".to_string(),
+        ))
+        .should_panic(Error::AssertFailed(
+            "Please provide the right tokens to add as collateral".to_string(),
+        ))
+        .run();
+}
+
+#[test]
+fn test_remove_collateral() {
+    let (mut test_env, mut issuer_state) = instantiate();
+
+    new_default_lender(&mut test_env, "btc");
+    set_oracle_price(&mut test_env, "btc", dec!(20000));
+
+    // The default btc lender has an LTV of 0.7 so for a bitcoin at 20000$, we should be able to loan 42k$ for 3 bitcoins
+    test_env
+        .call_method(IssuerMethods::TakeLoan(
+            "btc".to_string(),
+            dec!(3),
+            dec!(28000),
+        ))
+        .run();
+
+    // We should be able to remove 1.18 *2000btc of collateral
+    test_env
+        .call_method(IssuerMethods::RemoveCollateral(
+            dec!("1.18"),
+            "#0#".to_string(),
+        ))
+        .run();
+
+    // Check that the issuer has the right state
+    issuer_state.update();
+    let mut lenders = HashMap::new();
+    let btc_lender = LenderState::from(
+        dec!("1.82"),
+        dec!("0.7"),
+        dec!("0.0001"),
+        dec!("1.3"),
+        dec!("0.1"),
+    );
+    lenders.insert(test_env.get_resource("btc").clone(), btc_lender);
+    issuer_state.assert_state_is(&HashMap::new(), &lenders, 1, 0);
+
+    assert_current_has_loan(
+        &test_env,
+        "#0#",
+        "btc",
+        dec!("1.82"),
+        dec!(28000),
+        dec!(28000),
         0,
+    );
+}
+
+#[test]
+fn test_remove_collateral_too_much_fails() {
+    let (mut test_env, _) = instantiate();
+
+    new_default_lender(&mut test_env, "btc");
+    set_oracle_price(&mut test_env, "btc", dec!(20000));
+
+    // The default btc lender has an LTV of 0.7 so for a bitcoin at 20000$, we should be able to loan 42k$ for 3 bitcoins
+    test_env
+        .call_method(IssuerMethods::TakeLoan(
+            "btc".to_string(),
+            dec!(3),
+            dec!(28000),
+        ))
+        .run();
+
+    // We can remove a maximum of 1.18 btc of collateral
+    test_env
+        .call_method(IssuerMethods::RemoveCollateral(
+            dec!("1.180000000000000001"),
+            "#0#".to_string(),
+        ))
+        .should_panic(Error::AssertFailed(
+            "Cannot remove 1.180000000000000001 because it would make the loan liquidatable"
+                .to_string(),
+        ))
+        .run();
+}
+
+#[test]
+fn test_remove_collateral_after_time_fails() {
+    let (mut test_env, _) = instantiate();
+
+    new_default_lender(&mut test_env, "btc");
+    set_oracle_price(&mut test_env, "btc", dec!(20000));
+
+    // The default btc lender has an LTV of 0.7 so for a bitcoin at 20000$, we should be able to loan 42k$ for 3 bitcoins
+    test_env
+        .call_method(IssuerMethods::TakeLoan(
+            "btc".to_string(),
+            dec!(3),
+            dec!(28000),
+        ))
+        .run();
+
+    // Change time
+    let new_time = Instant::new(0).add_days(31).unwrap();
+    test_env.set_current_time(new_time);
+
+    // Now removing 1.18 btc collateral does not work because of accrued interests
+    test_env
+        .call_method(IssuerMethods::RemoveCollateral(dec!(1), "#0#".to_string()))
+        .should_panic(Error::AssertFailed(
+            "Cannot remove 1.18 because it would make the loan liquidatable".to_string(),
+        ));
+}
+
+#[test]
+fn test_partial_liquidation() {
+    let (mut test_env, mut issuer_state) = instantiate();
+
+    new_default_lender(&mut test_env, "btc");
+    set_oracle_price(&mut test_env, "btc", dec!(10000));
+
+    test_env
+        .call_method(IssuerMethods::TakeLoan(
+            "btc".to_string(),
+            dec!(1),
+            dec!(6000),
+        ))
+        .run();
+
+    // Liquidation threshold is at 1.3 hence the loan can be partially liquidated at 1*price/6000 = 1.3
+    // => price = 7800
+    set_oracle_price(&mut test_env, "btc", dec!(7000));
+
+    // Take loan to liquidate first one
+    test_env
+        .call_method(IssuerMethods::TakeLoan(
+            "btc".to_string(),
+            dec!(10),
+            dec!(10000),
+        ))
+        .run();
+
+    test_env
+        .call_method(IssuerMethods::Liquidate(dec!(616), "#0#".to_string()))
+        .run();
+
+    // Check that the issuer has the right state
+    issuer_state.update();
+    let mut lenders = HashMap::new();
+    let btc_lender = LenderState::from(
+        dec!("10.906892382103990327"),
+        dec!("0.7"),
         dec!("0.0001"),
+        dec!("1.3"),
+        dec!("0.1"),
+    );
+    lenders.insert(test_env.get_resource("btc").clone(), btc_lender);
+
+    let reserves = HashMap::new();
+    issuer_state.assert_state_is(&reserves, &lenders, 2, 0);
+
+    assert_current_has_loan(
+        &test_env,
+        "#0#",
+        "btc",
+        dec!("0.906892382103990327"),
+        dec!("5384"),
+        dec!("5384"),
+        0,
     );
     assert_current_has_loan(
         &test_env,
@@ -731,8 +1069,8 @@ fn test_partial_liquidation() {
         "btc",
         dec!(10),
         dec!(10000),
+        dec!(10000),
         0,
-        dec!("0.0001"),
     );
 }
 
@@ -791,8 +1129,8 @@ fn test_partial_liquidation_too_much_sent() {
         "btc",
         dec!("0.695154559476600963"),
         dec!("3368.825942078912358"),
+        dec!("3368.825942078912358"),
         0,
-        dec!("0.0001"),
     );
     assert_current_has_loan(
         &test_env,
@@ -800,8 +1138,8 @@ fn test_partial_liquidation_too_much_sent() {
         "btc",
         dec!(10),
         dec!(10000),
+        dec!(10000),
         0,
-        dec!("0.0001"),
     );
 }
 
@@ -824,7 +1162,7 @@ fn test_partial_liquidation_after_time() {
     let new_time = Instant::new(0).add_days(31).unwrap();
     test_env.set_current_time(new_time);
 
-    // Interests are at 18.6$ therefore liquidation threshold price is at 1.3*6018.6 = 7824.18
+    // Interests are at 18.63$ therefore liquidation threshold price is at 1.3*6018.63 = 7824.22
     set_oracle_price(&mut test_env, "btc", dec!(7824));
 
     // Take loan to liquidate first one
@@ -843,7 +1181,7 @@ fn test_partial_liquidation_after_time() {
     issuer_state.update();
     let mut lenders = HashMap::new();
     let btc_lender = LenderState::from(
-        dec!("10.99983387600505017"),
+        dec!("10.999833876801495885"),
         dec!("0.7"),
         dec!("0.0001"),
         dec!("1.3"),
@@ -857,10 +1195,10 @@ fn test_partial_liquidation_after_time() {
         &test_env,
         "#0#",
         "btc",
-        dec!("0.99983387600505017"),
+        dec!("0.999833876801495885"),
         dec!("5999"),
+        dec!("5999.003095199961321046"),
         0,
-        dec!("0.0001"),
     );
     assert_current_has_loan(
         &test_env,
@@ -868,8 +1206,8 @@ fn test_partial_liquidation_after_time() {
         "btc",
         dec!(10),
         dec!(10000),
+        dec!("9969.048000386789547873"),
         2678400,
-        dec!("0.0001"),
     );
 }
 
@@ -892,7 +1230,7 @@ fn test_full_liquidation_after_time() {
     let new_time = Instant::new(0).add_days(31).unwrap();
     test_env.set_current_time(new_time);
 
-    // Interests are at 18.6$ therefore, full liquidation price happens for price < 6018.6
+    // Interests are at 18.63$ therefore, full liquidation price happens for price < 6018.63
     set_oracle_price(&mut test_env, "btc", dec!(6018));
 
     // Take loan to liquidate first one
@@ -925,45 +1263,48 @@ fn test_full_liquidation_after_time() {
     );
     issuer_state.assert_state_is(&reserves, &lenders, 2, 0);
 
-    assert_current_has_loan(
-        &test_env,
-        "#0#",
-        "btc",
-        dec!("0"),
-        dec!("0"),
-        0,
-        dec!("0.0001"),
-    );
+    assert_current_has_loan(&test_env, "#0#", "btc", dec!("0"), dec!("0"), dec!("0"), 0);
     assert_current_has_loan(
         &test_env,
         "#1#",
         "btc",
         dec!(10),
         dec!(10000),
+        dec!("9969.048000386789547873"),
         2678400,
-        dec!("0.0001"),
     );
 }
 
 #[test]
-fn test_liquidate_threshold_not_hit_fails() {
+fn test_close_factor_limits_partial_liquidation() {
     let (mut test_env, _) = instantiate();
 
     new_default_lender(&mut test_env, "btc");
-    set_oracle_price(&mut test_env, "btc", dec!(15000));
+    set_oracle_price(&mut test_env, "btc", dec!(10000));
+
+    // Only 5% of the debt can be repaid by a single liquidation
+    test_env
+        .call_method(IssuerMethods::ChangeLenderParameters(
+            "btc".to_string(),
+            dec!("0.7"),
+            dec!("0.0001"),
+            dec!("1.3"),
+            dec!("0.1"),
+            dec!("0.1"),
+            dec!("0.05"),
+        ))
+        .run();
 
     test_env
         .call_method(IssuerMethods::TakeLoan(
             "btc".to_string(),
             dec!(1),
-            dec!(10000),
+            dec!(6000),
         ))
         .run();
 
-    // Liquidation threshold is at 13000
-    set_oracle_price(&mut test_env, "btc", dec!(13001));
+    set_oracle_price(&mut test_env, "btc", dec!(7000));
 
-    // Take loan to liquidate first one
     test_env
         .call_method(IssuerMethods::TakeLoan(
             "btc".to_string(),
@@ -972,27 +1313,1843 @@ fn test_liquidate_threshold_not_hit_fails() {
         ))
         .run();
     test_env
-        .call_method(IssuerMethods::Liquidate(dec!(10000), "#0#".to_string()))
-        .should_panic(Error::AssertFailed(
-            "Cannot liquidate this loan: the collateralization ratio is 1.3001 >= 1.3".to_string(),
-        ))
+        .call_method(IssuerMethods::Liquidate(dec!(616), "#0#".to_string()))
         .run();
+
+    // Only 300 SUSD were used to liquidate the loan
+    assert_current_has_loan(
+        &test_env,
+        "#0#",
+        "btc",
+        dec!("0.952380952380952381"),
+        dec!("5700"),
+        dec!("5700"),
+        0,
+    );
 }
 
 #[test]
-fn test_change_lender_parameter() {
-    let (mut test_env, mut issuer_state) = instantiate();
+fn test_partial_liquidation_leaving_dust_closes_loan() {
+    let (mut test_env, _) = instantiate();
 
     new_default_lender(&mut test_env, "btc");
+    set_oracle_price(&mut test_env, "btc", dec!(10000));
 
-    // Change the parameters
     test_env
-        .call_method(IssuerMethods::ChangeLenderParameters(
+        .call_method(IssuerMethods::SetMinimumDebt("btc".to_string(), dec!(5500)))
+        .run();
+    test_env
+        .call_method(IssuerMethods::TakeLoan(
             "btc".to_string(),
-            dec!("0.5"),
-            dec!("0.5"),
-            dec!(2),
-            dec!("0.3"),
+            dec!(1),
+            dec!(6000),
+        ))
+        .run();
+
+    set_oracle_price(&mut test_env, "btc", dec!(7000));
+
+    test_env
+        .call_method(IssuerMethods::TakeLoan(
+            "btc".to_string(),
+            dec!(10),
+            dec!(10000),
+        ))
+        .run();
+
+    // A partial liquidation of 616 SUSD would leave a debt of 5384 < 5500
+    test_env
+        .call_method(IssuerMethods::Liquidate(dec!(616), "#0#".to_string()))
+        .should_panic(Error::AssertFailed(
+            "Please provide at least 6000 SUSD to liquidate this loan".to_string(),
+        ))
+        .run();
+    test_env
+        .call_method(IssuerMethods::Liquidate(dec!(6000), "#0#".to_string()))
+        .run();
+
+    // The liquidator earns 6600 SUSD of collateral and the rest stays in the loan
+    assert_eq!(
+        test_env.amount_owned_by_current("btc"),
+        dec!(9999989) + dec!("0.942857142857142857")
+    );
+    assert_current_has_loan(
+        &test_env,
+        "#0#",
+        "btc",
+        dec!("0.057142857142857143"),
+        dec!(0),
+        dec!(0),
+        0,
+    );
+}
+
+#[test]
+fn test_liquidate_threshold_not_hit_fails() {
+    let (mut test_env, _) = instantiate();
+
+    new_default_lender(&mut test_env, "btc");
+    set_oracle_price(&mut test_env, "btc", dec!(15000));
+
+    test_env
+        .call_method(IssuerMethods::TakeLoan(
+            "btc".to_string(),
+            dec!(1),
+            dec!(10000),
+        ))
+        .run();
+
+    // Liquidation threshold is at 13000
+    set_oracle_price(&mut test_env, "btc", dec!(13001));
+
+    // Take loan to liquidate first one
+    test_env
+        .call_method(IssuerMethods::TakeLoan(
+            "btc".to_string(),
+            dec!(10),
+            dec!(10000),
+        ))
+        .run();
+    test_env
+        .call_method(IssuerMethods::Liquidate(dec!(10000), "#0#".to_string()))
+        .should_panic(Error::AssertFailed(
+            "Cannot liquidate this loan: the collateralization ratio is 1.3001 >= 1.3".to_string(),
+        ))
+        .run();
+}
+
+fn enable_auction_liquidation(test_env: &mut TestEnvironment) {
+    test_env
+        .call_method(IssuerMethods::ChangeLenderLiquidationMode(
+            "btc".to_string(),
+            true,
+            dec!("1.2"),
+            3600,
+            dec!("0.01"),
+        ))
+        .run();
+}
+
+fn take_loan_and_start_auction(test_env: &mut TestEnvironment) {
+    new_default_lender(test_env, "btc");
+    enable_auction_liquidation(test_env);
+    set_oracle_price(test_env, "btc", dec!(10000));
+
+    test_env
+        .call_method(IssuerMethods::TakeLoan(
+            "btc".to_string(),
+            dec!(1),
+            dec!(6000),
+        ))
+        .run();
+
+    set_oracle_price(test_env, "btc", dec!(7000));
+
+    test_env
+        .call_method(IssuerMethods::StartAuction("#0#".to_string()))
+        .run();
+}
+
+#[test]
+fn test_liquidate_in_auction_mode_fails() {
+    let (mut test_env, _) = instantiate();
+
+    new_default_lender(&mut test_env, "btc");
+    enable_auction_liquidation(&mut test_env);
+    set_oracle_price(&mut test_env, "btc", dec!(10000));
+
+    test_env
+        .call_method(IssuerMethods::TakeLoan(
+            "btc".to_string(),
+            dec!(1),
+            dec!(6000),
+        ))
+        .run();
+
+    set_oracle_price(&mut test_env, "btc", dec!(7000));
+
+    test_env
+        .call_method(IssuerMethods::Liquidate(dec!(6000), "#0#".to_string()))
+        .should_panic(Error::AssertFailed(
+            "This lender liquidates loans through auctions".to_string(),
+        ))
+        .run();
+}
+
+#[test]
+fn test_start_auction() {
+    let (mut test_env, _) = instantiate();
+
+    take_loan_and_start_auction(&mut test_env);
+
+    // The kick incentive is 1% of the debt paid in collateral: 60/7000
+    assert_eq!(
+        test_env.amount_owned_by_current("btc"),
+        dec!(9999999) + dec!("0.008571428571428571")
+    );
+    assert_current_has_loan(&test_env, "#0#", "btc", dec!(0), dec!(0), dec!(0), 0);
+}
+
+#[test]
+fn test_start_auction_twice_fails() {
+    let (mut test_env, _) = instantiate();
+
+    take_loan_and_start_auction(&mut test_env);
+
+    test_env
+        .call_method(IssuerMethods::StartAuction("#0#".to_string()))
+        .should_panic(Error::AssertFailed(
+            "This loan is already being auctioned".to_string(),
+        ))
+        .run();
+}
+
+#[test]
+fn test_take_auction() {
+    let (mut test_env, _) = instantiate();
+
+    take_loan_and_start_auction(&mut test_env);
+
+    // After 10 minutes, the price decreased from 8400 to 7000
+    let new_time = Instant::new(600);
+    test_env.set_current_time(new_time);
+
+    test_env
+        .call_method(IssuerMethods::TakeAuction(dec!(6000), "#0#".to_string()))
+        .run();
+
+    // The collateral that was not sold is given back to the loan
+    assert_eq!(test_env.amount_owned_by_current(STABLECOIN_NAME), dec!(0));
+    assert_current_has_loan(
+        &test_env,
+        "#0#",
+        "btc",
+        dec!("0.134285714285714287"),
+        dec!(0),
+        dec!(0),
+        0,
+    );
+}
+
+#[test]
+fn test_take_auction_out_of_collateral_books_system_debt() {
+    let (mut test_env, _) = instantiate();
+
+    take_loan_and_start_auction(&mut test_env);
+
+    // After 20 minutes, the price is 5600 and the collateral left is worth 5552 SUSD
+    let new_time = Instant::new(1200);
+    test_env.set_current_time(new_time);
+
+    test_env
+        .call_method(IssuerMethods::TakeAuction(dec!(6000), "#0#".to_string()))
+        .run();
+
+    assert_eq!(
+        test_env.amount_owned_by_current(STABLECOIN_NAME),
+        dec!("447.9999999999999976")
+    );
+    assert_current_has_loan(&test_env, "#0#", "btc", dec!(0), dec!(0), dec!(0), 0);
+
+    // The principal that was not raised is auctioned as system debt
+    test_env.call_method(IssuerMethods::StartDebtAuction).run();
+}
+
+#[test]
+fn test_take_expired_auction_fails() {
+    let (mut test_env, _) = instantiate();
+
+    take_loan_and_start_auction(&mut test_env);
+
+    let new_time = Instant::new(3600);
+    test_env.set_current_time(new_time);
+
+    test_env
+        .call_method(IssuerMethods::TakeAuction(dec!(6000), "#0#".to_string()))
+        .should_panic(Error::AssertFailed(
+            "This auction has expired and should be reset".to_string(),
+        ))
+        .run();
+}
+
+#[test]
+fn test_reset_auction() {
+    let (mut test_env, _) = instantiate();
+
+    take_loan_and_start_auction(&mut test_env);
+
+    let new_time = Instant::new(3600);
+    test_env.set_current_time(new_time);
+
+    test_env
+        .call_method(IssuerMethods::ResetAuction("#0#".to_string()))
+        .run();
+    test_env
+        .call_method(IssuerMethods::TakeAuction(dec!(6000), "#0#".to_string()))
+        .run();
+}
+
+#[test]
+fn test_reset_running_auction_fails() {
+    let (mut test_env, _) = instantiate();
+
+    take_loan_and_start_auction(&mut test_env);
+
+    test_env
+        .call_method(IssuerMethods::ResetAuction("#0#".to_string()))
+        .should_panic(Error::AssertFailed(
+            "This auction has not expired yet".to_string(),
+        ))
+        .run();
+}
+
+#[test]
+fn test_repay_auctioned_loan_fails() {
+    let (mut test_env, _) = instantiate();
+
+    take_loan_and_start_auction(&mut test_env);
+
+    // The auctioned loan holds no debt but burning it would lock the auction
+    test_env
+        .call_method(IssuerMethods::RepayLoans(dec!(0), vec!["#0#".to_string()]))
+        .should_panic(Error::AssertFailed(
+            "This loan is being auctioned".to_string(),
+        ))
+        .run();
+}
+
+#[test]
+fn test_add_collateral_to_auctioned_loan_fails() {
+    let (mut test_env, _) = instantiate();
+
+    take_loan_and_start_auction(&mut test_env);
+
+    test_env
+        .call_method(IssuerMethods::AddCollateral(
+            "btc".to_string(),
+            dec!(1),
+            "#0#".to_string(),
+        ))
+        .should_panic(Error::AssertFailed(
+            "This loan is being auctioned".to_string(),
+        ))
+        .run();
+}
+
+#[test]
+fn test_deposit_in_stability_pool() {
+    let (mut test_env, _) = instantiate();
+
+    new_default_lender(&mut test_env, "btc");
+    set_oracle_price(&mut test_env, "btc", dec!(10000));
+
+    test_env
+        .call_method(IssuerMethods::TakeLoan(
+            "btc".to_string(),
+            dec!(1),
+            dec!(6000),
+        ))
+        .run();
+    test_env
+        .call_method(IssuerMethods::DepositInStabilityPool(dec!(5000)))
+        .run();
+
+    assert_eq!(
+        test_env.amount_owned_by_current(STABLECOIN_NAME),
+        dec!(1000)
+    );
+    assert_eq!(
+        test_env.amount_owned_by_current(STABILITY_DEPOSIT_NAME),
+        Decimal::ONE
+    );
+}
+
+#[test]
+fn test_withdraw_more_than_deposit_fails() {
+    let (mut test_env, _) = instantiate();
+
+    new_default_lender(&mut test_env, "btc");
+    set_oracle_price(&mut test_env, "btc", dec!(10000));
+
+    test_env
+        .call_method(IssuerMethods::TakeLoan(
+            "btc".to_string(),
+            dec!(1),
+            dec!(6000),
+        ))
+        .run();
+    test_env
+        .call_method(IssuerMethods::DepositInStabilityPool(dec!(5000)))
+        .run();
+    test_env
+        .call_method(IssuerMethods::WithdrawFromStabilityPool(
+            "#0#".to_string(),
+            dec!(6000),
+        ))
+        .should_panic(Error::AssertFailed(
+            "Cannot withdraw more than 5000 stablecoins".to_string(),
+        ))
+        .run();
+}
+
+#[test]
+fn test_liquidate_with_stability_pool() {
+    let (mut test_env, _) = instantiate();
+
+    new_default_lender(&mut test_env, "btc");
+    set_oracle_price(&mut test_env, "btc", dec!(10000));
+
+    test_env
+        .call_method(IssuerMethods::TakeLoan(
+            "btc".to_string(),
+            dec!(1),
+            dec!(6000),
+        ))
+        .run();
+    test_env
+        .call_method(IssuerMethods::TakeLoan(
+            "btc".to_string(),
+            dec!(10),
+            dec!(50000),
+        ))
+        .run();
+    test_env
+        .call_method(IssuerMethods::DepositInStabilityPool(dec!(50000)))
+        .run();
+
+    // The first loan is fully liquidated by the stability pool
+    set_oracle_price(&mut test_env, "btc", dec!(5900));
+    test_env
+        .call_method(IssuerMethods::LiquidateWithStabilityPool("#0#".to_string()))
+        .run();
+    assert_current_has_loan(&test_env, "#0#", "btc", dec!(0), dec!(0), dec!(0), 0);
+
+    // The deposit paid the 6000 SUSD of debt and gained the 1 BTC of collateral
+    test_env
+        .call_method(IssuerMethods::WithdrawFromStabilityPool(
+            "#0#".to_string(),
+            dec!(44000),
+        ))
+        .run();
+
+    assert_eq!(
+        test_env.amount_owned_by_current(STABLECOIN_NAME),
+        dec!(50000)
+    );
+    assert_eq!(test_env.amount_owned_by_current("btc"), dec!(9999990));
+}
+
+#[test]
+fn test_stability_pool_almost_emptied_keeps_working() {
+    let (mut test_env, _) = instantiate();
+
+    new_default_lender(&mut test_env, "btc");
+    set_oracle_price(&mut test_env, "btc", dec!(10000));
+
+    test_env
+        .call_method(IssuerMethods::TakeLoan(
+            "btc".to_string(),
+            dec!(1),
+            dec!(6000),
+        ))
+        .run();
+    test_env
+        .call_method(IssuerMethods::TakeLoan(
+            "btc".to_string(),
+            dec!(10),
+            dec!(50000),
+        ))
+        .run();
+    test_env
+        .call_method(IssuerMethods::DepositInStabilityPool(dec!(
+            "6000.000000000000000001"
+        )))
+        .run();
+
+    // The liquidation leaves a single unit of stablecoin in the pool, which would round the
+    // running product to zero without scaling it up
+    set_oracle_price(&mut test_env, "btc", dec!(5900));
+    test_env
+        .call_method(IssuerMethods::LiquidateWithStabilityPool("#0#".to_string()))
+        .run();
+
+    // New deposits can still be made and withdrawn
+    test_env
+        .call_method(IssuerMethods::DepositInStabilityPool(dec!(1000)))
+        .run();
+    test_env
+        .call_method(IssuerMethods::WithdrawFromStabilityPool(
+            "#1#".to_string(),
+            dec!(1000),
+        ))
+        .run();
+
+    assert_eq!(
+        test_env.amount_owned_by_current(STABLECOIN_NAME),
+        dec!("49999.999999999999999999")
+    );
+}
+
+#[test]
+fn test_liquidate_with_empty_stability_pool_fails() {
+    let (mut test_env, _) = instantiate();
+
+    new_default_lender(&mut test_env, "btc");
+    set_oracle_price(&mut test_env, "btc", dec!(10000));
+
+    test_env
+        .call_method(IssuerMethods::TakeLoan(
+            "btc".to_string(),
+            dec!(1),
+            dec!(6000),
+        ))
+        .run();
+
+    set_oracle_price(&mut test_env, "btc", dec!(5900));
+    test_env
+        .call_method(IssuerMethods::LiquidateWithStabilityPool("#0#".to_string()))
+        .should_panic(Error::AssertFailed(
+            "Please provide at least 6000 SUSD to liquidate this loan".to_string(),
+        ))
+        .run();
+}
+
+#[test]
+fn test_clear_bad_debt_and_debt_auction() {
+    let (mut test_env, _) = instantiate();
+
+    new_default_lender(&mut test_env, "btc");
+    set_oracle_price(&mut test_env, "btc", dec!(10000));
+
+    test_env
+        .call_method(IssuerMethods::TakeLoan(
+            "btc".to_string(),
+            dec!(1),
+            dec!(6000),
+        ))
+        .run();
+
+    // Neither the reserves nor the stability pool can cover the bad debt
+    set_oracle_price(&mut test_env, "btc", dec!(5000));
+    test_env
+        .call_method(IssuerMethods::ClearBadDebt("#0#".to_string()))
+        .run();
+    assert_current_has_loan(&test_env, "#0#", "btc", dec!(0), dec!(0), dec!(0), 0);
+
+    // Reserve claims start at a price of 1.2 SUSD
+    test_env.call_method(IssuerMethods::StartDebtAuction).run();
+    test_env
+        .call_method(IssuerMethods::TakeDebtAuction(dec!(1200)))
+        .run();
+
+    assert_eq!(
+        test_env.amount_owned_by_current(RESERVE_CLAIM_NAME),
+        dec!(1000)
+    );
+    assert_eq!(
+        test_env.amount_owned_by_current(STABLECOIN_NAME),
+        dec!(4800)
+    );
+}
+
+#[test]
+fn test_start_debt_auction_without_system_debt_fails() {
+    let (mut test_env, _) = instantiate();
+
+    test_env
+        .call_method(IssuerMethods::StartDebtAuction)
+        .should_panic(Error::AssertFailed(
+            "There is no system debt to auction".to_string(),
+        ))
+        .run();
+}
+
+#[test]
+fn test_debt_auction_with_custom_parameters() {
+    let (mut test_env, _) = instantiate();
+
+    new_default_lender(&mut test_env, "btc");
+    set_oracle_price(&mut test_env, "btc", dec!(10000));
+
+    test_env
+        .call_method(IssuerMethods::TakeLoan(
+            "btc".to_string(),
+            dec!(1),
+            dec!(6000),
+        ))
+        .run();
+
+    set_oracle_price(&mut test_env, "btc", dec!(5000));
+    test_env
+        .call_method(IssuerMethods::ClearBadDebt("#0#".to_string()))
+        .run();
+    test_env
+        .call_method(IssuerMethods::SetSystemAuctionParameters(dec!("1.5"), 7200))
+        .run();
+
+    // Reserve claims now start at a price of 1.5 SUSD
+    test_env.call_method(IssuerMethods::StartDebtAuction).run();
+    test_env
+        .call_method(IssuerMethods::TakeDebtAuction(dec!(1500)))
+        .run();
+
+    assert_eq!(
+        test_env.amount_owned_by_current(RESERVE_CLAIM_NAME),
+        dec!(1000)
+    );
+    assert_eq!(
+        test_env.amount_owned_by_current(STABLECOIN_NAME),
+        dec!(4500)
+    );
+}
+
+#[test]
+fn test_set_system_auction_start_premium_below_one_fails() {
+    let (mut test_env, _) = instantiate();
+
+    test_env
+        .call_method(IssuerMethods::SetSystemAuctionParameters(dec!("0.9"), 3600))
+        .should_panic(Error::AssertFailed(
+            "The auction start premium should be at least one".to_string(),
+        ))
+        .run();
+}
+
+#[test]
+fn test_surplus_auction() {
+    let (mut test_env, _) = instantiate();
+
+    new_default_lender(&mut test_env, "btc");
+    set_oracle_price(&mut test_env, "btc", dec!(10000));
+
+    test_env
+        .call_method(IssuerMethods::TakeLoan(
+            "btc".to_string(),
+            dec!(1),
+            dec!(6000),
+        ))
+        .run();
+    test_env
+        .call_method(IssuerMethods::TakeLoan(
+            "btc".to_string(),
+            dec!(10),
+            dec!(20000),
+        ))
+        .run();
+
+    set_oracle_price(&mut test_env, "btc", dec!(5000));
+    test_env
+        .call_method(IssuerMethods::ClearBadDebt("#0#".to_string()))
+        .run();
+    test_env.call_method(IssuerMethods::StartDebtAuction).run();
+    test_env
+        .call_method(IssuerMethods::TakeDebtAuction(dec!(1200)))
+        .run();
+
+    // The reserves first repay the 4800 SUSD of system debt left, the surplus above the buffer
+    // being 1000 SUSD
+    test_env
+        .call_method(IssuerMethods::SetSurplusBuffer(dec!(1000)))
+        .run();
+    test_env
+        .call_method(IssuerMethods::GiveTokens(vec![(
+            STABLECOIN_NAME.to_string(),
+            dec!(6800),
+        )]))
+        .run();
+    test_env
+        .call_method(IssuerMethods::StartSurplusAuction)
+        .run();
+
+    // The surplus is sold at 1 reserve claim per SUSD
+    test_env
+        .call_method(IssuerMethods::TakeSurplusAuction(dec!(500)))
+        .run();
+
+    assert_eq!(
+        test_env.amount_owned_by_current(RESERVE_CLAIM_NAME),
+        dec!(500)
+    );
+    assert_eq!(
+        test_env.amount_owned_by_current(STABLECOIN_NAME),
+        dec!(18500)
+    );
+}
+
+#[test]
+fn test_surplus_auction_below_buffer_fails() {
+    let (mut test_env, _) = instantiate();
+
+    new_default_lender(&mut test_env, "btc");
+    set_oracle_price(&mut test_env, "btc", dec!(10000));
+
+    test_env
+        .call_method(IssuerMethods::TakeLoan(
+            "btc".to_string(),
+            dec!(1),
+            dec!(6000),
+        ))
+        .run();
+    test_env
+        .call_method(IssuerMethods::SetSurplusBuffer(dec!(1000)))
+        .run();
+    test_env
+        .call_method(IssuerMethods::GiveTokens(vec![(
+            STABLECOIN_NAME.to_string(),
+            dec!(500),
+        )]))
+        .run();
+    test_env
+        .call_method(IssuerMethods::StartSurplusAuction)
+        .should_panic(Error::AssertFailed(
+            "There is no surplus to auction".to_string(),
+        ))
+        .run();
+}
+
+#[test]
+fn test_take_surplus_auction_is_capped_by_surplus_buffer() {
+    let (mut test_env, _) = instantiate();
+
+    new_default_lender(&mut test_env, "btc");
+    set_oracle_price(&mut test_env, "btc", dec!(10000));
+
+    test_env
+        .call_method(IssuerMethods::TakeLoan(
+            "btc".to_string(),
+            dec!(1),
+            dec!(6000),
+        ))
+        .run();
+    test_env
+        .call_method(IssuerMethods::TakeLoan(
+            "btc".to_string(),
+            dec!(10),
+            dec!(20000),
+        ))
+        .run();
+
+    set_oracle_price(&mut test_env, "btc", dec!(5000));
+    test_env
+        .call_method(IssuerMethods::ClearBadDebt("#0#".to_string()))
+        .run();
+    test_env.call_method(IssuerMethods::StartDebtAuction).run();
+    test_env
+        .call_method(IssuerMethods::TakeDebtAuction(dec!(1200)))
+        .run();
+
+    test_env
+        .call_method(IssuerMethods::SetSurplusBuffer(dec!(1000)))
+        .run();
+    test_env
+        .call_method(IssuerMethods::GiveTokens(vec![(
+            STABLECOIN_NAME.to_string(),
+            dec!(6800),
+        )]))
+        .run();
+    test_env
+        .call_method(IssuerMethods::StartSurplusAuction)
+        .run();
+
+    // Raising the buffer leaves only 500 SUSD of surplus for the running auction
+    test_env
+        .call_method(IssuerMethods::SetSurplusBuffer(dec!(1500)))
+        .run();
+    test_env
+        .call_method(IssuerMethods::TakeSurplusAuction(dec!(1000)))
+        .run();
+
+    assert_eq!(
+        test_env.amount_owned_by_current(RESERVE_CLAIM_NAME),
+        dec!(500)
+    );
+    assert_eq!(
+        test_env.amount_owned_by_current(STABLECOIN_NAME),
+        dec!(18500)
+    );
+
+    test_env
+        .call_method(IssuerMethods::TakeSurplusAuction(dec!(500)))
+        .should_panic(Error::AssertFailed(
+            "There is no surplus to auction".to_string(),
+        ))
+        .run();
+}
+
+#[test]
+fn test_take_surplus_auction_with_system_debt_fails() {
+    let (mut test_env, _) = instantiate();
+
+    new_default_lender(&mut test_env, "btc");
+    set_oracle_price(&mut test_env, "btc", dec!(10000));
+
+    test_env
+        .call_method(IssuerMethods::TakeLoan(
+            "btc".to_string(),
+            dec!(1),
+            dec!(6000),
+        ))
+        .run();
+    test_env
+        .call_method(IssuerMethods::TakeLoan(
+            "btc".to_string(),
+            dec!(10),
+            dec!(20000),
+        ))
+        .run();
+    test_env
+        .call_method(IssuerMethods::TakeLoan(
+            "btc".to_string(),
+            dec!(1),
+            dec!(6000),
+        ))
+        .run();
+
+    set_oracle_price(&mut test_env, "btc", dec!(5000));
+    test_env
+        .call_method(IssuerMethods::ClearBadDebt("#0#".to_string()))
+        .run();
+    test_env.call_method(IssuerMethods::StartDebtAuction).run();
+    test_env
+        .call_method(IssuerMethods::TakeDebtAuction(dec!(1200)))
+        .run();
+
+    test_env
+        .call_method(IssuerMethods::SetSurplusBuffer(dec!(1000)))
+        .run();
+    test_env
+        .call_method(IssuerMethods::GiveTokens(vec![(
+            STABLECOIN_NAME.to_string(),
+            dec!(6800),
+        )]))
+        .run();
+    test_env
+        .call_method(IssuerMethods::StartSurplusAuction)
+        .run();
+
+    // The reserves cannot cover this new bad debt, which becomes system debt
+    test_env
+        .call_method(IssuerMethods::ClearBadDebt("#2#".to_string()))
+        .run();
+
+    test_env
+        .call_method(IssuerMethods::TakeSurplusAuction(dec!(500)))
+        .should_panic(Error::AssertFailed(
+            "The system debt should be repaid before auctioning the surplus".to_string(),
+        ))
+        .run();
+    test_env
+        .call_method(IssuerMethods::StartDebtAuction)
+        .should_panic(Error::AssertFailed(
+            "The system debt cannot be auctioned while a surplus auction is running".to_string(),
+        ))
+        .run();
+}
+
+#[test]
+fn test_start_surplus_auction_during_debt_auction_fails() {
+    let (mut test_env, _) = instantiate();
+
+    new_default_lender(&mut test_env, "btc");
+    set_oracle_price(&mut test_env, "btc", dec!(10000));
+
+    test_env
+        .call_method(IssuerMethods::TakeLoan(
+            "btc".to_string(),
+            dec!(1),
+            dec!(6000),
+        ))
+        .run();
+
+    set_oracle_price(&mut test_env, "btc", dec!(5000));
+    test_env
+        .call_method(IssuerMethods::ClearBadDebt("#0#".to_string()))
+        .run();
+    test_env.call_method(IssuerMethods::StartDebtAuction).run();
+
+    test_env
+        .call_method(IssuerMethods::StartSurplusAuction)
+        .should_panic(Error::AssertFailed(
+            "The surplus cannot be auctioned while a debt auction is running".to_string(),
+        ))
+        .run();
+}
+
+#[test]
+fn test_flash_mint_above_maximum_fails() {
+    let (mut test_env, _) = instantiate();
+
+    test_env
+        .call_method(IssuerMethods::SetFlashMintParameters(
+            dec!("0.001"),
+            dec!(1000),
+        ))
+        .run();
+    test_env
+        .call_method(IssuerMethods::FlashMint(dec!(1001)))
+        .should_panic(Error::AssertFailed(
+            "Cannot flash mint more than 1000 stablecoins".to_string(),
+        ))
+        .run();
+}
+
+#[test]
+fn test_set_flash_mint_fee_not_smaller_than_one_fails() {
+    let (mut test_env, _) = instantiate();
+
+    test_env
+        .call_method(IssuerMethods::SetFlashMintParameters(dec!(1), dec!(1000)))
+        .should_panic(Error::AssertFailed(
+            "The flash mint fee should be such that 0<=FMF<1".to_string(),
+        ))
+        .run();
+}
+
+#[test]
+fn test_deposit_and_withdraw_savings() {
+    let (mut test_env, _) = instantiate();
+
+    new_default_lender(&mut test_env, "btc");
+    set_oracle_price(&mut test_env, "btc", dec!(20000));
+
+    test_env
+        .call_method(IssuerMethods::TakeLoan(
+            "btc".to_string(),
+            dec!(3),
+            dec!(42000),
+        ))
+        .run();
+
+    // Without savings rate, a share is worth one stablecoin
+    test_env
+        .call_method(IssuerMethods::DepositSavings(dec!(10000)))
+        .run();
+
+    assert_eq!(
+        test_env.amount_owned_by_current(SAVINGS_SHARE_NAME),
+        dec!(10000)
+    );
+    assert_eq!(
+        test_env.amount_owned_by_current(STABLECOIN_NAME),
+        dec!(32000)
+    );
+
+    test_env
+        .call_method(IssuerMethods::WithdrawSavings(dec!(4000)))
+        .run();
+
+    assert_eq!(
+        test_env.amount_owned_by_current(SAVINGS_SHARE_NAME),
+        dec!(6000)
+    );
+    assert_eq!(
+        test_env.amount_owned_by_current(STABLECOIN_NAME),
+        dec!(36000)
+    );
+}
+
+#[test]
+fn test_savings_earn_savings_rate() {
+    let (mut test_env, _) = instantiate();
+
+    new_default_lender(&mut test_env, "btc");
+    set_oracle_price(&mut test_env, "btc", dec!(20000));
+
+    test_env
+        .call_method(IssuerMethods::TakeLoan(
+            "btc".to_string(),
+            dec!(3),
+            dec!(42000),
+        ))
+        .run();
+    test_env
+        .call_method(IssuerMethods::SetSavingsRate(dec!("0.0001")))
+        .run();
+    test_env
+        .call_method(IssuerMethods::DepositSavings(dec!(10000)))
+        .run();
+
+    // Change time
+    let new_time = Instant::new(0).add_days(10).unwrap();
+    test_env.set_current_time(new_time);
+
+    test_env.call_method(IssuerMethods::DripSavings).run();
+    test_env.call_method(IssuerMethods::GetSavingsState).run();
+    test_env
+        .call_method(IssuerMethods::WithdrawSavings(dec!(10000)))
+        .run();
+
+    // The interests are minted as system debt because the reserves are empty
+    assert!(test_env.amount_owned_by_current(STABLECOIN_NAME) > dec!(42000));
+}
+
+#[test]
+fn test_set_savings_rate_not_smaller_than_one_fails() {
+    let (mut test_env, _) = instantiate();
+
+    test_env
+        .call_method(IssuerMethods::SetSavingsRate(dec!(1)))
+        .should_panic(Error::AssertFailed(
+            "The daily savings rate should be such that 0<=DSR<1".to_string(),
+        ))
+        .run();
+}
+
+#[test]
+fn test_redeem() {
+    let (mut test_env, _) = instantiate();
+
+    new_default_lender(&mut test_env, "btc");
+    set_oracle_price(&mut test_env, "btc", dec!(20000));
+
+    test_env
+        .call_method(IssuerMethods::TakeLoan(
+            "btc".to_string(),
+            dec!(3),
+            dec!(42000),
+        ))
+        .run();
+    test_env
+        .call_method(IssuerMethods::TakeLoan(
+            "btc".to_string(),
+            dec!(3),
+            dec!(30000),
+        ))
+        .run();
+
+    // The first loan has the lowest collateralization ratio so it is redeemed against first
+    test_env
+        .call_method(IssuerMethods::Redeem(dec!(20000), "btc".to_string()))
+        .run();
+
+    assert_eq!(
+        test_env.amount_owned_by_current(STABLECOIN_NAME),
+        dec!(52000)
+    );
+    // One bitcoin is taken from the loan and 0.5% of it is paid as fee to the reserves
+    assert_eq!(test_env.amount_owned_by_current("btc"), dec!("9999994.995"));
+    assert_current_has_loan(
+        &test_env,
+        "#0#",
+        "btc",
+        dec!(2),
+        dec!(22000),
+        dec!(22000),
+        0,
+    );
+    assert_current_has_loan(
+        &test_env,
+        "#1#",
+        "btc",
+        dec!(3),
+        dec!(30000),
+        dec!(30000),
+        0,
+    );
+}
+
+#[test]
+fn test_redeem_down_to_minimum_debt() {
+    let (mut test_env, _) = instantiate();
+
+    new_default_lender(&mut test_env, "btc");
+    set_oracle_price(&mut test_env, "btc", dec!(20000));
+
+    test_env
+        .call_method(IssuerMethods::SetMinimumDebt(
+            "btc".to_string(),
+            dec!(20000),
+        ))
+        .run();
+    test_env
+        .call_method(IssuerMethods::TakeLoan(
+            "btc".to_string(),
+            dec!(3),
+            dec!(42000),
+        ))
+        .run();
+    test_env
+        .call_method(IssuerMethods::TakeLoan(
+            "btc".to_string(),
+            dec!(3),
+            dec!(30000),
+        ))
+        .run();
+
+    // The first loan is only redeemed down to the minimum debt and the rest is redeemed against
+    // the second loan
+    test_env
+        .call_method(IssuerMethods::Redeem(dec!(30000), "btc".to_string()))
+        .run();
+
+    assert_eq!(
+        test_env.amount_owned_by_current(STABLECOIN_NAME),
+        dec!(42000)
+    );
+    assert_eq!(
+        test_env.amount_owned_by_current("btc"),
+        dec!("9999995.4925")
+    );
+    assert_current_has_loan(
+        &test_env,
+        "#0#",
+        "btc",
+        dec!("1.9"),
+        dec!(20000),
+        dec!(20000),
+        0,
+    );
+    assert_current_has_loan(
+        &test_env,
+        "#1#",
+        "btc",
+        dec!("2.6"),
+        dec!(22000),
+        dec!(22000),
+        0,
+    );
+}
+
+#[test]
+fn test_redeem_scans_at_most_twenty_loans() {
+    let (mut test_env, _) = instantiate();
+
+    new_default_lender(&mut test_env, "btc");
+    set_oracle_price(&mut test_env, "btc", dec!(20000));
+
+    for _ in 0..20 {
+        test_env
+            .call_method(IssuerMethods::TakeLoan(
+                "btc".to_string(),
+                dec!(1),
+                dec!(1000),
+            ))
+            .run();
+    }
+    test_env
+        .call_method(IssuerMethods::TakeLoan(
+            "btc".to_string(),
+            dec!(1),
+            dec!(10000),
+        ))
+        .run();
+
+    // The last loan is redeemed against first, then 19 of the others, so the last 1000 SUSD
+    // cannot be redeemed
+    test_env
+        .call_method(IssuerMethods::Redeem(dec!(30000), "btc".to_string()))
+        .run();
+
+    assert_eq!(
+        test_env.amount_owned_by_current(STABLECOIN_NAME),
+        dec!(1000)
+    );
+    assert_eq!(
+        test_env.amount_owned_by_current("btc"),
+        dec!("9999980.44275")
+    );
+    assert_current_has_loan(&test_env, "#20#", "btc", dec!("0.5"), dec!(0), dec!(0), 0);
+    assert_current_has_loan(&test_env, "#19#", "btc", dec!(1), dec!(1000), dec!(1000), 0);
+}
+
+#[test]
+fn test_redeem_against_underwater_loan_fails() {
+    let (mut test_env, _) = instantiate();
+
+    new_default_lender(&mut test_env, "btc");
+    set_oracle_price(&mut test_env, "btc", dec!(20000));
+
+    test_env
+        .call_method(IssuerMethods::TakeLoan(
+            "btc".to_string(),
+            dec!(3),
+            dec!(42000),
+        ))
+        .run();
+
+    // The collateral of the only loan is now worth less than its debt
+    set_oracle_price(&mut test_env, "btc", dec!(10000));
+
+    test_env
+        .call_method(IssuerMethods::Redeem(dec!(100), "btc".to_string()))
+        .should_panic(Error::AssertFailed(
+            "There are no loans to redeem stablecoins against".to_string(),
+        ))
+        .run();
+}
+
+#[test]
+fn test_set_redemption_fee_not_smaller_than_one_fails() {
+    let (mut test_env, _) = instantiate();
+
+    test_env
+        .call_method(IssuerMethods::SetRedemptionFee(dec!(1)))
+        .should_panic(Error::AssertFailed(
+            "The redemption fee should be such that 0<=RF<1".to_string(),
+        ))
+        .run();
+}
+
+#[test]
+fn test_flash_loan_more_than_reserves_fails() {
+    let (mut test_env, _) = instantiate();
+
+    test_env
+        .call_method(IssuerMethods::GiveTokens(vec![(
+            "btc".to_string(),
+            dec!(5),
+        )]))
+        .run();
+    test_env
+        .call_method(IssuerMethods::FlashLoan("btc".to_string(), dec!(10)))
+        .should_panic(Error::AssertFailed(
+            "The reserves do not hold enough tokens to lend 10".to_string(),
+        ))
+        .run();
+}
+
+#[test]
+fn test_flash_loan_and_repay() {
+    let (mut test_env, mut issuer_state) = instantiate();
+
+    test_env
+        .call_method(IssuerMethods::GiveTokens(vec![(
+            "btc".to_string(),
+            dec!(5),
+        )]))
+        .run();
+    flash_loan_and_repay(&mut test_env, "btc", dec!(5), dec!("0.0025")).run();
+
+    // The fee of 0.05% is added to the reserves
+    issuer_state.update();
+    let mut reserves = HashMap::new();
+    reserves.insert(test_env.get_resource("btc").clone(), dec!("5.0025"));
+    issuer_state.assert_state_is(&reserves, &HashMap::new(), 0, 0);
+
+    assert_eq!(
+        test_env.amount_owned_by_current("btc"),
+        dec!("9999994.9975")
+    );
+}
+
+#[test]
+fn test_flash_loan_not_repaid_fails() {
+    let (mut test_env, _) = instantiate();
+
+    test_env
+        .call_method(IssuerMethods::GiveTokens(vec![(
+            "btc".to_string(),
+            dec!(5),
+        )]))
+        .run();
+    flash_loan_and_repay(&mut test_env, "btc", dec!(5), dec!(0))
+        .should_panic(Error::AssertFailed(
+            "You did not provide enough tokens to repay the flash loan".to_string(),
+        ))
+        .run();
+}
+
+#[test]
+fn test_reserves_are_locked_during_flash_loan() {
+    let (mut test_env, _) = instantiate();
+
+    test_env
+        .call_method(IssuerMethods::GiveTokens(vec![(
+            STABLECOIN_NAME.to_string(),
+            dec!(500),
+        )]))
+        .run();
+    flash_loan_and_settle_system_debt(&mut test_env, STABLECOIN_NAME, dec!(500), dec!("0.25"))
+        .should_panic(Error::AssertFailed(
+            "The reserves are locked until their flash loan is repaid".to_string(),
+        ))
+        .run();
+}
+
+#[test]
+fn test_set_flash_loan_fee_not_smaller_than_one_fails() {
+    let (mut test_env, _) = instantiate();
+
+    test_env
+        .call_method(IssuerMethods::SetFlashLoanFee("btc".to_string(), dec!(1)))
+        .should_panic(Error::AssertFailed(
+            "The flash loan fee should be such that 0<=FLF<1".to_string(),
+        ))
+        .run();
+}
+
+#[test]
+fn test_get_loan_health() {
+    let (mut test_env, _) = instantiate();
+
+    new_default_lender(&mut test_env, "btc");
+    set_oracle_price(&mut test_env, "btc", dec!(20000));
+
+    test_env
+        .call_method(IssuerMethods::TakeLoan(
+            "btc".to_string(),
+            dec!(3),
+            dec!(30000),
+        ))
+        .run();
+
+    test_env
+        .call_method(IssuerMethods::GetLoanHealth("#0#".to_string()))
+        .run();
+}
+
+#[test]
+fn test_get_loan_history() {
+    let (mut test_env, _) = instantiate();
+
+    new_default_lender(&mut test_env, "btc");
+    set_oracle_price(&mut test_env, "btc", dec!(20000));
+
+    test_env
+        .call_method(IssuerMethods::TakeLoan(
+            "btc".to_string(),
+            dec!(3),
+            dec!(30000),
+        ))
+        .run();
+    test_env
+        .call_method(IssuerMethods::AddCollateral(
+            "btc".to_string(),
+            dec!(1),
+            "#0#".to_string(),
+        ))
+        .run();
+    test_env
+        .call_method(IssuerMethods::RepayPartial("#0#".to_string(), dec!(10000)))
+        .run();
+
+    test_env
+        .call_method(IssuerMethods::GetLoanHistory("#0#".to_string()))
+        .run();
+}
+
+#[test]
+fn test_get_history_of_unknown_loan_fails() {
+    let (mut test_env, _) = instantiate();
+
+    test_env
+        .call_method(IssuerMethods::GetLoanHistory("#0#".to_string()))
+        .should_panic(Error::AssertFailed(
+            "There is no history for this loan".to_string(),
+        ))
+        .run();
+}
+
+#[test]
+fn test_get_basket_loan_history() {
+    let (mut test_env, _) = instantiate();
+    take_default_basket_loan(&mut test_env);
+
+    test_env
+        .call_method(IssuerMethods::RepayBasketLoan(
+            dec!(14000),
+            "#0#".to_string(),
+        ))
+        .run();
+
+    test_env
+        .call_method(IssuerMethods::GetBasketLoanHistory("#0#".to_string()))
+        .run();
+}
+
+#[test]
+fn test_get_history_of_unknown_basket_loan_fails() {
+    let (mut test_env, _) = instantiate();
+
+    // Basket loans have their own history, which is not shared with the single loans
+    new_default_lender(&mut test_env, "btc");
+    set_oracle_price(&mut test_env, "btc", dec!(20000));
+    test_env
+        .call_method(IssuerMethods::TakeLoan(
+            "btc".to_string(),
+            dec!(3),
+            dec!(30000),
+        ))
+        .run();
+
+    test_env
+        .call_method(IssuerMethods::GetBasketLoanHistory("#0#".to_string()))
+        .should_panic(Error::AssertFailed(
+            "There is no history for this basket loan".to_string(),
+        ))
+        .run();
+}
+
+#[test]
+fn test_get_lender_totals_and_protocol_state() {
+    let (mut test_env, _) = instantiate();
+
+    new_default_lender(&mut test_env, "btc");
+    set_oracle_price(&mut test_env, "btc", dec!(20000));
+
+    test_env
+        .call_method(IssuerMethods::TakeLoan(
+            "btc".to_string(),
+            dec!(3),
+            dec!(30000),
+        ))
+        .run();
+    test_env
+        .call_method(IssuerMethods::RepayPartial("#0#".to_string(), dec!(5000)))
+        .run();
+
+    test_env
+        .call_method(IssuerMethods::GetLenderTotals("btc".to_string()))
+        .run();
+    test_env.call_method(IssuerMethods::GetProtocolState).run();
+}
+
+#[test]
+fn test_protocol_state_reports_psm_supply() {
+    let (mut test_env, _) = instantiate_with_psm();
+
+    new_default_lender(&mut test_env, "btc");
+    set_oracle_price(&mut test_env, "btc", dec!(20000));
+
+    test_env
+        .call_method(IssuerMethods::TakeLoan(
+            "btc".to_string(),
+            dec!(3),
+            dec!(30000),
+        ))
+        .run();
+
+    test_env.set_current_component("psm_comp");
+    test_env
+        .call_method(PsmMethods::MintStablecoins("usdc".to_string(), dec!(100)))
+        .run();
+    test_env.set_current_component("issuer_comp");
+
+    // The 100 SUSD minted by the PSM are not backed by loans and do not weigh on the solvency
+    assert_eq!(
+        get_protocol_state(&test_env),
+        (dec!(30000), dec!(100), Decimal::ZERO, dec!(2))
+    );
+}
+
+#[test]
+fn test_get_liquidatable_loans() {
+    let (mut test_env, _) = instantiate();
+
+    new_default_lender(&mut test_env, "btc");
+    set_oracle_price(&mut test_env, "btc", dec!(10000));
+
+    test_env
+        .call_method(IssuerMethods::TakeLoan(
+            "btc".to_string(),
+            dec!(1),
+            dec!(6000),
+        ))
+        .run();
+    test_env
+        .call_method(IssuerMethods::TakeLoan(
+            "btc".to_string(),
+            dec!(10),
+            dec!(10000),
+        ))
+        .run();
+
+    // Only the first loan can be liquidated at this price
+    set_oracle_price(&mut test_env, "btc", dec!(7000));
+
+    test_env
+        .call_method(IssuerMethods::GetLiquidatableLoans("btc".to_string()))
+        .run();
+}
+
+#[test]
+fn test_change_lender_parameter() {
+    let (mut test_env, mut issuer_state) = instantiate();
+
+    new_default_lender(&mut test_env, "btc");
+
+    // Change the parameters
+    test_env
+        .call_method(IssuerMethods::ChangeLenderParameters(
+            "btc".to_string(),
+            dec!("0.5"),
+            dec!("0.5"),
+            dec!("1.9"),
+            dec!("0.3"),
+            dec!("0.2"),
+            dec!("0.5"),
+        ))
+        .run();
+
+    issuer_state.update();
+    let mut lenders = HashMap::new();
+    let btc_lender = LenderState::from(
+        Decimal::ZERO,
+        dec!("0.5"),
+        dec!("0.5"),
+        dec!("1.9"),
+        dec!("0.3"),
+    );
+    lenders.insert(test_env.get_resource("btc").clone(), btc_lender);
+    issuer_state.assert_state_is(&HashMap::new(), &lenders, 0, 0);
+}
+
+#[test]
+fn test_change_lender_parameter_ltv_threshold_product_fails() {
+    let (mut test_env, _) = instantiate();
+
+    new_default_lender(&mut test_env, "btc");
+
+    test_env
+        .call_method(IssuerMethods::ChangeLenderParameters(
+            "btc".to_string(),
+            dec!("0.5"),
+            dec!("0.5"),
+            dec!(2),
+            dec!("0.3"),
+            dec!("0.2"),
+            dec!("0.5"),
+        ))
+        .should_panic(Error::AssertFailed(
+            "The LTV-liquidation threshold product should be smaller than one".to_string(),
+        ))
+        .run();
+}
+
+#[test]
+fn test_change_lender_parameter_close_factor_zero_fails() {
+    let (mut test_env, _) = instantiate();
+
+    new_default_lender(&mut test_env, "btc");
+
+    test_env
+        .call_method(IssuerMethods::ChangeLenderParameters(
+            "btc".to_string(),
+            dec!("0.7"),
+            dec!("0.0001"),
+            dec!("1.3"),
+            dec!("0.1"),
+            dec!("0.1"),
+            Decimal::ZERO,
+        ))
+        .should_panic(Error::AssertFailed(
+            "The close factor should be such that 0<CF<=1".to_string(),
+        ))
+        .run();
+}
+
+#[test]
+fn test_take_loan_below_minimum_debt_fails() {
+    let (mut test_env, _) = instantiate();
+
+    new_default_lender(&mut test_env, "btc");
+    set_oracle_price(&mut test_env, "btc", dec!(10000));
+
+    test_env
+        .call_method(IssuerMethods::SetMinimumDebt("btc".to_string(), dec!(100)))
+        .run();
+    test_env
+        .call_method(IssuerMethods::TakeLoan(
+            "btc".to_string(),
+            dec!(1),
+            dec!(50),
+        ))
+        .should_panic(Error::AssertFailed(
+            "The amount to loan should be at least 100".to_string(),
+        ))
+        .run();
+}
+
+#[test]
+fn test_repay_partial_leaving_dust_fails() {
+    let (mut test_env, _) = instantiate();
+
+    new_default_lender(&mut test_env, "btc");
+    set_oracle_price(&mut test_env, "btc", dec!(10000));
+
+    test_env
+        .call_method(IssuerMethods::SetMinimumDebt("btc".to_string(), dec!(100)))
+        .run();
+    test_env
+        .call_method(IssuerMethods::TakeLoan(
+            "btc".to_string(),
+            dec!(1),
+            dec!(6000),
+        ))
+        .run();
+    test_env
+        .call_method(IssuerMethods::RepayPartial("#0#".to_string(), dec!(5950)))
+        .should_panic(Error::AssertFailed(
+            "The loan should be fully repaid or keep a debt of at least 100".to_string(),
+        ))
+        .run();
+}
+
+#[test]
+fn test_remove_collateral_after_full_partial_repayment() {
+    let (mut test_env, _) = instantiate();
+
+    new_default_lender(&mut test_env, "btc");
+    set_oracle_price(&mut test_env, "btc", dec!(20000));
+
+    test_env
+        .call_method(IssuerMethods::TakeLoan(
+            "btc".to_string(),
+            dec!(3),
+            dec!(30000),
+        ))
+        .run();
+    test_env
+        .call_method(IssuerMethods::RepayPartial("#0#".to_string(), dec!(30000)))
+        .run();
+    test_env
+        .call_method(IssuerMethods::RemoveCollateral(dec!(3), "#0#".to_string()))
+        .run();
+
+    assert_eq!(test_env.amount_owned_by_current(STABLECOIN_NAME), dec!(0));
+    assert_eq!(test_env.amount_owned_by_current("btc"), dec!(10000000));
+    assert_current_has_loan(&test_env, "#0#", "btc", dec!(0), dec!(0), dec!(0), 0);
+}
+
+#[test]
+fn test_repay_partial_zero_fails() {
+    let (mut test_env, _) = instantiate();
+
+    new_default_lender(&mut test_env, "btc");
+    set_oracle_price(&mut test_env, "btc", dec!(20000));
+
+    test_env
+        .call_method(IssuerMethods::TakeLoan(
+            "btc".to_string(),
+            dec!(3),
+            dec!(30000),
+        ))
+        .run();
+    test_env
+        .call_method(IssuerMethods::RepayPartial("#0#".to_string(), dec!(0)))
+        .should_panic(Error::AssertFailed(
+            "The amount to repay should be positive".to_string(),
+        ))
+        .run();
+}
+
+#[test]
+fn test_repay_partial_fully_repaid_loan_fails() {
+    let (mut test_env, _) = instantiate();
+
+    new_default_lender(&mut test_env, "btc");
+    set_oracle_price(&mut test_env, "btc", dec!(20000));
+
+    test_env
+        .call_method(IssuerMethods::TakeLoan(
+            "btc".to_string(),
+            dec!(3),
+            dec!(30000),
+        ))
+        .run();
+    test_env
+        .call_method(IssuerMethods::RepayPartial("#0#".to_string(), dec!(30000)))
+        .run();
+    test_env
+        .call_method(IssuerMethods::TakeLoan(
+            "btc".to_string(),
+            dec!(3),
+            dec!(30000),
+        ))
+        .run();
+    test_env
+        .call_method(IssuerMethods::RepayPartial("#0#".to_string(), dec!(100)))
+        .should_panic(Error::AssertFailed(
+            "This loan is already fully repaid".to_string(),
+        ))
+        .run();
+}
+
+#[test]
+fn test_debt_ceiling_in_lender_state() {
+    let (mut test_env, mut issuer_state) = instantiate();
+
+    new_default_lender(&mut test_env, "btc");
+    set_oracle_price(&mut test_env, "btc", dec!(10000));
+
+    test_env
+        .call_method(IssuerMethods::SetDebtCeiling(
+            "btc".to_string(),
+            dec!(10000),
+        ))
+        .run();
+    test_env
+        .call_method(IssuerMethods::TakeLoan(
+            "btc".to_string(),
+            dec!(1),
+            dec!(6000),
+        ))
+        .run();
+
+    issuer_state.update();
+    let mut lenders = HashMap::new();
+    let btc_lender = LenderState::from(
+        Decimal::ONE,
+        dec!("0.7"),
+        dec!("0.0001"),
+        dec!("1.3"),
+        dec!("0.1"),
+    )
+    .with_debt(dec!(6000), dec!(10000));
+    lenders.insert(test_env.get_resource("btc").clone(), btc_lender);
+    issuer_state.assert_state_is(&HashMap::new(), &lenders, 1, 0);
+}
+
+#[test]
+fn test_take_loan_above_debt_ceiling_fails() {
+    let (mut test_env, _) = instantiate();
+
+    new_default_lender(&mut test_env, "btc");
+    set_oracle_price(&mut test_env, "btc", dec!(10000));
+
+    test_env
+        .call_method(IssuerMethods::SetDebtCeiling("btc".to_string(), dec!(5000)))
+        .run();
+    test_env
+        .call_method(IssuerMethods::TakeLoan(
+            "btc".to_string(),
+            dec!(1),
+            dec!(6000),
+        ))
+        .should_panic(Error::AssertFailed(
+            "Cannot lend 6000 because the debt ceiling of this collateral would be exceeded"
+                .to_string(),
+        ))
+        .run();
+}
+
+#[test]
+fn test_borrow_more_above_global_debt_ceiling_fails() {
+    let (mut test_env, _) = instantiate();
+
+    new_default_lender(&mut test_env, "btc");
+    set_oracle_price(&mut test_env, "btc", dec!(10000));
+
+    test_env
+        .call_method(IssuerMethods::SetGlobalDebtCeiling(dec!(6500)))
+        .run();
+    test_env
+        .call_method(IssuerMethods::TakeLoan(
+            "btc".to_string(),
+            dec!(1),
+            dec!(6000),
+        ))
+        .run();
+    test_env
+        .call_method(IssuerMethods::BorrowMore("#0#".to_string(), dec!(1000)))
+        .should_panic(Error::AssertFailed(
+            "Cannot lend 1000 because the global debt ceiling would be exceeded".to_string(),
+        ))
+        .run();
+}
+
+/// Creates btc and eth lenders and takes a basket loan of 14000 against 1 btc at 20000 and 10
+/// eth at 1000
+fn take_default_basket_loan(test_env: &mut TestEnvironment) {
+    test_env.create_fixed_supply_token("eth", dec!(10000000));
+    new_default_lender(test_env, "btc");
+    new_default_lender(test_env, "eth");
+    set_oracle_price(test_env, "btc", dec!(20000));
+    set_oracle_price(test_env, "eth", dec!(1000));
+
+    test_env
+        .call_method(IssuerMethods::TakeBasketLoan(
+            vec![("btc".to_string(), dec!(1)), ("eth".to_string(), dec!(10))],
+            dec!(14000),
+        ))
+        .run();
+}
+
+#[test]
+fn test_take_basket_loan() {
+    let (mut test_env, mut issuer_state) = instantiate();
+    take_default_basket_loan(&mut test_env);
+
+    issuer_state.update();
+    let mut lenders = HashMap::new();
+    let btc_lender = LenderState::from(
+        dec!(1),
+        dec!("0.7"),
+        dec!("0.0001"),
+        dec!("1.3"),
+        dec!("0.1"),
+    );
+    let eth_lender = LenderState::from(
+        dec!(10),
+        dec!("0.7"),
+        dec!("0.0001"),
+        dec!("1.3"),
+        dec!("0.1"),
+    );
+    lenders.insert(test_env.get_resource("btc").clone(), btc_lender);
+    lenders.insert(test_env.get_resource("eth").clone(), eth_lender);
+    issuer_state.assert_state_is(&HashMap::new(), &lenders, 0, 0);
+
+    assert_eq!(
+        test_env.amount_owned_by_current(STABLECOIN_NAME),
+        dec!(14000)
+    );
+}
+
+#[test]
+fn test_take_basket_loan_below_minimum_debt_fails() {
+    let (mut test_env, _) = instantiate();
+    test_env.create_fixed_supply_token("eth", dec!(10000000));
+    new_default_lender(&mut test_env, "btc");
+    new_default_lender(&mut test_env, "eth");
+    set_oracle_price(&mut test_env, "btc", dec!(20000));
+    set_oracle_price(&mut test_env, "eth", dec!(1000));
+
+    test_env
+        .call_method(IssuerMethods::SetMinimumDebt("eth".to_string(), dec!(5000)))
+        .run();
+
+    // Only 14000 * 7000 / 21000 = 4666.67 stablecoins are lent against eth
+    test_env
+        .call_method(IssuerMethods::TakeBasketLoan(
+            vec![("btc".to_string(), dec!(1)), ("eth".to_string(), dec!(10))],
+            dec!(14000),
+        ))
+        .should_panic(Error::AssertFailed(
+            "The amount to loan should be at least 5000".to_string(),
+        ))
+        .run();
+}
+
+#[test]
+fn test_take_basket_loan_above_weighted_ltv_fails() {
+    let (mut test_env, _) = instantiate();
+    test_env.create_fixed_supply_token("eth", dec!(10000000));
+    new_default_lender(&mut test_env, "btc");
+    new_default_lender(&mut test_env, "eth");
+    set_oracle_price(&mut test_env, "btc", dec!(20000));
+    set_oracle_price(&mut test_env, "eth", dec!(1000));
+
+    // The collateral is worth 30000 with an LTV of 0.7 so at most 21000 can be borrowed
+    test_env
+        .call_method(IssuerMethods::TakeBasketLoan(
+            vec![("btc".to_string(), dec!(1)), ("eth".to_string(), dec!(10))],
+            dec!(21001),
+        ))
+        .should_panic(Error::AssertFailed(
+            "You need to provide more collateral to loan 21001".to_string(),
+        ))
+        .run();
+}
+
+#[test]
+fn test_repay_basket_loan() {
+    let (mut test_env, mut issuer_state) = instantiate();
+    take_default_basket_loan(&mut test_env);
+
+    test_env
+        .call_method(IssuerMethods::RepayBasketLoan(
+            dec!(14000),
+            "#0#".to_string(),
         ))
         .run();
 
@@ -1000,11 +3157,299 @@ fn test_change_lender_parameter() {
     let mut lenders = HashMap::new();
     let btc_lender = LenderState::from(
         Decimal::ZERO,
-        dec!("0.5"),
-        dec!("0.5"),
-        dec!(2),
-        dec!("0.3"),
+        dec!("0.7"),
+        dec!("0.0001"),
+        dec!("1.3"),
+        dec!("0.1"),
+    );
+    let eth_lender = LenderState::from(
+        Decimal::ZERO,
+        dec!("0.7"),
+        dec!("0.0001"),
+        dec!("1.3"),
+        dec!("0.1"),
+    );
+    lenders.insert(test_env.get_resource("btc").clone(), btc_lender);
+    lenders.insert(test_env.get_resource("eth").clone(), eth_lender);
+    issuer_state.assert_state_is(&HashMap::new(), &lenders, 0, 0);
+
+    assert_eq!(
+        test_env.amount_owned_by_current(STABLECOIN_NAME),
+        Decimal::ZERO
+    );
+}
+
+#[test]
+fn test_liquidate_basket_loan_seizes_by_priority() {
+    let (mut test_env, mut issuer_state) = instantiate();
+    take_default_basket_loan(&mut test_env);
+
+    // The collateral is now worth 15000 for a debt of 14000
+    set_oracle_price(&mut test_env, "btc", dec!(5000));
+
+    // btc comes first in the priority order, so 1100 * 1.1 / 5000 = 0.242 btc are seized
+    test_env
+        .call_method(IssuerMethods::LiquidateBasketLoan(
+            dec!(1100),
+            "#0#".to_string(),
+        ))
+        .run();
+
+    issuer_state.update();
+    let mut lenders = HashMap::new();
+    let btc_lender = LenderState::from(
+        dec!("0.758"),
+        dec!("0.7"),
+        dec!("0.0001"),
+        dec!("1.3"),
+        dec!("0.1"),
+    );
+    let eth_lender = LenderState::from(
+        dec!(10),
+        dec!("0.7"),
+        dec!("0.0001"),
+        dec!("1.3"),
+        dec!("0.1"),
     );
     lenders.insert(test_env.get_resource("btc").clone(), btc_lender);
+    lenders.insert(test_env.get_resource("eth").clone(), eth_lender);
     issuer_state.assert_state_is(&HashMap::new(), &lenders, 0, 0);
+
+    assert_eq!(
+        test_env.amount_owned_by_current(STABLECOIN_NAME),
+        dec!("12900.000000000000000001")
+    );
+}
+
+#[test]
+fn test_liquidate_healthy_basket_loan_fails() {
+    let (mut test_env, _) = instantiate();
+    take_default_basket_loan(&mut test_env);
+
+    set_oracle_price(&mut test_env, "btc", dec!(12000));
+
+    test_env
+        .call_method(IssuerMethods::LiquidateBasketLoan(
+            dec!(1000),
+            "#0#".to_string(),
+        ))
+        .should_panic(Error::AssertFailed(
+            "Cannot liquidate this loan: the collateralization ratio is 1.571428571428571428 >= 1.3"
+                .to_string(),
+        ))
+        .run();
+}
+
+#[test]
+fn test_basket_liquidation_is_limited_by_close_factor() {
+    let (mut test_env, _) = instantiate();
+    take_default_basket_loan(&mut test_env);
+
+    // The smallest close factor of the lenders of the loan applies to the whole debt
+    test_env
+        .call_method(IssuerMethods::ChangeLenderParameters(
+            "eth".to_string(),
+            dec!("0.7"),
+            dec!("0.0001"),
+            dec!("1.3"),
+            dec!("0.1"),
+            dec!("0.1"),
+            dec!("0.05"),
+        ))
+        .run();
+    set_oracle_price(&mut test_env, "btc", dec!(5000));
+
+    // Only 5% of the 14000 SUSD of debt are repaid
+    test_env
+        .call_method(IssuerMethods::LiquidateBasketLoan(
+            dec!(1100),
+            "#0#".to_string(),
+        ))
+        .run();
+
+    assert_eq!(
+        test_env.amount_owned_by_current(STABLECOIN_NAME),
+        dec!("13300.000000000000000001")
+    );
+}
+
+#[test]
+fn test_basket_liquidation_leaving_dust_requires_whole_debt() {
+    let (mut test_env, _) = instantiate();
+    take_default_basket_loan(&mut test_env);
+
+    // Repaying 1100 SUSD would leave 4300 SUSD lent against eth, below its minimum debt
+    test_env
+        .call_method(IssuerMethods::SetMinimumDebt("eth".to_string(), dec!(4500)))
+        .run();
+    set_oracle_price(&mut test_env, "btc", dec!(5000));
+
+    test_env
+        .call_method(IssuerMethods::LiquidateBasketLoan(
+            dec!(1100),
+            "#0#".to_string(),
+        ))
+        .should_panic(Error::AssertFailed(
+            "Please provide at least 14000 SUSD to liquidate this loan".to_string(),
+        ))
+        .run();
+
+    test_env
+        .call_method(IssuerMethods::LiquidateBasketLoan(
+            dec!(14000),
+            "#0#".to_string(),
+        ))
+        .run();
+
+    assert_eq!(
+        test_env.amount_owned_by_current(STABLECOIN_NAME),
+        Decimal::ZERO
+    );
+}
+
+#[test]
+fn test_liquidate_undercollateralized_basket_loan_repays_principal() {
+    let (mut test_env, _) = instantiate();
+    take_default_basket_loan(&mut test_env);
+
+    // The collateral is worth 14070 SUSD, more than the principal but less than the debt once
+    // interests accrued
+    set_oracle_price(&mut test_env, "btc", dec!(4070));
+    let new_time = Instant::new(0).add_days(100).unwrap();
+    test_env.set_current_time(new_time);
+
+    test_env
+        .call_method(IssuerMethods::LiquidateBasketLoan(
+            dec!(14000),
+            "#0#".to_string(),
+        ))
+        .run();
+
+    assert_eq!(
+        test_env.amount_owned_by_current(STABLECOIN_NAME),
+        Decimal::ZERO
+    );
+}
+
+#[test]
+fn test_liquidate_basket_loan_with_bad_debt_fails() {
+    let (mut test_env, _) = instantiate();
+    take_default_basket_loan(&mut test_env);
+
+    set_oracle_price(&mut test_env, "btc", dec!(3000));
+
+    test_env
+        .call_method(IssuerMethods::LiquidateBasketLoan(
+            dec!(14000),
+            "#0#".to_string(),
+        ))
+        .should_panic(Error::AssertFailed(
+            "This loan has bad debt that should be cleared".to_string(),
+        ))
+        .run();
+}
+
+#[test]
+fn test_clear_basket_bad_debt() {
+    let (mut test_env, _) = instantiate();
+    take_default_basket_loan(&mut test_env);
+
+    set_oracle_price(&mut test_env, "btc", dec!(3000));
+
+    // Neither the reserves nor the stability pool can cover the 14000 SUSD of principal, which
+    // become system debt
+    test_env
+        .call_method(IssuerMethods::ClearBasketBadDebt("#0#".to_string()))
+        .run();
+
+    test_env.call_method(IssuerMethods::StartDebtAuction).run();
+    test_env
+        .call_method(IssuerMethods::TakeDebtAuction(dec!(1200)))
+        .run();
+
+    assert_eq!(
+        test_env.amount_owned_by_current(RESERVE_CLAIM_NAME),
+        dec!(1000)
+    );
+    assert_eq!(
+        test_env.amount_owned_by_current(STABLECOIN_NAME),
+        dec!(12800)
+    );
+}
+
+#[test]
+fn test_clear_basket_bad_debt_with_stability_pool() {
+    let (mut test_env, _) = instantiate();
+    take_default_basket_loan(&mut test_env);
+
+    test_env
+        .call_method(IssuerMethods::DepositInStabilityPool(dec!(14000)))
+        .run();
+    set_oracle_price(&mut test_env, "btc", dec!(3000));
+
+    test_env
+        .call_method(IssuerMethods::ClearBasketBadDebt("#0#".to_string()))
+        .run();
+
+    // The pool absorbed the whole debt, so there is no system debt to auction
+    test_env
+        .call_method(IssuerMethods::StartDebtAuction)
+        .should_panic(Error::AssertFailed(
+            "There is no system debt to auction".to_string(),
+        ))
+        .run();
+}
+
+#[test]
+fn test_clear_basket_bad_debt_without_bad_debt_fails() {
+    let (mut test_env, _) = instantiate();
+    take_default_basket_loan(&mut test_env);
+
+    set_oracle_price(&mut test_env, "btc", dec!(5000));
+
+    test_env
+        .call_method(IssuerMethods::ClearBasketBadDebt("#0#".to_string()))
+        .should_panic(Error::AssertFailed(
+            "There is no bad debt to clear!".to_string(),
+        ))
+        .run();
+}
+
+#[test]
+fn test_get_basket_loan_health() {
+    let (mut test_env, _) = instantiate();
+    take_default_basket_loan(&mut test_env);
+
+    test_env
+        .call_method(IssuerMethods::GetBasketLoanHealth("#0#".to_string()))
+        .run();
+}
+
+#[test]
+fn test_get_liquidatable_basket_loans() {
+    let (mut test_env, _) = instantiate();
+    take_default_basket_loan(&mut test_env);
+
+    set_oracle_price(&mut test_env, "btc", dec!(5000));
+
+    test_env
+        .call_method(IssuerMethods::GetLiquidatableBasketLoans)
+        .run();
+}
+
+#[test]
+fn test_set_collateral_priority_missing_token_fails() {
+    let (mut test_env, _) = instantiate();
+    test_env.create_fixed_supply_token("eth", dec!(10000000));
+    new_default_lender(&mut test_env, "btc");
+    new_default_lender(&mut test_env, "eth");
+
+    test_env
+        .call_method(IssuerMethods::SetCollateralPriority(
+            vec!["eth".to_string()],
+        ))
+        .should_panic(Error::AssertFailed(
+            "The priority should contain every collateral token exactly once".to_string(),
+        ))
+        .run();
 }
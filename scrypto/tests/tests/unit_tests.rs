@@ -1,3 +1,5 @@
 mod dao;
 mod dex;
+mod minter_registry;
+mod price_feed;
 mod stablecoin;